@@ -8,18 +8,19 @@ use crate::utils::*;
 use plasma::*;
 use std::{cmp::{max, min},
           rc::Rc,
-          sync::Arc};
+          sync::Arc,
+          time::Instant};
 
 use yastl::Pool;
 use sdl2::{event::{Event, WindowEvent},
-           keyboard::Keycode,
+           keyboard::{Keycode, Mod},
            pixels::PixelFormatEnum,
            rect::Rect,
            video::{FullscreenType, Window, WindowContext}};
 use sdl2_sys::SDL_WindowFlags;
 
-type PlasmaMixerT = PlasmaMixer;
-type PlasmaICPT<'a> = PlasmaICP<'a>;
+/// The format of the streaming texture.
+const PIXEL_FORMAT: PixelFormat = PixelFormat::Rgb24;
 
 const PLASMA_WIDTH: u32 = 512;
 const PLASMA_HEIGHT: u32 = 512;
@@ -27,6 +28,10 @@ const TARGET_WIDTH: u32 = 1024;
 const TARGET_HEIGHT: u32 = 1024;
 const MIN_STEPS: f32 = 80.0;
 const MAX_STEPS: f32 = 200.0;
+const STEPS_PER_SECOND: f32 = 60.0;
+const MAX_STEPS_PER_FRAME: f32 = 4.0;
+/// The duration of the transition to the new color adjustments.
+const ADJUST_TRANSITION_SECONDS: f32 = 0.25;
 
 #[derive(Debug, PartialEq)]
 enum AppMode {
@@ -53,7 +58,7 @@ macro_rules! program_name {
     () => (concat!(env!("CARGO_PKG_NAME"), " (64-bit)"));
 }
 
-static ABOUT_INFO: &'static str = concat!(program_name!(),
+static ABOUT_INFO: &str = concat!(program_name!(),
                                           " v",
                                           env!("CARGO_PKG_VERSION"),
                                           " Copyright © 2018-2024 ",
@@ -65,21 +70,95 @@ static ABOUT_INFO: &'static str = concat!(program_name!(),
                                           ".\nTarget CPU features: ",
                                           target_features!(),
                                           ".\n\nThis program comes with ABSOLUTELY NO WARRANTY.\n\n[ESC] to quit.\n[F1] \
-                                           for this message.\nDouble click to toggle fullscreen.");
+                                           for this message.\n[M] to switch the mixer.\n[B] [C] [S] [H] [T] to \
+                                           increase the brightness, contrast, saturation, hue and temperature, with \
+                                           [Shift] to decrease.\n[Backspace] to reset the colors.\nDouble click to \
+                                           toggle fullscreen.");
+
+/// Removes the `<name> <value>` or `<name>=<value>` option from `args` and returns its value.
+fn take_arg(args: &mut Vec<String>, name: &str) -> Result<Option<String>, String> {
+    let prefix = format!("{}=", name);
+    let pos = match args.iter().position(|s| s == name || s.starts_with(&prefix)) {
+        Some(pos) => pos,
+        None => return Ok(None),
+    };
+    let arg = args.remove(pos);
+    match arg.strip_prefix(&prefix) {
+        Some(value) => Ok(Some(value.to_string())),
+        None if pos < args.len() => Ok(Some(args.remove(pos))),
+        None => Err(format!("No value for {}", name)),
+    }
+}
+
+/// Removes the `--seed <number>` option from `args` and returns its value.
+fn take_seed_arg(args: &mut Vec<String>) -> Result<Option<u64>, String> {
+    take_arg(args, "--seed")?.map(|value| value.parse().map_err(|_| format!("Invalid seed: {}", value)))
+                             .transpose()
+}
+
+/// Removes the `--mixer <name>` option from `args` and returns its value.
+fn take_mixer_arg(args: &mut Vec<String>) -> Result<Option<MixerKind>, String> {
+    take_arg(args, "--mixer")?.map(|value| value.parse().map_err(|_| format!("Unknown mixer: {}", value)))
+                              .transpose()
+}
+
+/// Removes the `--expr <expression>` option from `args` and returns the parsed mixer.
+#[cfg(feature = "expr")]
+fn take_expr_arg(args: &mut Vec<String>) -> Result<Option<ExprMixer>, String> {
+    take_arg(args, "--expr")?.map(|value| value.parse().map_err(|err| format!("Invalid expression: {}", err)))
+                             .transpose()
+}
+
+/// Removes the `--loop <steps>` option from `args` and returns its value.
+fn take_loop_arg(args: &mut Vec<String>) -> Result<Option<f32>, String> {
+    take_arg(args, "--loop")?.map(|value| match value.parse() {
+                                  Ok(steps) if steps > 0.0 && f32::is_finite(steps) => Ok(steps),
+                                  _ => Err(format!("Invalid loop length: {}", value)),
+                              })
+                             .transpose()
+}
+
+/// Returns the color adjustments changed by the key or `None` if the key doesn't change them.
+fn adjust_by_key(adjust: ColorAdjust, keycode: Keycode, keymod: Mod) -> Option<ColorAdjust> {
+    let sign = if keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD) { -1.0 } else { 1.0 };
+    let step = |value: f32, delta: f32, min: f32, max: f32| (value + sign * delta).clamp(min, max);
+    Some(match keycode {
+        Keycode::B => ColorAdjust { brightness: step(adjust.brightness, 0.05, -1.0, 1.0), ..adjust },
+        Keycode::C => ColorAdjust { contrast: step(adjust.contrast, 0.1, 0.0, 3.0), ..adjust },
+        Keycode::S => ColorAdjust { saturation: step(adjust.saturation, 0.1, 0.0, 3.0), ..adjust },
+        Keycode::H => ColorAdjust { hue: (adjust.hue + sign / 12.0).rem_euclid(2.0), ..adjust },
+        Keycode::T => ColorAdjust { temperature: step(adjust.temperature, 0.1, -1.0, 1.0), ..adjust },
+        Keycode::Backspace => ColorAdjust::IDENTITY,
+        _ => return None,
+    })
+}
 
 fn run() -> Result<(), String> {
     let app_mode: AppMode;
 
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let seed = take_seed_arg(&mut args)?.unwrap_or_else(rand::random);
+    let loop_steps = take_loop_arg(&mut args)?;
+    let mut mixer = take_mixer_arg(&mut args)?.unwrap_or_default();
+    #[cfg(feature = "expr")]
+    let mut expr = match take_expr_arg(&mut args)? {
+        Some(expr) => {
+            mixer = MixerKind::Expr;
+            expr
+        },
+        None => ExprMixer::default(),
+    };
+
     if cfg!(target_os = "windows") {
-        match std::env::args().nth(1).as_mut().map(|s| {
-                                                  s.make_ascii_lowercase();
-                                                  s.as_str()
-                                              }) {
+        match args.first().cloned().as_mut().map(|s| {
+                                               s.make_ascii_lowercase();
+                                               s.as_str()
+                                           }) {
             Some("/s") | Some("-s") => app_mode = AppMode::Screensaver,
             Some("/c") => app_mode = AppMode::ScreensaverConfig,
             Some("/w") | Some("-w") => app_mode = AppMode::Wallpaper,
             Some("/p") => {
-                let handle = std::env::args().nth(2).ok_or_else(|| "No window handle for preview")?;
+                let handle = args.get(1).cloned().ok_or("No window handle for preview")?;
                 app_mode = AppMode::ScreensaverPreview(handle);
             },
             Some(s) if s.len() > 3 && s.starts_with("/p:") => {
@@ -188,16 +267,31 @@ fn run() -> Result<(), String> {
     let mut texture =
         texture_creator.create_texture_streaming(PixelFormatEnum::RGB24, plasma_width, plasma_height).map_err(err_str)?;
 
-    let mut rng = rand::thread_rng();
     let cfg = PhaseAmpCfg::new(MIN_STEPS, MAX_STEPS);
-    let mut plasma = Arc::new(Plasma::new(plasma_width, plasma_height, cfg, &mut rng));
-    // let mixer = PlasmaMixerT::new();
+    let (plasma, mut rng) = Plasma::new_seeded(plasma_width, plasma_height, cfg, seed);
+    let mut plasma = match loop_steps {
+        Some(loop_steps) => Plasma::new_looped(plasma_width, plasma_height, cfg, seed, loop_steps),
+        None => plasma,
+    };
+    plasma.set_phase_amps_len(mixer.phase_amps_len(), &mut rng);
+    if plasma.timeline_seed().is_some() {
+        plasma.at_time(0.0);
+    }
+    let mut plasma = Arc::new(plasma);
 
-    let thread_count = min(4, max(2, sdl2::cpuinfo::cpu_count())) as usize;
+    let thread_count = sdl2::cpuinfo::cpu_count().clamp(2, 4) as usize;
     let pool = Pool::new(thread_count);
     let mut workspaces: Vec<Vec<u8>> = std::iter::repeat_with(Vec::new).take(thread_count).collect();
 
     let mut app_state = AppState::Active;
+    let mut last_update = Instant::now();
+    #[cfg(feature = "expr")]
+    let start = last_update;
+    let mut time = 0.0f64;
+    let mut adjust = ColorAdjust::IDENTITY;
+    let mut adjust_from = adjust;
+    let mut adjust_to = adjust;
+    let mut adjust_progress = 1.0f32;
 
     let mut event_pump = sdl_context.event_pump()?;
 
@@ -226,11 +320,45 @@ fn run() -> Result<(), String> {
                             canvas.window_mut().set_fullscreen(ft)?;
                         },
                         Event::KeyDown { keycode: Some(Keycode::F1), .. } => {
-                            info(format!("{}\n\nSDL: {} {}", ABOUT_INFO, sdl2::version::version(), sdl2::version::revision()).into());
+                            let loop_info = match loop_steps {
+                                Some(steps) => format!("\nLoop: {} steps", steps),
+                                None => String::new(),
+                            };
+                            let ColorAdjust { brightness, contrast, saturation, hue, temperature } = adjust_to;
+                            let adjust_info = format!("\nBrightness: {:+.2} Contrast: {:.1} Saturation: {:.1} \
+                                                       Hue: {:.0}° Temperature: {:+.1}",
+                                                      brightness,
+                                                      contrast,
+                                                      saturation,
+                                                      hue * 180.0,
+                                                      temperature);
+                            info(format!("{}\n\nSeed: {}{}\nMixer: {}{}\nSDL: {} {}",
+                                         ABOUT_INFO,
+                                         seed,
+                                         loop_info,
+                                         mixer,
+                                         adjust_info,
+                                         sdl2::version::version(),
+                                         sdl2::version::revision()).into());
+                        },
+                        Event::KeyDown { keycode: Some(Keycode::M), .. } => {
+                            mixer = mixer.next();
+                            let plasma = Arc::get_mut(&mut plasma).ok_or("Could not access plasma data exclusively")?;
+                            plasma.set_phase_amps_len(mixer.phase_amps_len(), &mut rng);
+                            if plasma.timeline_seed().is_some() {
+                                plasma.at_time(time);
+                            }
                         },
                         Event::KeyDown { keycode: Some(Keycode::Escape), .. }
                         | Event::Window { win_event: WindowEvent::Close, .. }
                         | Event::Quit { .. } => break 'mainloop,
+                        Event::KeyDown { keycode: Some(keycode), keymod, .. } => {
+                            if let Some(target) = adjust_by_key(adjust_to, keycode, keymod) {
+                                adjust_from = adjust;
+                                adjust_to = target;
+                                adjust_progress = 0.0;
+                            }
+                        },
                         Event::Window { win_event: WindowEvent::Minimized, .. } => {
                             app_state = AppState::Inactive;
                             continue 'mainloop;
@@ -245,6 +373,7 @@ fn run() -> Result<(), String> {
             match event_pump.wait_event() {
                 Event::Window { win_event: WindowEvent::Restored, .. } => {
                     app_state = AppState::Active;
+                    last_update = Instant::now();
                 },
                 Event::Window { win_event: WindowEvent::Close, .. } | Event::Quit { .. } => break 'mainloop,
                 _ => continue 'mainloop,
@@ -252,9 +381,14 @@ fn run() -> Result<(), String> {
         }
 
         // render plasma
+        #[cfg(feature = "expr")]
+        let expr = {
+            expr.set_time(last_update.duration_since(start).as_secs_f32());
+            &expr
+        };
         texture.with_lock(None, |buffer: &mut [u8], pitch: usize| {
                    let count = thread_count;
-                   let segmh = (plasma_height as usize + count - 1) / count;
+                   let segmh = (plasma_height as usize).div_ceil(count);
                    pool.scoped(|scope| {
                            for (i, (chunk, wrkspc)) in
                                buffer.chunks_mut(segmh * pitch).zip(workspaces.iter_mut()).enumerate()
@@ -263,21 +397,37 @@ fn run() -> Result<(), String> {
                                let h = min(segmh, plasma_height as usize - y);
                                let plasma = Arc::clone(&plasma);
                                scope.execute(move || {
-                                        plasma.render_part::<PixelBufRGB24, PlasmaICPT, PlasmaMixerT>(
-                                            chunk,
-                                            pitch,
-                                            0,
-                                            y,
-                                            plasma_width as usize,
-                                            h,
-                                            Some(wrkspc));
+                                        let (w, wrkspc) = (plasma_width as usize, Some(wrkspc));
+                                        #[cfg(feature = "expr")]
+                                        if mixer == MixerKind::Expr {
+                                            plasma.render_part_dispatch_expr(expr, PIXEL_FORMAT, &adjust, chunk,
+                                                                             pitch, 0, y, w, h, wrkspc);
+                                            return;
+                                        }
+                                        plasma.render_part_dispatch_adjusted(mixer, PIXEL_FORMAT, &adjust, chunk,
+                                                                             pitch, 0, y, w, h, wrkspc);
                                     });
                            }
                        })
                })?;
 
         // update plasma
-        Arc::get_mut(&mut plasma).ok_or_else(|| "Could not access plasma data exclusively")?.update(&mut rng);
+        let now = Instant::now();
+        let elapsed = now.duration_since(last_update).as_secs_f32();
+        let steps = (elapsed * STEPS_PER_SECOND).min(MAX_STEPS_PER_FRAME);
+        last_update = now;
+        if adjust_progress < 1.0 {
+            adjust_progress = (adjust_progress + elapsed / ADJUST_TRANSITION_SECONDS).min(1.0);
+            adjust = adjust_from.interpolate(&adjust_to, adjust_progress, Easing::SmoothStep);
+        }
+        let plasma = Arc::get_mut(&mut plasma).ok_or("Could not access plasma data exclusively")?;
+        match loop_steps {
+            Some(loop_steps) => {
+                time = (time + steps as f64) % loop_steps as f64;
+                plasma.at_time(time);
+            },
+            None => plasma.update_by(steps, &mut rng),
+        }
         // canvas.set_draw_color(Color::RGBA(0, 0, 0, 255));
         // let dst = Some(Rect::new(0, 0, 400, 300));
         // canvas.clear();
@@ -308,7 +458,7 @@ fn main() {
     std::process::exit(match run() {
                            Ok(_) => 0,
                            Err(err) => {
-                               alert(format!("{}", err).into());
+                               alert(err.into());
                                1
                            },
                        });
@@ -32,6 +32,7 @@ fn bench_export(ben: &mut Bencher) {
 }
 
 #[bench]
+#[allow(clippy::needless_range_loop)]
 fn bench_export_indexed(ben: &mut Bencher) {
     use plasma::*;
 
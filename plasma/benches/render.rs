@@ -6,10 +6,8 @@ RUSTFLAGS='-C target-cpu=native' cargo bench --bench render --features=rand/std,
 */
 #![allow(unexpected_cfgs)]
 #![feature(test)]
-#![feature(portable_simd)]
 extern crate test;
 
-use rand;
 use plasma::{self, PixelBuffer};
 
 use test::{black_box, Bencher};
@@ -47,15 +45,86 @@ fn bench_render_rgb24(ben: &mut Bencher) {
     bench_render_buf::<plasma::PixelBufRGB24>(ben)
 }
 
-#[cfg(not(feature = "use-simd"))]
+#[bench]
+fn bench_render_rgb24_srgb(ben: &mut Bencher) {
+    println!("RGB24 sRGB");
+    bench_render_buf::<plasma::PixelBufSrgb<plasma::PixelBufRGB24>>(ben)
+}
+
 #[bench]
 fn bench_render_rgb16(ben: &mut Bencher) {
     println!("RGB16");
     bench_render_buf::<plasma::PixelBufRGB16>(ben)
 }
 
+#[bench]
+fn bench_render_palette(ben: &mut Bencher) {
+    use plasma::*;
+    println!("RGB24 palette");
+    let mixer = PaletteMixer::from_gradient(&[(0.0, [0, 0, 64]), (0.4, [255, 96, 0]), (0.7, [255, 255, 160])]);
+    bench_render::<PixelBufRGB24>(ben, PlasmaMixer::phase_amps_len(), |plasma, buffer, pitch, workspc| {
+        plasma.render_with::<PixelBufRGB24, PlasmaICP, _>(&mixer, buffer, pitch, Some(workspc))
+    })
+}
+
+#[bench]
+fn bench_render_monochrome(ben: &mut Bencher) {
+    use plasma::*;
+    println!("RGB24 monochrome");
+    bench_render::<PixelBufRGB24>(ben, MonochromeMixer::phase_amps_len(), |plasma, buffer, pitch, workspc| {
+        plasma.render::<PixelBufRGB24, MonochromeICP, MonochromeMixer>(buffer, pitch, Some(workspc))
+    })
+}
+
+#[bench]
+fn bench_render_rgb_channels(ben: &mut Bencher) {
+    use plasma::*;
+    println!("RGB24 rgb channels");
+    bench_render::<PixelBufRGB24>(ben, RgbChannelsMixer::phase_amps_len(), |plasma, buffer, pitch, workspc| {
+        plasma.render::<PixelBufRGB24, RgbChannelsICP, RgbChannelsMixer>(buffer, pitch, Some(workspc))
+    })
+}
+
+#[bench]
+fn bench_render_contour(ben: &mut Bencher) {
+    use plasma::*;
+    println!("RGB24 contour");
+    bench_render::<PixelBufRGB24>(ben, ContourMixer::phase_amps_len(), |plasma, buffer, pitch, workspc| {
+        plasma.render::<PixelBufRGB24, ContourICP, ContourMixer>(buffer, pitch, Some(workspc))
+    })
+}
+
+#[bench]
+fn bench_render_lava_lamp(ben: &mut Bencher) {
+    use plasma::*;
+    println!("RGB24 lava lamp");
+    bench_render::<PixelBufRGB24>(ben, LavaLampMixer::phase_amps_len(), |plasma, buffer, pitch, workspc| {
+        plasma.render::<PixelBufRGB24, LavaLampICP, LavaLampMixer>(buffer, pitch, Some(workspc))
+    })
+}
+
+#[bench]
+fn bench_render_blend(ben: &mut Bencher) {
+    use plasma::*;
+    println!("RGB24 plasma screen contour blend");
+    type Blend = BlendMixer<PlasmaMixer, ContourMixer>;
+    let mixer = Blend::new(PlasmaMixer, ContourMixer, BlendMode::Screen);
+    bench_render::<PixelBufRGB24>(ben, Blend::phase_amps_len(), |plasma, buffer, pitch, workspc| {
+        plasma.render_with::<PixelBufRGB24, BlendICP<PlasmaMixer, PlasmaICP, ContourICP>, _>(&mixer, buffer, pitch,
+                                                                                             Some(workspc))
+    })
+}
+
 fn bench_render_buf<PBuf: PixelBuffer>(ben: &mut Bencher) {
     use plasma::*;
+    bench_render::<PBuf>(ben, PlasmaMixer::phase_amps_len(), |plasma, buffer, pitch, workspc| {
+        plasma.render::<PBuf, PlasmaICP, PlasmaMixer>(buffer, pitch, Some(workspc))
+    })
+}
+
+fn bench_render<PBuf: PixelBuffer>(ben: &mut Bencher, phase_amps_len: usize,
+                                   render: impl Fn(&plasma::Plasma, &mut [u8], usize, &mut Vec<u8>)) {
+    use plasma::*;
 
     #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
     {
@@ -92,13 +161,14 @@ fn bench_render_buf<PBuf: PixelBuffer>(ben: &mut Bencher) {
     let mut rng = rand::thread_rng();
     let cfg = PhaseAmpCfg::new(min_steps, max_steps);
     let mut plasma = Plasma::new(plasma_width, plasma_height, cfg, &mut rng);
+    plasma.set_phase_amps_len(phase_amps_len, &mut rng);
     let pitch: usize = PBuf::PIXEL_BYTES * plasma_width as usize;
     let mut buffer_rgb24: Vec<u8> = vec![0; pitch * plasma_height as usize];
     let mut workspc = Vec::new();
     ben.iter(|| {
            for _ in 0..10 {
                let buffer: &mut [u8] = &mut buffer_rgb24;
-               plasma.render::<PBuf, PlasmaICP, PlasmaMixer>(buffer, pitch, Some(&mut workspc));
+               render(&plasma, buffer, pitch, &mut workspc);
                plasma.update(&mut rng);
                black_box(buffer);
            }
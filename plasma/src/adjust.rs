@@ -0,0 +1,233 @@
+//! Color adjustments applied to the pixels produced by the mixers.
+use core::f32::consts::PI;
+
+use crate::{color::PixelRgb, easing::*, mixer::*, simd_polyfill::*};
+#[cfg(not(feature = "std"))]
+use crate::m_polyfill::*;
+
+/// The color adjustments of the rendered pixels.
+///
+/// The adjustments are applied in order: the hue rotation, the saturation, the color temperature,
+/// the contrast and the brightness. All of them are combined into a single affine transformation,
+/// see [ColorAdjust::matrix]. Use an [AdjustedMixer] to apply them when rendering.
+///
+/// The default value is the [ColorAdjust::IDENTITY] which leaves the colors unchanged.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ColorAdjust {
+    /// The value added to each color component, `0.0` leaves the brightness unchanged.
+    pub brightness:  f32,
+    /// The contrast factor around the middle gray, `1.0` leaves the contrast unchanged.
+    pub contrast:    f32,
+    /// The saturation factor, `0.0` yields the grayscale and `1.0` leaves the saturation unchanged.
+    pub saturation:  f32,
+    /// The hue rotation in half turns, so the range `[0, 2)` makes a full circle.
+    pub hue:         f32,
+    /// The color temperature in the range: `[-1, 1]`. The positive values make the colors warmer
+    /// and the negative ones cooler.
+    pub temperature: f32,
+}
+
+/// A [Mixer] applying the [ColorAdjust] to each pixel of the wrapped mixer.
+///
+/// The wrapped mixer is rendered with its own [IntermediateCalculatorProducer] and
+/// [PixelTermCalculatorProducer] if it is a [PixelTermsMixer].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AdjustedMixer<M> {
+    mixer:  M,
+    adjust: ColorAdjust,
+    matrix: [[f32; 4]; 3],
+}
+
+/// The maximum change of the red and blue gains by the [ColorAdjust::temperature].
+const TEMPERATURE_GAIN: f32 = 0.2;
+/// The luminance coefficients used by the hue rotation and the saturation.
+const LUMA: [f32; 3] = [0.213, 0.715, 0.072];
+
+impl ColorAdjust {
+    /// The adjustments leaving the colors unchanged.
+    pub const IDENTITY: ColorAdjust =
+        ColorAdjust { brightness: 0.0, contrast: 1.0, saturation: 1.0, hue: 0.0, temperature: 0.0 };
+
+    /// Returns `true` if the adjustments leave the colors unchanged.
+    pub fn is_identity(&self) -> bool { *self == ColorAdjust::IDENTITY }
+
+    /// Returns the adjustments between `self` and `to`.
+    ///
+    /// The `t` argument is the progress of the interpolation: `0.0` yields `self`, `1.0` yields `to`.
+    /// The values outside the range `[0, 1]` are clamped. The `easing` is applied to the progress.
+    ///
+    /// The hue is interpolated along the shortest arc, the other adjustments linearly.
+    pub fn interpolate(&self, to: &ColorAdjust, t: f32, easing: Easing) -> ColorAdjust {
+        let t = easing.ease(t);
+        let lerp = |a: f32, b: f32| a + (b - a) * t;
+        let delta_hue = match (to.hue - self.hue) % 2.0 {
+            d if d > 1.0 => d - 2.0,
+            d if d < -1.0 => d + 2.0,
+            d => d,
+        };
+        ColorAdjust { brightness:  lerp(self.brightness, to.brightness),
+                      contrast:    lerp(self.contrast, to.contrast),
+                      saturation:  lerp(self.saturation, to.saturation),
+                      hue:         self.hue + delta_hue * t,
+                      temperature: lerp(self.temperature, to.temperature) }
+    }
+
+    /// Returns the affine transformation of the adjustments.
+    ///
+    /// Each row computes one of the red, green and blue components: the first three columns
+    /// are the factors of the source components and the last one is the offset.
+    pub fn matrix(&self) -> [[f32; 4]; 3] {
+        let (sin, cos) = ((self.hue * PI).sin(), (self.hue * PI).cos());
+        let [lr, lg, lb] = LUMA;
+        let hue = [[lr + cos * (1.0 - lr) - sin * lr, lg - cos * lg - sin * lg, lb - cos * lb + sin * (1.0 - lb)],
+                   [lr - cos * lr + sin * 0.143, lg + cos * (1.0 - lg) + sin * 0.140, lb - cos * lb - sin * 0.283],
+                   [lr - cos * lr - sin * (1.0 - lr), lg - cos * lg + sin * lg, lb + cos * (1.0 - lb) + sin * lb]];
+        let s = self.saturation;
+        let saturation = [[lr + (1.0 - lr) * s, lg - lg * s, lb - lb * s],
+                          [lr - lr * s, lg + (1.0 - lg) * s, lb - lb * s],
+                          [lr - lr * s, lg - lg * s, lb + (1.0 - lb) * s]];
+        let temperature = self.temperature.clamp(-1.0, 1.0) * TEMPERATURE_GAIN;
+        let gains = [(1.0 + temperature) * self.contrast, self.contrast, (1.0 - temperature) * self.contrast];
+        let offset = 0.5 - 0.5 * self.contrast + self.brightness;
+        let mut matrix = [[0.0; 4]; 3];
+        for ((row, sat_row), gain) in matrix.iter_mut().zip(saturation.iter()).zip(gains) {
+            for (col, cell) in row[..3].iter_mut().enumerate() {
+                *cell = gain * sat_row.iter().zip(hue.iter()).map(|(s, hue_row)| s * hue_row[col]).sum::<f32>();
+            }
+            row[3] = offset;
+        }
+        matrix
+    }
+}
+
+impl Default for ColorAdjust {
+    fn default() -> Self { ColorAdjust::IDENTITY }
+}
+
+impl<M> AdjustedMixer<M> {
+    /// Creates an instance of [AdjustedMixer] applying the `adjust` to the pixels of the `mixer`.
+    pub fn new(mixer: M, adjust: ColorAdjust) -> Self { AdjustedMixer { mixer, adjust, matrix: adjust.matrix() } }
+
+    /// Returns the current adjustments.
+    #[inline]
+    pub fn adjust(&self) -> &ColorAdjust { &self.adjust }
+
+    /// Changes the adjustments, e.g. to animate them with [ColorAdjust::interpolate].
+    pub fn set_adjust(&mut self, adjust: ColorAdjust) {
+        self.adjust = adjust;
+        self.matrix = adjust.matrix();
+    }
+
+    /// Returns the reference to the wrapped mixer.
+    #[inline]
+    pub fn mixer(&self) -> &M { &self.mixer }
+
+    /// Returns the mutable reference to the wrapped mixer.
+    #[inline]
+    pub fn mixer_mut(&mut self) -> &mut M { &mut self.mixer }
+
+    #[inline]
+    fn apply(&self, pixel: PixelRgb) -> PixelRgb {
+        let [r, g, b] = self.matrix.map(|[mr, mg, mb, offset]| {
+                                           pixel.r * Flt::sc_splat(mr)
+                                           + pixel.g * Flt::sc_splat(mg)
+                                           + pixel.b * Flt::sc_splat(mb)
+                                           + Flt::sc_splat(offset)
+                                       });
+        PixelRgb::new(r, g, b)
+    }
+}
+
+impl<M: Default> Default for AdjustedMixer<M> {
+    fn default() -> Self { AdjustedMixer::new(M::default(), ColorAdjust::IDENTITY) }
+}
+
+impl<M: Mixer<Flt>> Mixer<Flt> for AdjustedMixer<M> {
+    type IntermediateH = M::IntermediateH;
+    type IntermediateV = M::IntermediateV;
+
+    #[inline]
+    fn intermediate_h_len() -> usize { M::intermediate_h_len() }
+
+    #[inline]
+    fn intermediate_v_len() -> usize { M::intermediate_v_len() }
+
+    #[inline]
+    fn phase_amps_len() -> usize { M::phase_amps_len() }
+
+    #[inline]
+    fn separable_phase_amps_len() -> usize { M::separable_phase_amps_len() }
+
+    #[inline]
+    fn mix_pixels(&self, vxp: &Self::IntermediateH, vyp: &Self::IntermediateV, next_pixel: &mut dyn FnMut(PixelRgb)) {
+        self.mixer.mix_pixels(vxp, vyp, &mut |pixel| next_pixel(self.apply(pixel)))
+    }
+}
+
+impl<M: PixelTermsMixer<Flt>> PixelTermsMixer<Flt> for AdjustedMixer<M> {
+    type IntermediateP = M::IntermediateP;
+
+    #[inline]
+    fn intermediate_p_len() -> usize { M::intermediate_p_len() }
+
+    #[inline]
+    fn pixel_phase_amps_len() -> usize { M::pixel_phase_amps_len() }
+
+    #[inline]
+    fn mix_pixels_terms(&self, vxp: &Self::IntermediateH, vyp: &Self::IntermediateV, vpp: &Self::IntermediateP,
+                        next_pixel: &mut dyn FnMut(PixelRgb)) {
+        self.mixer.mix_pixels_terms(vxp, vyp, vpp, &mut |pixel| next_pixel(self.apply(pixel)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    fn render<M: Mixer<Flt>>(plasma: &Plasma, mixer: &M) -> Vec<u8> {
+        let pitch = 32 * PixelBufRGB24::PIXEL_BYTES;
+        let mut buffer = vec![0u8; pitch * 20];
+        plasma.render_with::<PixelBufRGB24, RgbChannelsICP, M>(mixer, &mut buffer, pitch, None);
+        buffer
+    }
+
+    fn assert_close(buffer: &[u8], expected: &[u8]) {
+        assert!(buffer.iter().zip(expected).all(|(a, b)| a.abs_diff(*b) <= 1));
+    }
+
+    #[test]
+    fn adjust_works() {
+        let (mut plasma, mut rng) = Plasma::new_seeded(32, 20, PhaseAmpCfg::default(), 3);
+        plasma.set_phase_amps_len(RgbChannelsMixer::phase_amps_len(), &mut rng);
+        let expected = render(&plasma, &RgbChannelsMixer);
+        assert_close(&render(&plasma, &AdjustedMixer::<RgbChannelsMixer>::default()), &expected);
+        let full_turn = ColorAdjust { hue: 2.0, ..ColorAdjust::IDENTITY };
+        assert_close(&render(&plasma, &AdjustedMixer::new(RgbChannelsMixer, full_turn)), &expected);
+
+        let gray = ColorAdjust { saturation: 0.0, ..ColorAdjust::IDENTITY };
+        for rgb in render(&plasma, &AdjustedMixer::new(RgbChannelsMixer, gray)).chunks_exact(3) {
+            assert!(rgb[0].abs_diff(rgb[1]) <= 1 && rgb[1].abs_diff(rgb[2]) <= 1, "{:?}", rgb);
+        }
+
+        let mut mixer = AdjustedMixer::new(RgbChannelsMixer, ColorAdjust { contrast: 0.0, ..ColorAdjust::IDENTITY });
+        assert!(render(&plasma, &mixer).iter().all(|&v| v == 127));
+        mixer.set_adjust(ColorAdjust { brightness: 1.0, ..*mixer.adjust() });
+        assert!(render(&plasma, &mixer).iter().all(|&v| v == 255));
+
+        let warm = ColorAdjust { temperature: 1.0, ..ColorAdjust::IDENTITY };
+        let [[r, ..], _, [.., b, _]] = warm.matrix();
+        assert!(r > 1.0 && b < 1.0);
+    }
+
+    #[test]
+    fn interpolate_works() {
+        let from = ColorAdjust { hue: 1.75, brightness: -0.5, ..ColorAdjust::IDENTITY };
+        let to = ColorAdjust { hue: 0.25, brightness: 0.5, saturation: 0.0, ..ColorAdjust::IDENTITY };
+        assert_eq!(from.interpolate(&to, 0.0, Easing::Linear), from);
+        assert_eq!(from.interpolate(&to, -1.0, Easing::SmoothStep), from);
+        let half = from.interpolate(&to, 0.5, Easing::Linear);
+        assert_eq!(half, ColorAdjust { hue: 2.0, brightness: 0.0, saturation: 0.5, ..ColorAdjust::IDENTITY });
+        let end = from.interpolate(&to, 1.0, Easing::Linear);
+        assert_eq!(ColorAdjust { hue: end.hue % 2.0, ..end }, to);
+    }
+}
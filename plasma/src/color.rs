@@ -1,5 +1,6 @@
 //! Contains a [PixelRgb] with optional SIMD implementation selected by a "use-simd" feature.
 use cfg_if::cfg_if;
+use crate::simd_polyfill::{csplat, Flt, SimdCompat};
 use derive_more::{
     Debug, Neg,
     Add, Sub, Mul, Div, Rem,
@@ -140,22 +141,22 @@ cfg_if! {if #[cfg(feature = "use-simd")] {
                 r = less.select(c+m, r); g = less.select(x+m, g);
             }
             let less2 = h.simd_lt(Flt::splat(2.0));
-            let less = less.select_mask(mfalse, less2);
+            let less = less.select(mfalse, less2);
             if less.any() {
                 r = less.select(x+m, r); g = less.select(c+m, g);
             }
             let less3 = h.simd_lt(Flt::splat(3.0));
-            let less = less2.select_mask(mfalse, less3);
+            let less = less2.select(mfalse, less3);
             if less.any() {
                 g = less.select(c+m, g); b = less.select(x+m, b);
             }
             let less4 = h.simd_lt(Flt::splat(4.0));
-            let less = less3.select_mask(mfalse, less4);
+            let less = less3.select(mfalse, less4);
             if less.any() {
                 g = less.select(x+m, g); b = less.select(c+m, b);
             }
             let less5 = h.simd_lt(Flt::splat(5.0));
-            let less = less4.select_mask(mfalse, less5);
+            let less = less4.select(mfalse, less5);
             if less.any() {
                 r = less.select(x+m, r); b = less.select(c+m, b);
             }
@@ -167,6 +168,20 @@ cfg_if! {if #[cfg(feature = "use-simd")] {
         }
     }
 
+    #[inline]
+    fn srgb_encode(v: f32s) -> f32s {
+        let high = (v.sc_max(Flt::splat(SRGB_LINEAR_CUTOFF)).ln() * Flt::splat(1.0 / SRGB_GAMMA)).exp()
+                   * Flt::splat(1.055) - Flt::splat(0.055);
+        v.simd_le(Flt::splat(SRGB_LINEAR_CUTOFF)).select(v * Flt::splat(12.92), high)
+    }
+
+    #[inline]
+    fn srgb_decode(v: f32s) -> f32s {
+        let high = (((v.sc_max(Flt::splat(SRGB_ENCODED_CUTOFF)) + Flt::splat(0.055)) / Flt::splat(1.055)).ln()
+                    * Flt::splat(SRGB_GAMMA)).exp();
+        v.simd_le(Flt::splat(SRGB_ENCODED_CUTOFF)).select(v / Flt::splat(12.92), high)
+    }
+
 } else {
     #[cfg(not(feature = "std"))]
     use crate::m_polyfill::*;
@@ -251,8 +266,87 @@ cfg_if! {if #[cfg(feature = "use-simd")] {
             PixelRgb { r, g, b }
         }
     }
+
+    #[inline]
+    fn srgb_encode(v: f32) -> f32 {
+        if v <= SRGB_LINEAR_CUTOFF {
+            v * 12.92
+        }
+        else {
+            v.powf(1.0 / SRGB_GAMMA) * 1.055 - 0.055
+        }
+    }
+
+    #[inline]
+    fn srgb_decode(v: f32) -> f32 {
+        if v <= SRGB_ENCODED_CUTOFF {
+            v / 12.92
+        }
+        else {
+            ((v + 0.055) / 1.055).powf(SRGB_GAMMA)
+        }
+    }
 }}
 
+/// The exponent of the sRGB transfer function.
+const SRGB_GAMMA: f32 = 2.4;
+/// The linear value below which the sRGB transfer function is linear.
+pub(crate) const SRGB_LINEAR_CUTOFF: f32 = 0.0031308;
+/// The sRGB encoded value below which the sRGB transfer function is linear.
+const SRGB_ENCODED_CUTOFF: f32 = 0.04045;
+
+impl PixelRgb {
+    /// Creates an instance of a [PixelRgb] from HSL color components.
+    ///
+    /// `hue` should be in the range: `[0, 2)` and will be normalized like in [PixelRgb::from_hsv].
+    /// `sat` and `light` should be in the range: `[0, 1]` and won't be normalized.
+    #[inline]
+    pub fn from_hsl(hue: Flt, sat: Flt, light: Flt) -> PixelRgb {
+        const ONE: Flt = csplat(1.0);
+        let val = light + sat * light.sc_min(ONE - light);
+        // the saturation doesn't matter if the value is 0
+        let sat = (ONE - light / val.sc_max(csplat(f32::MIN_POSITIVE))) * csplat(2.0);
+        PixelRgb::from_hsv(hue, sat, val)
+    }
+
+    /// Creates an instance of a [PixelRgb] in the linear RGB color space from the Oklab color components.
+    ///
+    /// `light` should be in the range: `[0, 1]`, `a` and `b` are usually within `[-0.4, 0.4]`.
+    /// The colors outside of the sRGB gamut have components outside of the range `[0, 1]`.
+    #[inline]
+    pub fn from_oklab(light: Flt, a: Flt, b: Flt) -> PixelRgb {
+        let l = light + a * csplat(0.396_337_78) + b * csplat(0.215_803_76);
+        let m = light - a * csplat(0.105_561_35) - b * csplat(0.063_854_17);
+        let s = light - a * csplat(0.089_484_18) - b * csplat(1.291_485_5);
+        let (l, m, s) = (l * l * l, m * m * m, s * s * s);
+        PixelRgb { r: l * csplat(4.076_741_7) - m * csplat(3.307_711_6) + s * csplat(0.230_969_94),
+                   g: m * csplat(2.609_757_4) - l * csplat(1.268_438) - s * csplat(0.341_319_4),
+                   b: s * csplat(1.707_614_7) - l * csplat(0.004_196_086_3) - m * csplat(0.703_418_6), }
+    }
+
+    /// Creates an instance of a [PixelRgb] in the linear RGB color space from the Oklch color components.
+    ///
+    /// `light` should be in the range: `[0, 1]` and `chroma` is usually within `[0, 0.4]`.
+    /// `hue` is given in half turns, so like in [PixelRgb::from_hsv] the range `[0, 2)` makes a full circle.
+    #[inline]
+    pub fn from_oklch(light: Flt, chroma: Flt, hue: Flt) -> PixelRgb {
+        let angle = hue * csplat(core::f32::consts::PI);
+        PixelRgb::from_oklab(light, chroma * angle.cos(), chroma * angle.sin())
+    }
+
+    /// Converts the linear RGB color components to the sRGB encoded ones.
+    #[inline]
+    pub fn to_srgb(self) -> PixelRgb {
+        PixelRgb { r: srgb_encode(self.r), g: srgb_encode(self.g), b: srgb_encode(self.b) }
+    }
+
+    /// Converts the sRGB encoded color components to the linear RGB ones.
+    #[inline]
+    pub fn to_linear(self) -> PixelRgb {
+        PixelRgb { r: srgb_decode(self.r), g: srgb_decode(self.g), b: srgb_decode(self.b) }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{color::*, simd_polyfill::*};
@@ -289,6 +383,43 @@ mod tests {
                    PixelRgb::from_hsv(splat(1.5), splat(1.0), splat(1.0)));
     }
 
+    fn assert_close(pixel: PixelRgb, rgb: [f32; 3]) {
+        for (res, expected) in pixel.iter_rgb_values().zip(rgb.iter().cycle()) {
+            assert!((res - expected).abs() < 1e-3, "{:?} != {:?}", pixel, rgb);
+        }
+    }
+
+    #[test]
+    fn hsl_works() {
+        assert_close(PixelRgb::from_hsl(splat(0.0), splat(1.0), splat(0.5)), [1.0, 0.0, 0.0]);
+        assert_close(PixelRgb::from_hsl(splat(2.0 / 3.0), splat(1.0), splat(0.5)), [0.0, 1.0, 0.0]);
+        assert_close(PixelRgb::from_hsl(splat(0.5), splat(0.0), splat(0.25)), [0.25, 0.25, 0.25]);
+        assert_close(PixelRgb::from_hsl(splat(0.0), splat(1.0), splat(0.0)), [0.0, 0.0, 0.0]);
+        assert_close(PixelRgb::from_hsl(splat(0.0), splat(1.0), splat(1.0)), [1.0, 1.0, 1.0]);
+        assert_close(PixelRgb::from_hsl(splat(0.0), splat(0.5), splat(0.75)), [0.875, 0.625, 0.625]);
+    }
+
+    #[test]
+    fn oklab_works() {
+        assert_close(PixelRgb::from_oklab(splat(1.0), splat(0.0), splat(0.0)), [1.0, 1.0, 1.0]);
+        assert_close(PixelRgb::from_oklab(splat(0.0), splat(0.0), splat(0.0)), [0.0, 0.0, 0.0]);
+        assert_close(PixelRgb::from_oklab(splat(0.627_955), splat(0.224_863), splat(0.125_846)), [1.0, 0.0, 0.0]);
+        assert_close(PixelRgb::from_oklab(splat(0.452_014), splat(-0.032_457), splat(-0.311_528)), [0.0, 0.0, 1.0]);
+        assert_close(PixelRgb::from_oklch(splat(0.627_955), splat(0.257_683), splat(0.162_507)), [1.0, 0.0, 0.0]);
+        assert_close(PixelRgb::from_oklch(splat(0.627_955), splat(0.257_683), splat(2.162_507)), [1.0, 0.0, 0.0]);
+        assert_close(PixelRgb::from_oklch(splat(0.5), splat(0.0), splat(0.7)), [0.125, 0.125, 0.125]);
+    }
+
+    #[test]
+    fn srgb_works() {
+        assert_close(PixelRgb::new(splat(0.5), splat(0.001), splat(1.0)).to_srgb(), [0.735_357, 0.012_92, 1.0]);
+        assert_close(PixelRgb::new(splat(0.735_357), splat(0.012_92), splat(0.0)).to_linear(), [0.5, 0.001, 0.0]);
+        for v in [0.0, 0.002, 0.01, 0.2, 0.7, 1.0] {
+            let pixel = PixelRgb::new(splat(v), splat(v), splat(v));
+            assert_close(pixel.to_srgb().to_linear(), [v, v, v]);
+        }
+    }
+
     cfg_if! {if #[cfg(feature = "use-simd")] {
 
         #[test]
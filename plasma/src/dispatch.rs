@@ -0,0 +1,594 @@
+//! Rendering with the mixer and the pixel format selected at runtime.
+//!
+//! The generic rendering functions are monomorphized here for each combination of
+//! a [MixerKind] and a [PixelFormat].
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::{fmt, str::FromStr};
+
+use crate::{adjust::*, error::*, mixer::*, mixers::*, phase_amp::*, pixel_buffer::*, plasma::*};
+
+/// The built-in mixers available for rendering selected at runtime.
+///
+/// Each mixer is rendered with its own [IntermediateCalculatorProducer].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum MixerKind {
+    /// The [PlasmaMixer] with the [Fundamental] frequencies.
+    #[default]
+    Plasma,
+    /// The [PlasmaMixer] with the [Overtones] frequencies.
+    Overtones,
+    /// The [PlasmaOklchMixer] encoded to sRGB with the [PixelBufSrgb] buffers.
+    Oklch,
+    /// The [MonochromeMixer].
+    Monochrome,
+    /// The [RgbChannelsMixer].
+    RgbChannels,
+    /// The [ContourMixer].
+    Contour,
+    /// The [LavaLampMixer].
+    LavaLamp,
+    /// The [RadialMixer] with the [PixelWaveProducer] per-pixel terms.
+    Radial,
+    /// The [ExprMixer] of the [DEFAULT_EXPR], see [render_part_dispatch_expr] for the other expressions.
+    #[cfg(feature = "expr")]
+    Expr,
+}
+
+/// The pixel formats available for rendering selected at runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum PixelFormat {
+    /// The [PixelBufRGB24] format.
+    #[default]
+    Rgb24,
+    /// The [PixelBufRGBA32] format.
+    Rgba32,
+    /// The [PixelBufBGR24] format.
+    Bgr24,
+    /// The [PixelBufBGRA32] format.
+    Bgra32,
+    /// The [PixelBufARGB32] format.
+    Argb32,
+    /// The [PixelBufLuma8] format.
+    Luma8,
+    /// The [PixelBufMono8] format.
+    Mono8,
+    /// The [PixelBufRGB332] format.
+    Rgb332,
+    /// The [PixelBufRGB555] format.
+    Rgb555,
+    /// The [PixelBufRGB16LE] format.
+    Rgb16Le,
+    /// The [PixelBufRGB16] format.
+    Rgb16,
+    /// The [PixelBufRGB16x2] format.
+    Rgb16x2,
+}
+
+impl MixerKind {
+    /// All of the mixer kinds.
+    pub const ALL: &'static [MixerKind] = &[MixerKind::Plasma,
+                                            MixerKind::Overtones,
+                                            MixerKind::Oklch,
+                                            MixerKind::Monochrome,
+                                            MixerKind::RgbChannels,
+                                            MixerKind::Contour,
+                                            MixerKind::LavaLamp,
+                                            MixerKind::Radial,
+                                            #[cfg(feature = "expr")]
+                                            MixerKind::Expr];
+
+    /// Returns the name of the mixer kind, the same as accepted by [MixerKind::from_str].
+    pub fn name(self) -> &'static str {
+        match self {
+            MixerKind::Plasma => "plasma",
+            MixerKind::Overtones => "overtones",
+            MixerKind::Oklch => "oklch",
+            MixerKind::Monochrome => "monochrome",
+            MixerKind::RgbChannels => "rgb-channels",
+            MixerKind::Contour => "contour",
+            MixerKind::LavaLamp => "lava-lamp",
+            MixerKind::Radial => "radial",
+            #[cfg(feature = "expr")]
+            MixerKind::Expr => "expr",
+        }
+    }
+
+    /// Returns the next mixer kind from [MixerKind::ALL], wrapping around after the last one.
+    pub fn next(self) -> Self { next_of(MixerKind::ALL, self) }
+
+    /// Returns the number of phase'n'amplitude components required by the mixer.
+    pub fn phase_amps_len(self) -> usize {
+        match self {
+            MixerKind::Plasma | MixerKind::Overtones => PlasmaMixer::phase_amps_len(),
+            MixerKind::Oklch => PlasmaOklchMixer::phase_amps_len(),
+            MixerKind::Monochrome => MonochromeMixer::phase_amps_len(),
+            MixerKind::RgbChannels => RgbChannelsMixer::phase_amps_len(),
+            MixerKind::Contour => ContourMixer::phase_amps_len(),
+            MixerKind::LavaLamp => LavaLampMixer::phase_amps_len(),
+            MixerKind::Radial => RadialMixer::phase_amps_len(),
+            #[cfg(feature = "expr")]
+            MixerKind::Expr => ExprMixer::phase_amps_len(),
+        }
+    }
+}
+
+impl PixelFormat {
+    /// All of the pixel formats.
+    pub const ALL: &'static [PixelFormat] = &[PixelFormat::Rgb24,
+                                              PixelFormat::Rgba32,
+                                              PixelFormat::Bgr24,
+                                              PixelFormat::Bgra32,
+                                              PixelFormat::Argb32,
+                                              PixelFormat::Luma8,
+                                              PixelFormat::Mono8,
+                                              PixelFormat::Rgb332,
+                                              PixelFormat::Rgb555,
+                                              PixelFormat::Rgb16Le,
+                                              PixelFormat::Rgb16,
+                                              PixelFormat::Rgb16x2];
+
+    /// Returns the name of the pixel format, the same as accepted by [PixelFormat::from_str].
+    pub fn name(self) -> &'static str {
+        match self {
+            PixelFormat::Rgb24 => "rgb24",
+            PixelFormat::Rgba32 => "rgba32",
+            PixelFormat::Bgr24 => "bgr24",
+            PixelFormat::Bgra32 => "bgra32",
+            PixelFormat::Argb32 => "argb32",
+            PixelFormat::Luma8 => "luma8",
+            PixelFormat::Mono8 => "mono8",
+            PixelFormat::Rgb332 => "rgb332",
+            PixelFormat::Rgb555 => "rgb555",
+            PixelFormat::Rgb16Le => "rgb16le",
+            PixelFormat::Rgb16 => "rgb16",
+            PixelFormat::Rgb16x2 => "rgb16x2",
+        }
+    }
+
+    /// Returns the next pixel format from [PixelFormat::ALL], wrapping around after the last one.
+    pub fn next(self) -> Self { next_of(PixelFormat::ALL, self) }
+
+    /// Returns the number of bytes of a single plasma pixel, see [PixelBuffer::PIXEL_BYTES].
+    pub fn pixel_bytes(self) -> usize {
+        match self {
+            PixelFormat::Rgb24 => PixelBufRGB24::PIXEL_BYTES,
+            PixelFormat::Rgba32 => PixelBufRGBA32::PIXEL_BYTES,
+            PixelFormat::Bgr24 => PixelBufBGR24::PIXEL_BYTES,
+            PixelFormat::Bgra32 => PixelBufBGRA32::PIXEL_BYTES,
+            PixelFormat::Argb32 => PixelBufARGB32::PIXEL_BYTES,
+            PixelFormat::Luma8 => PixelBufLuma8::PIXEL_BYTES,
+            PixelFormat::Mono8 => PixelBufMono8::PIXEL_BYTES,
+            PixelFormat::Rgb332 => PixelBufRGB332::PIXEL_BYTES,
+            PixelFormat::Rgb555 => PixelBufRGB555::PIXEL_BYTES,
+            PixelFormat::Rgb16Le => PixelBufRGB16LE::PIXEL_BYTES,
+            PixelFormat::Rgb16 => PixelBufRGB16::PIXEL_BYTES,
+            PixelFormat::Rgb16x2 => PixelBufRGB16x2::PIXEL_BYTES,
+        }
+    }
+}
+
+impl fmt::Display for MixerKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.write_str(self.name()) }
+}
+
+impl fmt::Display for PixelFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.write_str(self.name()) }
+}
+
+impl FromStr for MixerKind {
+    type Err = PlasmaError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        MixerKind::ALL.iter().copied().find(|kind| kind.name() == name).ok_or(PlasmaError::UnknownName)
+    }
+}
+
+impl FromStr for PixelFormat {
+    type Err = PlasmaError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        PixelFormat::ALL.iter().copied().find(|format| format.name() == name).ok_or(PlasmaError::UnknownName)
+    }
+}
+
+fn next_of<T: Copy + PartialEq>(all: &[T], item: T) -> T {
+    let index = all.iter().position(|&other| other == item).unwrap_or(0);
+    all[(index + 1) % all.len()]
+}
+
+/// Calls `$func` for the separable mixers and `$terms` for the [PixelTermsMixer]s with the instance
+/// of the mixer, wrapped in the [AdjustedMixer] if the adjustments are given in `$adjust`.
+macro_rules! dispatch {
+    ($func:ident, $terms:ident::<$pa:ty>($mixer:expr, $format:expr, $adjust:tt)($($arg:expr),*)) => {
+        match $format {
+            PixelFormat::Rgb24 => dispatch!(@mixer $func, $terms::<PixelBufRGB24, $pa>($mixer, $adjust)($($arg),*)),
+            PixelFormat::Rgba32 => dispatch!(@mixer $func, $terms::<PixelBufRGBA32, $pa>($mixer, $adjust)($($arg),*)),
+            PixelFormat::Bgr24 => dispatch!(@mixer $func, $terms::<PixelBufBGR24, $pa>($mixer, $adjust)($($arg),*)),
+            PixelFormat::Bgra32 => dispatch!(@mixer $func, $terms::<PixelBufBGRA32, $pa>($mixer, $adjust)($($arg),*)),
+            PixelFormat::Argb32 => dispatch!(@mixer $func, $terms::<PixelBufARGB32, $pa>($mixer, $adjust)($($arg),*)),
+            PixelFormat::Luma8 => dispatch!(@mixer $func, $terms::<PixelBufLuma8, $pa>($mixer, $adjust)($($arg),*)),
+            PixelFormat::Mono8 => dispatch!(@mixer $func, $terms::<PixelBufMono8, $pa>($mixer, $adjust)($($arg),*)),
+            PixelFormat::Rgb332 => dispatch!(@mixer $func, $terms::<PixelBufRGB332, $pa>($mixer, $adjust)($($arg),*)),
+            PixelFormat::Rgb555 => dispatch!(@mixer $func, $terms::<PixelBufRGB555, $pa>($mixer, $adjust)($($arg),*)),
+            PixelFormat::Rgb16Le => {
+                dispatch!(@mixer $func, $terms::<PixelBufRGB16LE, $pa>($mixer, $adjust)($($arg),*))
+            }
+            PixelFormat::Rgb16 => dispatch!(@mixer $func, $terms::<PixelBufRGB16, $pa>($mixer, $adjust)($($arg),*)),
+            PixelFormat::Rgb16x2 => {
+                dispatch!(@mixer $func, $terms::<PixelBufRGB16x2, $pa>($mixer, $adjust)($($arg),*))
+            }
+        }
+    };
+    (@mixer $func:ident, $terms:ident::<$buf:ty, $pa:ty>($mixer:expr, $adjust:tt)($($arg:expr),*)) => {
+        match $mixer {
+            MixerKind::Plasma => {
+                $func::<$buf, PlasmaInterCalcProducer<$pa>, _, $pa>(&dispatch!(@adjust $adjust, PlasmaMixer), $($arg),*)
+            }
+            MixerKind::Overtones => {
+                $func::<$buf, PlasmaInterCalcProducer<$pa, Overtones>, _, $pa>(&dispatch!(@adjust $adjust, PlasmaMixer),
+                                                                              $($arg),*)
+            }
+            MixerKind::Oklch => {
+                $func::<PixelBufSrgb<$buf>, PlasmaInterCalcProducer<$pa>, _, $pa>(
+                    &dispatch!(@adjust $adjust, PlasmaOklchMixer),
+                    $($arg),*
+                )
+            }
+            MixerKind::Monochrome => {
+                $func::<$buf, MonochromeInterCalcProducer<$pa>, _, $pa>(&dispatch!(@adjust $adjust, MonochromeMixer),
+                                                                       $($arg),*)
+            }
+            MixerKind::RgbChannels => {
+                $func::<$buf, RgbChannelsInterCalcProducer<$pa>, _, $pa>(&dispatch!(@adjust $adjust, RgbChannelsMixer),
+                                                                        $($arg),*)
+            }
+            MixerKind::Contour => {
+                $func::<$buf, ContourInterCalcProducer<$pa>, _, $pa>(&dispatch!(@adjust $adjust, ContourMixer),
+                                                                    $($arg),*)
+            }
+            MixerKind::LavaLamp => {
+                $func::<$buf, LavaLampInterCalcProducer<$pa>, _, $pa>(&dispatch!(@adjust $adjust, LavaLampMixer),
+                                                                     $($arg),*)
+            }
+            MixerKind::Radial => {
+                $terms::<$buf, PlasmaInterCalcProducer<$pa>, PixelWaveProducer<$pa>, _, $pa>(
+                    &dispatch!(@adjust $adjust, RadialMixer),
+                    $($arg),*
+                )
+            }
+            #[cfg(feature = "expr")]
+            MixerKind::Expr => {
+                $func::<$buf, PlasmaInterCalcProducer<$pa>, _, $pa>(
+                    &dispatch!(@adjust $adjust, ExprMixer::shared_default()),
+                    $($arg),*
+                )
+            }
+        }
+    };
+    (@adjust [], $mixer:expr) => { $mixer };
+    (@adjust [$adjust:expr], $mixer:expr) => { AdjustedMixer::new($mixer, $adjust) };
+}
+
+/// Calls `$func` with the buffer of the pixel `$format` and the `$icp` producer.
+#[cfg(feature = "expr")]
+macro_rules! dispatch_format {
+    ($func:ident::<$icp:ty, $pa:ty>($format:expr)($($arg:expr),*)) => {
+        match $format {
+            PixelFormat::Rgb24 => $func::<PixelBufRGB24, $icp, _, $pa>($($arg),*),
+            PixelFormat::Rgba32 => $func::<PixelBufRGBA32, $icp, _, $pa>($($arg),*),
+            PixelFormat::Bgr24 => $func::<PixelBufBGR24, $icp, _, $pa>($($arg),*),
+            PixelFormat::Bgra32 => $func::<PixelBufBGRA32, $icp, _, $pa>($($arg),*),
+            PixelFormat::Argb32 => $func::<PixelBufARGB32, $icp, _, $pa>($($arg),*),
+            PixelFormat::Luma8 => $func::<PixelBufLuma8, $icp, _, $pa>($($arg),*),
+            PixelFormat::Mono8 => $func::<PixelBufMono8, $icp, _, $pa>($($arg),*),
+            PixelFormat::Rgb332 => $func::<PixelBufRGB332, $icp, _, $pa>($($arg),*),
+            PixelFormat::Rgb555 => $func::<PixelBufRGB555, $icp, _, $pa>($($arg),*),
+            PixelFormat::Rgb16Le => $func::<PixelBufRGB16LE, $icp, _, $pa>($($arg),*),
+            PixelFormat::Rgb16 => $func::<PixelBufRGB16, $icp, _, $pa>($($arg),*),
+            PixelFormat::Rgb16x2 => $func::<PixelBufRGB16x2, $icp, _, $pa>($($arg),*),
+        }
+    };
+}
+
+/// Renders the part of the plasma into the provided `buffer` without the [Plasma] instance
+/// with the `mixer` and the pixel `format` selected at runtime.
+///
+/// See [render_part].
+///
+/// # Panics
+///
+/// __Panics__ if [PhaseAmpsSelect::select] panics.
+#[allow(clippy::too_many_arguments)]
+pub fn render_part_dispatch<'a, P>(mixer: MixerKind, format: PixelFormat, buffer: &mut [u8], pitch: usize, pw: usize,
+                                   ph: usize, phase_amps: &'a P, x: usize, y: usize, w: usize, h: usize,
+                                   wrkspc: Option<&mut Vec<u8>>)
+    where P: PhaseAmpsSelect<'a> + ?Sized
+{
+    dispatch!(render_part_with, render_part_terms::<P>(mixer, format, [])(buffer, pitch, pw, ph, phase_amps, x, y, w,
+                                                                          h, wrkspc))
+}
+
+/// Renders the part of the plasma into the provided `buffer` without the [Plasma] instance
+/// with the `mixer` and the pixel `format` selected at runtime.
+///
+/// This is a fallible variant of [render_part_dispatch], see [try_render_part] for the list of errors.
+#[allow(clippy::too_many_arguments)]
+pub fn try_render_part_dispatch<'a, P>(mixer: MixerKind, format: PixelFormat, buffer: &mut [u8], pitch: usize,
+                                       pw: usize, ph: usize, phase_amps: &'a P, x: usize, y: usize, w: usize,
+                                       h: usize, wrkspc: Option<&mut Vec<u8>>)
+                                       -> Result<(), PlasmaError>
+    where P: PhaseAmpsSelect<'a> + ?Sized
+{
+    dispatch!(try_render_part_with, try_render_part_terms::<P>(mixer, format, [])(buffer, pitch, pw, ph, phase_amps,
+                                                                                  x, y, w, h, wrkspc))
+}
+
+/// Renders the part of the plasma into the provided `buffer` without the [Plasma] instance
+/// with the `mixer` and the pixel `format` selected at runtime and the color adjustments.
+///
+/// The pixels of the mixer are transformed with the [AdjustedMixer] unless the `adjust` is
+/// the [ColorAdjust::IDENTITY]. See [render_part_dispatch].
+///
+/// # Panics
+///
+/// __Panics__ if [PhaseAmpsSelect::select] panics.
+#[allow(clippy::too_many_arguments)]
+pub fn render_part_dispatch_adjusted<'a, P>(mixer: MixerKind, format: PixelFormat, adjust: &ColorAdjust,
+                                            buffer: &mut [u8], pitch: usize, pw: usize, ph: usize,
+                                            phase_amps: &'a P, x: usize, y: usize, w: usize, h: usize,
+                                            wrkspc: Option<&mut Vec<u8>>)
+    where P: PhaseAmpsSelect<'a> + ?Sized
+{
+    if adjust.is_identity() {
+        return render_part_dispatch(mixer, format, buffer, pitch, pw, ph, phase_amps, x, y, w, h, wrkspc);
+    }
+    dispatch!(render_part_with, render_part_terms::<P>(mixer, format, [*adjust])(buffer, pitch, pw, ph, phase_amps,
+                                                                                 x, y, w, h, wrkspc))
+}
+
+/// Renders the part of the plasma into the provided `buffer` without the [Plasma] instance
+/// with the `mixer` and the pixel `format` selected at runtime and the color adjustments.
+///
+/// This is a fallible variant of [render_part_dispatch_adjusted], see [try_render_part] for the list of errors.
+#[allow(clippy::too_many_arguments)]
+pub fn try_render_part_dispatch_adjusted<'a, P>(mixer: MixerKind, format: PixelFormat, adjust: &ColorAdjust,
+                                                buffer: &mut [u8], pitch: usize, pw: usize, ph: usize,
+                                                phase_amps: &'a P, x: usize, y: usize, w: usize, h: usize,
+                                                wrkspc: Option<&mut Vec<u8>>)
+                                                -> Result<(), PlasmaError>
+    where P: PhaseAmpsSelect<'a> + ?Sized
+{
+    if adjust.is_identity() {
+        return try_render_part_dispatch(mixer, format, buffer, pitch, pw, ph, phase_amps, x, y, w, h, wrkspc);
+    }
+    dispatch!(try_render_part_with, try_render_part_terms::<P>(mixer, format, [*adjust])(buffer, pitch, pw, ph,
+                                                                                         phase_amps, x, y, w, h,
+                                                                                         wrkspc))
+}
+
+/// Renders the part of the plasma into the provided `buffer` without the [Plasma] instance
+/// with the expression `mixer`, the pixel `format` selected at runtime and the color adjustments.
+///
+/// This is the [MixerKind::Expr] counterpart of [render_part_dispatch_adjusted] for any expression.
+///
+/// # Panics
+///
+/// __Panics__ if [PhaseAmpsSelect::select] panics.
+#[cfg(feature = "expr")]
+#[allow(clippy::too_many_arguments)]
+pub fn render_part_dispatch_expr<'a, P>(mixer: &ExprMixer, format: PixelFormat, adjust: &ColorAdjust,
+                                        buffer: &mut [u8], pitch: usize, pw: usize, ph: usize, phase_amps: &'a P,
+                                        x: usize, y: usize, w: usize, h: usize, wrkspc: Option<&mut Vec<u8>>)
+    where P: PhaseAmpsSelect<'a> + ?Sized
+{
+    if adjust.is_identity() {
+        dispatch_format!(render_part_with::<PlasmaInterCalcProducer<P>, P>(format)(mixer, buffer, pitch, pw, ph,
+                                                                                   phase_amps, x, y, w, h, wrkspc))
+    }
+    else {
+        let mixer = &AdjustedMixer::new(mixer, *adjust);
+        dispatch_format!(render_part_with::<PlasmaInterCalcProducer<P>, P>(format)(mixer, buffer, pitch, pw, ph,
+                                                                                   phase_amps, x, y, w, h, wrkspc))
+    }
+}
+
+/// Renders the part of the plasma into the provided `buffer` without the [Plasma] instance
+/// with the expression `mixer`, the pixel `format` selected at runtime and the color adjustments.
+///
+/// This is a fallible variant of [render_part_dispatch_expr], see [try_render_part] for the list of errors.
+#[cfg(feature = "expr")]
+#[allow(clippy::too_many_arguments)]
+pub fn try_render_part_dispatch_expr<'a, P>(mixer: &ExprMixer, format: PixelFormat, adjust: &ColorAdjust,
+                                            buffer: &mut [u8], pitch: usize, pw: usize, ph: usize,
+                                            phase_amps: &'a P, x: usize, y: usize, w: usize, h: usize,
+                                            wrkspc: Option<&mut Vec<u8>>)
+                                            -> Result<(), PlasmaError>
+    where P: PhaseAmpsSelect<'a> + ?Sized
+{
+    if adjust.is_identity() {
+        dispatch_format!(try_render_part_with::<PlasmaInterCalcProducer<P>, P>(format)(mixer, buffer, pitch, pw, ph,
+                                                                                       phase_amps, x, y, w, h,
+                                                                                       wrkspc))
+    }
+    else {
+        let mixer = &AdjustedMixer::new(mixer, *adjust);
+        dispatch_format!(try_render_part_with::<PlasmaInterCalcProducer<P>, P>(format)(mixer, buffer, pitch, pw, ph,
+                                                                                       phase_amps, x, y, w, h,
+                                                                                       wrkspc))
+    }
+}
+
+impl Plasma {
+    /// Renders the plasma into the provided `buffer` with the `mixer` and the pixel `format`
+    /// selected at runtime.
+    ///
+    /// See [Plasma::render].
+    pub fn render_dispatch(&self, mixer: MixerKind, format: PixelFormat, buffer: &mut [u8], pitch: usize,
+                           wrkspc: Option<&mut Vec<u8>>) {
+        let (pw, ph) = (self.pixel_width as usize, self.pixel_height as usize);
+        render_part_dispatch(mixer, format, buffer, pitch, pw, ph, &self.phase_amps[..], 0, 0, pw, ph, wrkspc)
+    }
+
+    /// Renders the part of the plasma into the provided `buffer` with the `mixer` and the pixel
+    /// `format` selected at runtime.
+    ///
+    /// See [Plasma::render_part].
+    #[allow(clippy::too_many_arguments)]
+    pub fn render_part_dispatch(&self, mixer: MixerKind, format: PixelFormat, buffer: &mut [u8], pitch: usize,
+                                x: usize, y: usize, w: usize, h: usize, wrkspc: Option<&mut Vec<u8>>) {
+        let (pw, ph) = (self.pixel_width as usize, self.pixel_height as usize);
+        render_part_dispatch(mixer, format, buffer, pitch, pw, ph, &self.phase_amps[..], x, y, w, h, wrkspc)
+    }
+
+    /// Renders the part of the plasma into the provided `buffer` with the `mixer` and the pixel
+    /// `format` selected at runtime.
+    ///
+    /// This is a fallible variant of [Plasma::render_part_dispatch], see [try_render_part] for the list of errors.
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_render_part_dispatch(&self, mixer: MixerKind, format: PixelFormat, buffer: &mut [u8], pitch: usize,
+                                    x: usize, y: usize, w: usize, h: usize, wrkspc: Option<&mut Vec<u8>>)
+                                    -> Result<(), PlasmaError> {
+        let (pw, ph) = (self.pixel_width as usize, self.pixel_height as usize);
+        try_render_part_dispatch(mixer, format, buffer, pitch, pw, ph, &self.phase_amps[..], x, y, w, h, wrkspc)
+    }
+
+    /// Renders the part of the plasma into the provided `buffer` with the `mixer` and the pixel
+    /// `format` selected at runtime and the color adjustments.
+    ///
+    /// See [render_part_dispatch_adjusted].
+    #[allow(clippy::too_many_arguments)]
+    pub fn render_part_dispatch_adjusted(&self, mixer: MixerKind, format: PixelFormat, adjust: &ColorAdjust,
+                                         buffer: &mut [u8], pitch: usize, x: usize, y: usize, w: usize, h: usize,
+                                         wrkspc: Option<&mut Vec<u8>>) {
+        let (pw, ph) = (self.pixel_width as usize, self.pixel_height as usize);
+        let phase_amps = &self.phase_amps[..];
+        render_part_dispatch_adjusted(mixer, format, adjust, buffer, pitch, pw, ph, phase_amps, x, y, w, h, wrkspc)
+    }
+
+    /// Renders the part of the plasma into the provided `buffer` with the `mixer` and the pixel
+    /// `format` selected at runtime and the color adjustments.
+    ///
+    /// This is a fallible variant of [Plasma::render_part_dispatch_adjusted], see [try_render_part] for the list
+    /// of errors.
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_render_part_dispatch_adjusted(&self, mixer: MixerKind, format: PixelFormat, adjust: &ColorAdjust,
+                                             buffer: &mut [u8], pitch: usize, x: usize, y: usize, w: usize,
+                                             h: usize, wrkspc: Option<&mut Vec<u8>>)
+                                             -> Result<(), PlasmaError> {
+        let (pw, ph) = (self.pixel_width as usize, self.pixel_height as usize);
+        let phase_amps = &self.phase_amps[..];
+        try_render_part_dispatch_adjusted(mixer, format, adjust, buffer, pitch, pw, ph, phase_amps, x, y, w, h, wrkspc)
+    }
+
+    /// Renders the part of the plasma into the provided `buffer` with the expression `mixer`, the pixel
+    /// `format` selected at runtime and the color adjustments.
+    ///
+    /// See [render_part_dispatch_expr].
+    #[cfg(feature = "expr")]
+    #[allow(clippy::too_many_arguments)]
+    pub fn render_part_dispatch_expr(&self, mixer: &ExprMixer, format: PixelFormat, adjust: &ColorAdjust,
+                                     buffer: &mut [u8], pitch: usize, x: usize, y: usize, w: usize, h: usize,
+                                     wrkspc: Option<&mut Vec<u8>>) {
+        let (pw, ph) = (self.pixel_width as usize, self.pixel_height as usize);
+        let phase_amps = &self.phase_amps[..];
+        render_part_dispatch_expr(mixer, format, adjust, buffer, pitch, pw, ph, phase_amps, x, y, w, h, wrkspc)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dispatch::*;
+
+    #[test]
+    fn dispatch_works() {
+        for &mixer in MixerKind::ALL {
+            assert_eq!(mixer.to_string().parse(), Ok(mixer));
+        }
+        for &format in PixelFormat::ALL {
+            assert_eq!(format.to_string().parse(), Ok(format));
+        }
+        assert_eq!("plasma2".parse::<MixerKind>(), Err(PlasmaError::UnknownName));
+        assert_eq!(MixerKind::ALL[MixerKind::ALL.len() - 1].next(), MixerKind::Plasma);
+        assert_eq!(PixelFormat::Rgb24.next(), PixelFormat::Rgba32);
+
+        let (mut plasma, mut rng) = Plasma::new_seeded(40, 30, PhaseAmpCfg::default(), 5);
+        let format = PixelFormat::Rgba32;
+        let pitch = 40 * format.pixel_bytes();
+        let (mut buffer, mut expected) = (vec![0u8; pitch * 30], vec![0u8; pitch * 30]);
+        #[cfg(feature = "expr")]
+        let expr: ExprMixer = DEFAULT_EXPR.parse().unwrap();
+        for &mixer in MixerKind::ALL {
+            plasma.set_phase_amps_len(mixer.phase_amps_len(), &mut rng);
+            plasma.try_render_part_dispatch(mixer, format, &mut buffer, pitch, 0, 0, 40, 30, None).unwrap();
+            match mixer {
+                MixerKind::Plasma => plasma.render::<PixelBufRGBA32, PlasmaICP, PlasmaMixer>(&mut expected, pitch, None),
+                MixerKind::Overtones => {
+                    plasma.render::<PixelBufRGBA32, PlasmaHarmonicsICP<Overtones>, PlasmaMixer>(&mut expected,
+                                                                                                 pitch,
+                                                                                                 None)
+                }
+                MixerKind::Oklch => {
+                    plasma.render::<PixelBufSrgb<PixelBufRGBA32>, PlasmaICP, PlasmaOklchMixer>(&mut expected,
+                                                                                               pitch,
+                                                                                               None)
+                }
+                MixerKind::Monochrome => {
+                    plasma.render::<PixelBufRGBA32, MonochromeICP, MonochromeMixer>(&mut expected, pitch, None)
+                }
+                MixerKind::RgbChannels => {
+                    plasma.render::<PixelBufRGBA32, RgbChannelsICP, RgbChannelsMixer>(&mut expected, pitch, None)
+                }
+                MixerKind::Contour => plasma.render::<PixelBufRGBA32, ContourICP, ContourMixer>(&mut expected, pitch, None),
+                MixerKind::LavaLamp => {
+                    plasma.render::<PixelBufRGBA32, LavaLampICP, LavaLampMixer>(&mut expected, pitch, None)
+                }
+                MixerKind::Radial => {
+                    plasma.render_terms::<PixelBufRGBA32, PlasmaICP, PixelWaveTCP, _>(&RadialMixer,
+                                                                                      &mut expected,
+                                                                                      pitch,
+                                                                                      None)
+                }
+                #[cfg(feature = "expr")]
+                MixerKind::Expr => {
+                    plasma.render_with::<PixelBufRGBA32, PlasmaICP, _>(&expr, &mut expected, pitch, None)
+                }
+            }
+            assert_eq!(buffer, expected, "{}", mixer);
+        }
+        let mixer = MixerKind::Radial;
+        plasma.set_phase_amps_len(mixer.phase_amps_len(), &mut rng);
+        let adjust = ColorAdjust { saturation: 0.5, hue: 0.25, ..ColorAdjust::IDENTITY };
+        plasma.try_render_part_dispatch_adjusted(mixer, format, &adjust, &mut buffer, pitch, 0, 0, 40, 30, None)
+              .unwrap();
+        plasma.render_terms::<PixelBufRGBA32, PlasmaICP, PixelWaveTCP, _>(&AdjustedMixer::new(RadialMixer, adjust),
+                                                                          &mut expected,
+                                                                          pitch,
+                                                                          None);
+        assert_eq!(buffer, expected);
+        assert!(plasma.try_render_part_dispatch(MixerKind::Plasma, format, &mut buffer, pitch, 0, 0, 40, 30, None)
+                      .is_err());
+    }
+
+    #[cfg(feature = "expr")]
+    #[test]
+    fn dispatch_expr_works() {
+        let (mut plasma, mut rng) = Plasma::new_seeded(40, 30, PhaseAmpCfg::default(), 5);
+        plasma.set_phase_amps_len(MixerKind::Expr.phase_amps_len(), &mut rng);
+        let format = PixelFormat::Bgr24;
+        let pitch = 40 * format.pixel_bytes();
+        let (mut buffer, mut expected) = (vec![0u8; pitch * 30], vec![0u8; pitch * 30]);
+        let mut mixer: ExprMixer = "rgb(x0 * y0, abs(x1 - y1), t)".parse().unwrap();
+        mixer.set_time(0.5);
+        let adjust = ColorAdjust { contrast: 1.5, ..ColorAdjust::IDENTITY };
+        plasma.render_part_dispatch_expr(&mixer, format, &adjust, &mut buffer, pitch, 0, 0, 40, 30, None);
+        plasma.render_with::<PixelBufBGR24, PlasmaICP, _>(&AdjustedMixer::new(&mixer, adjust), &mut expected, pitch,
+                                                          None);
+        assert_eq!(buffer, expected);
+        let (pw, ph) = (plasma.pixel_width as usize, plasma.pixel_height as usize);
+        let phase_amps = &plasma.phase_amps[..20];
+        assert_eq!(try_render_part_dispatch_expr(&mixer, format, &adjust, &mut buffer, pitch, pw, ph, phase_amps, 0,
+                                                 0, 40, 30, None),
+                   Err(PlasmaError::PhaseAmpsLength { expected: 24, actual: 20 }));
+    }
+}
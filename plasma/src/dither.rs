@@ -0,0 +1,285 @@
+//! Dithering of the pixels rendered into the buffers of the low bit depth.
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+use core::cmp::min;
+
+use crate::{error::*, mixer::*, phase_amp::*, pixel_buffer::*, plasma::*, simd_polyfill::*};
+
+/// The methods of reducing the banding when quantizing the color components for a [DitherPixelBuffer].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Dither {
+    /// No dithering, the components are rounded to the nearest quantized value.
+    #[default]
+    None,
+    /// The ordered dithering with the 8x8 Bayer matrix.
+    Bayer,
+    /// The ordered dithering with the 16x16 blue noise mask, less regular than [Dither::Bayer].
+    BlueNoise,
+    /// The Floyd–Steinberg error diffusion.
+    ///
+    /// The error is diffused only within the rendered area, so the results of rendering in parts differ
+    /// at the boundaries of the parts.
+    FloydSteinberg,
+}
+
+/// The side of the [BAYER] matrix.
+const BAYER_SIZE: usize = 8;
+/// The thresholds of the ordered dithering in the range `[0, 64)`.
+#[rustfmt::skip]
+static BAYER: [u8; BAYER_SIZE * BAYER_SIZE] = [
+     0, 32,  8, 40,  2, 34, 10, 42,
+    48, 16, 56, 24, 50, 18, 58, 26,
+    12, 44,  4, 36, 14, 46,  6, 38,
+    60, 28, 52, 20, 62, 30, 54, 22,
+     3, 35, 11, 43,  1, 33,  9, 41,
+    51, 19, 59, 27, 49, 17, 57, 25,
+    15, 47,  7, 39, 13, 45,  5, 37,
+    63, 31, 55, 23, 61, 29, 53, 21,
+];
+
+/// The side of the [BLUE_NOISE] mask.
+const BLUE_NOISE_SIZE: usize = 16;
+/// The thresholds of the ordered dithering in the range `[0, 256)`, generated with the void-and-cluster method.
+#[rustfmt::skip]
+static BLUE_NOISE: [u8; BLUE_NOISE_SIZE * BLUE_NOISE_SIZE] = [
+    252, 131,  58,  10, 227, 146, 191,  81,  40, 204, 106,  29, 229,  42, 164,  66,
+     16, 215,  34, 240,  94,  43, 109, 166,  12,  69, 213, 132,  77, 114,  22, 148,
+     93, 167, 113, 177,  65, 210, 248, 141, 232, 186,  47, 153, 180, 239, 208, 190,
+     46,  75, 202, 135, 157,   3, 124,  24,  88, 119, 245,  98,   2,  56, 138, 105,
+    224,   6, 235,  25,  80, 195,  50, 222,  60, 161,  17, 194, 218,  82,  35, 246,
+    121, 145,  54,  97, 254, 181, 102, 172, 205,  33, 144,  70, 125, 170, 155, 183,
+     28, 192, 168, 129, 217,  37, 150,  74, 241, 111, 228,  44, 255, 100,  11,  67,
+    221, 107, 209,  14,  63, 118,  20, 130,   7,  92, 178, 137,  23, 206, 233,  89,
+    136,  76,  41, 158,  86, 244, 225, 187, 156,  55, 214,  79, 189, 116,  51, 162,
+    250,   1, 238, 185, 203, 140,  48,  99, 199,  30, 163,   5,  64, 149,  36, 198,
+    173,  95,  57, 110,  31, 175,  13,  68, 251, 123, 231, 108, 243, 219, 127,  18,
+    112, 230, 151, 128,  78, 234, 115, 216,  84, 142,  45, 169,  96, 182,  83,  61,
+    212,  27, 188,   8, 211, 165,  38, 152, 184,  21,  72, 207,  32,  15, 247, 159,
+     73, 139,  49, 249,  90,  59, 133, 103,   0, 196, 237, 117, 134,  52, 143, 201,
+     39, 226, 104, 171,  19, 200, 242, 223,  53,  91, 160,  62, 220, 193, 101,   4,
+    179,  85, 197, 154, 120,  71,  26, 174, 126, 253, 147,   9, 176,  87, 236, 122,
+];
+
+/// The fractions of the quantization error diffused to the pixels: right, below left, below and below right.
+const FLOYD_STEINBERG: [f32; 4] = [7.0 / 16.0, 3.0 / 16.0, 5.0 / 16.0, 1.0 / 16.0];
+
+impl Dither {
+    /// All of the dithering methods.
+    pub const ALL: &'static [Dither] = &[Dither::None, Dither::Bayer, Dither::BlueNoise, Dither::FloydSteinberg];
+
+    /// Returns the threshold of the ordered dithering in the range `(0, 1)` at the absolute pixel coordinates.
+    #[inline]
+    fn threshold(self, x: usize, y: usize) -> f32 {
+        match self {
+            Dither::Bayer => {
+                let rank = BAYER[y % BAYER_SIZE * BAYER_SIZE + x % BAYER_SIZE];
+                (rank as f32 + 0.5) / BAYER.len() as f32
+            },
+            Dither::BlueNoise => {
+                let rank = BLUE_NOISE[y % BLUE_NOISE_SIZE * BLUE_NOISE_SIZE + x % BLUE_NOISE_SIZE];
+                (rank as f32 + 0.5) / BLUE_NOISE.len() as f32
+            },
+            Dither::None | Dither::FloydSteinberg => 0.5,
+        }
+    }
+}
+
+/// Returns the components of the pixel scaled to the range of the quantized values of the buffer `B`.
+#[inline]
+fn scaled_components<B: DitherPixelBuffer>([r, g, b]: [f32; 3]) -> [f32; 3] {
+    match B::MAX_VALUES {
+        QuantizedMax::Luma(luma) => [(r * 0.2126 + g * 0.7152 + b * 0.0722) * luma as f32, 0.0, 0.0],
+        QuantizedMax::Rgb([rmax, gmax, bmax]) => [r * rmax as f32, g * gmax as f32, b * bmax as f32],
+    }
+}
+
+/// Renders the part of the plasma into the provided `buffer` of the low bit depth without the [Plasma] instance
+/// with the provided `mixer` instance, quantizing the color components with the `dither` method.
+///
+/// The ordered dithering methods depend only on the absolute pixel coordinates, so the plasma may be rendered
+/// in parts, e.g. in tiles, without the visible seams. See [render_part_with] for the description of the other
+/// arguments.
+///
+/// Unlike the [PixelBuffer::put_pixel] of the buffers, which truncates the components, the quantization rounds
+/// them to the nearest value. The color components are clamped like with the 8-bit [PixelBuffer]s.
+///
+/// # Panics
+///
+/// __Panics__ if the buffer is too small or if [PhaseAmpsSelect::select] panics.
+#[allow(clippy::too_many_arguments)]
+pub fn render_part_dithered<'a, B, L, M, P>(mixer: &M, dither: Dither, buffer: &mut [u8], pitch: usize, pw: usize,
+                                            ph: usize, phase_amps: &'a P, x: usize, y: usize, w: usize, h: usize,
+                                            wrkspc: Option<&mut Vec<u8>>)
+    where B: DitherPixelBuffer,
+          L: IntermediateCalculatorProducer<'a, P, Flt>,
+          M: Mixer<Flt>,
+          P: PhaseAmpsSelect<'a> + ?Sized
+{
+    if x >= pw || y >= ph {
+        return;
+    }
+    let channels = B::MAX_VALUES.values().len();
+    /* the errors of the current and the next line, with a guard pixel on both ends */
+    let error_len = if dither == Dither::FloydSteinberg { min(pw, x + w) - x + 2 } else { 0 };
+    let (mut errors, mut next_errors) = (vec![[0.0f32; 3]; error_len], vec![[0.0f32; 3]; error_len]);
+    render_float_lines::<L, M, P, _>(mixer, pw, ph, phase_amps, x, y, w, h, wrkspc, 1, |lines, row| {
+        for line in 0..lines.height {
+            let y = y + row + line;
+            let mut writer = buffer[(row + line) * pitch..][..lines.width * B::PIXEL_BYTES].iter_mut();
+            for col in 0..lines.width {
+                let mut values = scaled_components::<B>(lines.rgb(line, col));
+                if dither == Dither::FloydSteinberg {
+                    for (value, error) in values.iter_mut().zip(errors[col + 1]) {
+                        *value += error;
+                    }
+                }
+                let threshold = dither.threshold(x + col, y);
+                let mut quantized = [0u8; 3];
+                for ((q, value), &max) in quantized.iter_mut().zip(values).zip(B::MAX_VALUES.values()) {
+                    // this is saturating conversion
+                    *q = min((value + threshold) as u8, max);
+                }
+                if dither == Dither::FloydSteinberg {
+                    let [right, below_left, below, below_right] = FLOYD_STEINBERG;
+                    for (n, (&q, value)) in quantized.iter().zip(values).enumerate().take(channels) {
+                        let error = value - q as f32;
+                        errors[col + 2][n] += error * right;
+                        next_errors[col][n] += error * below_left;
+                        next_errors[col + 1][n] += error * below;
+                        next_errors[col + 2][n] += error * below_right;
+                    }
+                }
+                B::put_quantized(&mut writer, &quantized[..channels]);
+            }
+            if dither == Dither::FloydSteinberg {
+                core::mem::swap(&mut errors, &mut next_errors);
+                next_errors.fill([0.0; 3]);
+            }
+        }
+    });
+}
+
+/// Renders the part of the plasma into the provided `buffer` of the low bit depth without the [Plasma] instance
+/// with the provided `mixer` instance, quantizing the color components with the `dither` method.
+///
+/// This is a fallible variant of [render_part_dithered], see [try_render_part] for the list of errors.
+#[allow(clippy::too_many_arguments)]
+pub fn try_render_part_dithered<'a, B, L, M, P>(mixer: &M, dither: Dither, buffer: &mut [u8], pitch: usize,
+                                                pw: usize, ph: usize, phase_amps: &'a P, x: usize, y: usize, w: usize,
+                                                h: usize, wrkspc: Option<&mut Vec<u8>>)
+                                                -> Result<(), PlasmaError>
+    where B: DitherPixelBuffer,
+          L: IntermediateCalculatorProducer<'a, P, Flt>,
+          M: Mixer<Flt>,
+          P: PhaseAmpsSelect<'a> + ?Sized
+{
+    check_area(pw, ph, x, y, w, h)?;
+    check_phase_amps::<L, M, P>(phase_amps)?;
+    check_buffer::<B>(buffer, pitch, w, h)?;
+    render_part_dithered::<B, L, M, P>(mixer, dither, buffer, pitch, pw, ph, phase_amps, x, y, w, h, wrkspc);
+    Ok(())
+}
+
+impl Plasma {
+    /// Renders the part of the plasma into the provided `buffer` of the low bit depth with the provided `mixer`
+    /// instance, quantizing the color components with the `dither` method.
+    ///
+    /// See [render_part_dithered].
+    #[allow(clippy::too_many_arguments)]
+    pub fn render_part_dithered<'a, B, L, M>(&'a self, mixer: &M, dither: Dither, buffer: &mut [u8], pitch: usize,
+                                             x: usize, y: usize, w: usize, h: usize, wrkspc: Option<&mut Vec<u8>>)
+        where B: DitherPixelBuffer,
+              L: ICProducer<'a>,
+              M: Mixer<Flt>
+    {
+        let (pw, ph) = (self.pixel_width as usize, self.pixel_height as usize);
+        render_part_dithered::<B, L, M, _>(mixer, dither, buffer, pitch, pw, ph, &self.phase_amps[..], x, y, w, h,
+                                           wrkspc)
+    }
+
+    /// Renders the part of the plasma into the provided `buffer` of the low bit depth with the provided `mixer`
+    /// instance, quantizing the color components with the `dither` method.
+    ///
+    /// This is a fallible variant of [Plasma::render_part_dithered], see [try_render_part] for the list of errors.
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_render_part_dithered<'a, B, L, M>(&'a self, mixer: &M, dither: Dither, buffer: &mut [u8],
+                                                 pitch: usize, x: usize, y: usize, w: usize, h: usize,
+                                                 wrkspc: Option<&mut Vec<u8>>)
+                                                 -> Result<(), PlasmaError>
+        where B: DitherPixelBuffer,
+              L: ICProducer<'a>,
+              M: Mixer<Flt>
+    {
+        let (pw, ph) = (self.pixel_width as usize, self.pixel_height as usize);
+        try_render_part_dithered::<B, L, M, _>(mixer, dither, buffer, pitch, pw, ph, &self.phase_amps[..], x, y, w,
+                                               h, wrkspc)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{dither::*, *};
+
+    const W: usize = 37;
+    const H: usize = 19;
+
+    fn render<B: DitherPixelBuffer>(plasma: &Plasma, mixer: &impl Mixer<Flt>, dither: Dither,
+                                    areas: &[(usize, usize, usize, usize)])
+                                    -> Vec<u8> {
+        let pitch = W * B::PIXEL_BYTES;
+        /* an extra line, so the tiles can be rendered at the buffer offsets */
+        let mut buffer = vec![0u8; pitch * (H + 1)];
+        for &(x, y, w, h) in areas {
+            let buffer = &mut buffer[y * pitch + x * B::PIXEL_BYTES..];
+            plasma.try_render_part_dithered::<B, PlasmaICP, _>(mixer, dither, buffer, pitch, x, y, w, h, None)
+                  .unwrap();
+        }
+        buffer.truncate(pitch * H);
+        buffer
+    }
+
+    #[test]
+    fn dither_works() {
+        let (plasma, _) = Plasma::new_seeded(W as u32, H as u32, PhaseAmpCfg::default(), 9);
+        let whole = [(0, 0, W, H)];
+        let tiles = [(0, 0, 16, 5), (16, 0, 21, 5), (0, 5, 11, H - 5), (11, 5, W - 11, H - 5)];
+        for &dither in Dither::ALL {
+            let expected = render::<PixelBufRGB16>(&plasma, &PlasmaMixer, dither, &whole);
+            if dither != Dither::FloydSteinberg {
+                assert_eq!(render::<PixelBufRGB16>(&plasma, &PlasmaMixer, dither, &tiles), expected, "{:?}", dither);
+            }
+            let rgb16le = render::<PixelBufRGB16LE>(&plasma, &PlasmaMixer, dither, &whole);
+            let swapped: Vec<u8> = expected.chunks_exact(2).flat_map(|rgb| [rgb[1], rgb[0]]).collect();
+            assert_eq!(rgb16le, swapped);
+            let rgb16x2 = render::<PixelBufRGB16x2>(&plasma, &PlasmaMixer, dither, &whole);
+            let doubled: Vec<u8> = expected.chunks_exact(2).flat_map(|rgb| [rgb[0], rgb[1], rgb[0], rgb[1]]).collect();
+            assert_eq!(rgb16x2, doubled);
+        }
+        let plain = render::<PixelBufLuma8>(&plasma, &PlasmaMixer, Dither::None, &whole);
+        let mut buffer = vec![0u8; W * H];
+        plasma.render::<PixelBufLuma8, PlasmaICP, PlasmaMixer>(&mut buffer, W, None);
+        assert!(plain.iter().zip(buffer).all(|(a, b)| a.abs_diff(b) <= 1));
+        let mono = render::<PixelBufMono8>(&plasma, &PlasmaMixer, Dither::Bayer, &whole);
+        assert!(mono.iter().all(|&v| v == 0 || v == 255));
+        assert!(mono.contains(&0) && mono.contains(&255));
+    }
+
+    #[test]
+    fn dither_preserves_mean() {
+        let (plasma, _) = Plasma::new_seeded(W as u32, H as u32, PhaseAmpCfg::default(), 9);
+        /* a uniform gray of 0.5 + 0.1, between the levels of all of the buffers */
+        let gray = AdjustedMixer::new(PlasmaMixer, ColorAdjust { contrast: 0.0, brightness: 0.1, ..Default::default() });
+        let mono = render::<PixelBufMono8>(&plasma, &gray, Dither::None, &[(0, 0, W, H)]);
+        assert!(mono.iter().all(|&v| v == 255));
+        let rgb332 = render::<PixelBufRGB332>(&plasma, &gray, Dither::None, &[(0, 0, W, H)]);
+        assert!(rgb332.iter().all(|&v| v >> 5 == 4));
+        for &dither in &Dither::ALL[1..] {
+            let mono = render::<PixelBufMono8>(&plasma, &gray, dither, &[(0, 0, W, H)]);
+            let mean = mono.iter().map(|&v| v as f32 / 255.0).sum::<f32>() / mono.len() as f32;
+            assert!((mean - 0.6).abs() < 0.02, "{:?}: {}", dither, mean);
+            let rgb332 = render::<PixelBufRGB332>(&plasma, &gray, dither, &[(0, 0, W, H)]);
+            let mean = rgb332.iter().map(|&v| (v >> 5) as f32 / 7.0).sum::<f32>() / rgb332.len() as f32;
+            assert!((mean - 0.6).abs() < 0.01, "{:?}: {}", dither, mean);
+        }
+    }
+}
@@ -0,0 +1,76 @@
+//! Easing curves used by the amplitude transitions and the keyframe interpolation.
+use crate::phase_amp::transform;
+
+/// The easing curve applied to the progress of a transition.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Easing {
+    /// A constant speed.
+    Linear = 0,
+    /// `3t^2 - 2t^3`: starts and stops smoothly.
+    #[default]
+    SmoothStep = 1,
+    /// `6t^5 - 15t^4 + 10t^3`: like [Easing::SmoothStep] but also with no acceleration jumps.
+    SmootherStep = 2,
+    /// `t^2`: starts slowly.
+    QuadIn = 3,
+    /// `1 - (1 - t)^2`: stops slowly.
+    QuadOut = 4,
+    /// `sin(PI/2 * t)^4`: starts very slowly and stops smoothly.
+    ///
+    /// This is the curve of the random amplitude transitions of the [PhaseAmpCfg](crate::PhaseAmpCfg) created with
+    /// [PhaseAmpCfg::new](crate::PhaseAmpCfg::new).
+    SineQuartic = 5,
+}
+
+impl Easing {
+    /// Returns the eased value of the progress `t`, clamped to the range: `[0, 1]`.
+    pub fn ease(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::SmoothStep => t * t * (3.0 - 2.0 * t),
+            Easing::SmootherStep => t * t * t * (t * (t * 6.0 - 15.0) + 10.0),
+            Easing::QuadIn => t * t,
+            Easing::QuadOut => t * (2.0 - t),
+            Easing::SineQuartic => transform(t),
+        }
+    }
+
+    /// Returns the [Easing] with the given discriminant, used by the exported state data.
+    pub(crate) fn from_index(index: u32) -> Option<Self> {
+        Some(match index {
+            0 => Easing::Linear,
+            1 => Easing::SmoothStep,
+            2 => Easing::SmootherStep,
+            3 => Easing::QuadIn,
+            4 => Easing::QuadOut,
+            5 => Easing::SineQuartic,
+            _ => return None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::easing::*;
+
+    #[test]
+    fn easing_works() {
+        for index in 0.. {
+            let easing = match Easing::from_index(index) {
+                Some(easing) => easing,
+                None => break,
+            };
+            assert_eq!(easing as u32, index);
+            assert_eq!(easing.ease(0.0), 0.0);
+            assert!((easing.ease(1.0) - 1.0).abs() < 1e-6);
+            assert_eq!(easing.ease(-1.0), 0.0);
+            assert_eq!(easing.ease(2.0), easing.ease(1.0));
+            for i in 1..=100 {
+                assert!(easing.ease(i as f32 / 100.0) > easing.ease((i - 1) as f32 / 100.0));
+            }
+        }
+        assert_eq!(Easing::SmoothStep.ease(0.5), 0.5);
+        assert_eq!(Easing::SmootherStep.ease(0.5), 0.5);
+    }
+}
@@ -0,0 +1,90 @@
+//! Errors returned by the fallible `try_` variants of the plasma methods.
+use derive_more::{Display, Error};
+
+/// An error returned by the fallible `try_` variants of the plasma methods.
+#[derive(Debug, Display, Error, Clone, Copy, PartialEq, Eq)]
+pub enum PlasmaError {
+    /// The `min_steps` is not larger than `1.0`, it's not lower than `max_steps` or `max_steps` is not finite.
+    #[display("min steps must be larger than 1.0 and lower than max steps")]
+    InvalidSteps,
+    /// The phase velocity or acceleration limit is not a positive number.
+    #[display("phase velocity and acceleration limits must be positive numbers")]
+    InvalidPhaseLimits,
+    /// The amplitude minimum is negative or it's not lower than the amplitude maximum.
+    #[display("amplitude min must not be negative and must be lower than amplitude max")]
+    InvalidAmplitudeRange,
+    /// The buffer can't hold all of the rendered lines.
+    #[display("buffer too small: {actual} bytes, at least {required} required")]
+    BufferTooSmall {
+        /// The required number of bytes.
+        required: usize,
+        /// The length of the provided buffer.
+        actual:   usize,
+    },
+    /// The pitch is smaller than the number of bytes of the rendered line.
+    #[display("pitch too small: {actual} bytes, at least {required} required")]
+    PitchTooSmall {
+        /// The required number of bytes.
+        required: usize,
+        /// The provided pitch.
+        actual:   usize,
+    },
+    /// The rendered area exceeds the plasma boundaries.
+    #[display("area out of bounds")]
+    AreaOutOfBounds,
+    /// The rendered area doesn't start at the even pixel coordinates required by the chroma subsampling.
+    #[display("area offset must be even")]
+    UnalignedArea,
+    /// The number of the phase'n'amplitude components doesn't match the one required by the mixer.
+    #[display("wrong number of phase amps: {actual}, {expected} expected")]
+    PhaseAmpsLength {
+        /// The number of components required by the mixer.
+        expected: usize,
+        /// The number of the provided components.
+        actual:   usize,
+    },
+    /// The intermediate calculator producer doesn't compose the number of values required by the mixer.
+    #[display("wrong number of intermediate values: {actual}, {expected} expected")]
+    IntermediateLength {
+        /// The number of values required by the mixer.
+        expected: usize,
+        /// The number of values composed by the producer.
+        actual:   usize,
+    },
+    /// The phase'n'amplitude data has an odd number of values, so its last component is incomplete.
+    #[display("phase'n'amplitude data length must be even: {len}")]
+    OddPhaseAmpsData {
+        /// The number of the provided values.
+        len: usize,
+    },
+    /// The keyframe time is not finite or it's not larger than the time of the previous keyframe.
+    #[display("keyframe time must be finite and larger than the time of the previous keyframe")]
+    InvalidKeyframeTime,
+    /// The keyframed animation has no keyframes.
+    #[display("no keyframes")]
+    NoKeyframes,
+    /// The gradient has no color stops or their positions are not ascending within the range `[0, 1]`.
+    #[display("gradient stops must be ascending within the range [0, 1]")]
+    InvalidGradient,
+    /// The plasma was not created in the timeline mode.
+    #[display("plasma is not in the timeline mode")]
+    NotTimeline,
+    /// The plasma was not created with the looped timeline.
+    #[display("plasma is not looped")]
+    NotLooped,
+    /// The length of the loop is not a positive finite number of steps.
+    #[display("loop steps must be a positive number")]
+    InvalidLoopSteps,
+    /// The number of the loop frames is `0`.
+    #[display("the number of frames must not be 0")]
+    InvalidFrames,
+    /// The name of a mixer or a pixel format is not recognized.
+    #[display("unknown name")]
+    UnknownName,
+    /// The expression of the `ExprMixer` is not valid.
+    #[display("invalid expression at {position}")]
+    InvalidExpression {
+        /// The byte position of the offending part of the expression.
+        position: usize,
+    },
+}
@@ -0,0 +1,245 @@
+//! Blending between the phase'n'amplitude data exported with [PhaseAmpDataExp::export_phase_amps].
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::f32::consts::PI;
+
+use crate::{easing::*, error::PlasmaError, phase_amp::*};
+
+const PI2: f32 = 2.0 * PI;
+
+/// Interpolates between two sets of exported phase'n'amplitude data and appends the result to `out`.
+///
+/// The `t` argument is the progress of the interpolation: `0.0` yields `from`, `1.0` yields `to`.
+/// The values outside the range `[0, 1]` are clamped. The `easing` is applied to the progress.
+///
+/// The phases are interpolated along the shortest arc, so the phases differing by about a full
+/// turn don't make the plasma spin. The amplitudes are interpolated linearly.
+///
+/// # Panics
+///
+/// __Panics__ if `from` and `to` differ in length or if their length is odd.
+pub fn interpolate_phase_amps(from: &[f32], to: &[f32], t: f32, easing: Easing, out: &mut Vec<f32>) {
+    if let Err(err) = try_interpolate_phase_amps(from, to, t, easing, out) {
+        panic!("{}", err);
+    }
+}
+
+/// Interpolates between two sets of exported phase'n'amplitude data and appends the result to `out`.
+///
+/// This is a fallible variant of [interpolate_phase_amps], returning [PlasmaError::PhaseAmpsLength]
+/// if `from` and `to` differ in length or [PlasmaError::OddPhaseAmpsData] if their length is odd,
+/// instead of panicking. Nothing is appended to `out` on error.
+pub fn try_interpolate_phase_amps(from: &[f32], to: &[f32], t: f32, easing: Easing, out: &mut Vec<f32>)
+                                  -> Result<(), PlasmaError> {
+    check_phase_amps_data(from)?;
+    check_phase_amps_data(to)?;
+    if from.len() != to.len() {
+        return Err(PlasmaError::PhaseAmpsLength { expected: from.len() / 2, actual: to.len() / 2 });
+    }
+    let t = easing.ease(t);
+    out.reserve_exact(from.len());
+    for (a, b) in from.chunks_exact(2).zip(to.chunks_exact(2)) {
+        let delta_phase = match (b.phase() - a.phase()) % PI2 {
+            d if d > PI => d - PI2,
+            d if d < -PI => d + PI2,
+            d => d,
+        };
+        let amplitude = a.amplitude() + (b.amplitude() - a.amplitude()) * t;
+        out.extend(&[a.phase() + delta_phase * t, amplitude]);
+    }
+    Ok(())
+}
+
+#[inline]
+fn check_phase_amps_data(data: &[f32]) -> Result<(), PlasmaError> {
+    if !data.len().is_multiple_of(2) {
+        return Err(PlasmaError::OddPhaseAmpsData { len: data.len() });
+    }
+    Ok(())
+}
+
+/// A keyframed animation of the exported phase'n'amplitude data.
+///
+/// Each keyframe is a set of data exported with [PhaseAmpDataExp::export_phase_amps] placed
+/// at some point in time. The data between the keyframes is computed with [interpolate_phase_amps].
+/// The result may be rendered directly with [render_part](crate::render_part) or imported
+/// into the [Plasma](crate::Plasma) instance.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PhaseAmpKeyframes {
+    easing: Easing,
+    times:  Vec<f32>,
+    data:   Vec<f32>,
+}
+
+impl PhaseAmpKeyframes {
+    /// Creates new empty [PhaseAmpKeyframes] instance with the `easing` applied between each
+    /// pair of keyframes.
+    pub fn new(easing: Easing) -> Self { PhaseAmpKeyframes { easing, times: Vec::new(), data: Vec::new() } }
+
+    /// Appends the keyframe with the `phase_amps` data at the time `time`.
+    ///
+    /// # Panics
+    ///
+    /// __Panics__ if `time` is not finite or not larger than the time of the previous keyframe,
+    /// if the length of `phase_amps` is odd or if it differs from the data of the previous keyframes.
+    pub fn push(&mut self, time: f32, phase_amps: &[f32]) {
+        if let Err(err) = self.try_push(time, phase_amps) {
+            panic!("{}", err);
+        }
+    }
+
+    /// Appends the keyframe with the `phase_amps` data at the time `time`.
+    ///
+    /// This is a fallible variant of [PhaseAmpKeyframes::push], returning [PlasmaError::InvalidKeyframeTime],
+    /// [PlasmaError::OddPhaseAmpsData] or [PlasmaError::PhaseAmpsLength] instead of panicking.
+    /// The keyframes are left unchanged on error.
+    pub fn try_push(&mut self, time: f32, phase_amps: &[f32]) -> Result<(), PlasmaError> {
+        if !time.is_finite() || self.times.last().is_some_and(|&last| time <= last) {
+            return Err(PlasmaError::InvalidKeyframeTime);
+        }
+        check_phase_amps_data(phase_amps)?;
+        if !self.is_empty() && phase_amps.len() != self.frame_len() {
+            return Err(PlasmaError::PhaseAmpsLength { expected: self.frame_len() / 2, actual: phase_amps.len() / 2 });
+        }
+        self.times.push(time);
+        self.data.extend_from_slice(phase_amps);
+        Ok(())
+    }
+
+    /// Returns the number of keyframes.
+    #[inline]
+    pub fn len(&self) -> usize { self.times.len() }
+
+    /// Returns `true` if there are no keyframes.
+    #[inline]
+    pub fn is_empty(&self) -> bool { self.times.is_empty() }
+
+    /// Returns the time of the first and the last keyframe.
+    pub fn time_range(&self) -> Option<(f32, f32)> { Some((*self.times.first()?, *self.times.last()?)) }
+
+    /// Appends the phase'n'amplitude data at the time `t` to `out`.
+    ///
+    /// Before the first keyframe the data of the first keyframe is produced and after the
+    /// last keyframe the data of the last one.
+    ///
+    /// # Panics
+    ///
+    /// __Panics__ if there are no keyframes.
+    pub fn phase_amps_at(&self, t: f32, out: &mut Vec<f32>) {
+        if let Err(err) = self.try_phase_amps_at(t, out) {
+            panic!("{}", err);
+        }
+    }
+
+    /// Appends the phase'n'amplitude data at the time `t` to `out`.
+    ///
+    /// This is a fallible variant of [PhaseAmpKeyframes::phase_amps_at], returning [PlasmaError::NoKeyframes]
+    /// instead of panicking. Nothing is appended to `out` on error.
+    pub fn try_phase_amps_at(&self, t: f32, out: &mut Vec<f32>) -> Result<(), PlasmaError> {
+        if self.is_empty() {
+            return Err(PlasmaError::NoKeyframes);
+        }
+        let next = self.times.partition_point(|&time| time <= t);
+        if next == 0 {
+            out.extend_from_slice(self.frame(0));
+        }
+        else if next == self.len() {
+            out.extend_from_slice(self.frame(next - 1));
+        }
+        else {
+            let (t0, t1) = (self.times[next - 1], self.times[next]);
+            interpolate_phase_amps(self.frame(next - 1), self.frame(next), (t - t0) / (t1 - t0), self.easing, out);
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn frame_len(&self) -> usize { self.data.len() / self.times.len() }
+
+    #[inline]
+    fn frame(&self, index: usize) -> &[f32] {
+        let len = self.frame_len();
+        &self.data[index * len..(index + 1) * len]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::keyframes::*;
+
+    #[test]
+    fn interpolation_works() {
+        let from = [0.5, 0.0, 6.0, 1.0, 1.0, 0.25];
+        let to = [1.5, 1.0, 0.5 + PI2, 0.0, 1.0 - 2.0 * PI2, 0.75];
+        let mut out = Vec::new();
+        interpolate_phase_amps(&from, &to, 0.0, Easing::Linear, &mut out);
+        assert_eq!(out, from);
+        out.clear();
+        interpolate_phase_amps(&from, &to, 0.5, Easing::Linear, &mut out);
+        let expected = [1.0, 0.5, 6.0 + (0.5 + PI2 - 6.0) / 2.0, 0.5, 1.0, 0.5];
+        for (a, b) in out.iter().zip(expected.iter()) {
+            assert!((a - b).abs() < 1e-5, "{} != {}", a, b);
+        }
+        out.clear();
+        interpolate_phase_amps(&from, &to, 1.0, Easing::Linear, &mut out);
+        for (a, b) in out.chunks_exact(2).zip(to.chunks_exact(2)) {
+            assert!(((a[0] - b[0]) % PI2).abs() < 1e-5 || ((a[0] - b[0]) % PI2).abs() > PI2 - 1e-5);
+            assert_eq!(a[1], b[1]);
+        }
+    }
+
+    #[test]
+    fn keyframes_works() {
+        let mut keyframes = PhaseAmpKeyframes::new(Easing::Linear);
+        assert!(keyframes.is_empty());
+        assert_eq!(keyframes.time_range(), None);
+        let mut out = Vec::new();
+        assert_eq!(keyframes.try_phase_amps_at(0.0, &mut out), Err(PlasmaError::NoKeyframes));
+        assert!(out.is_empty());
+        keyframes.push(10.0, &[0.0, 0.0, 1.0, 1.0]);
+        keyframes.push(20.0, &[1.0, 1.0, 2.0, 0.0]);
+        keyframes.push(40.0, &[0.0, 0.5, 1.0, 0.5]);
+        assert_eq!(keyframes.len(), 3);
+        assert_eq!(keyframes.time_range(), Some((10.0, 40.0)));
+        let at = |t| {
+            let mut out = Vec::new();
+            keyframes.phase_amps_at(t, &mut out);
+            out
+        };
+        assert_eq!(at(0.0), [0.0, 0.0, 1.0, 1.0]);
+        assert_eq!(at(10.0), [0.0, 0.0, 1.0, 1.0]);
+        assert_eq!(at(15.0), [0.5, 0.5, 1.5, 0.5]);
+        assert_eq!(at(20.0), [1.0, 1.0, 2.0, 0.0]);
+        assert_eq!(at(30.0), [0.5, 0.75, 1.5, 0.25]);
+        assert_eq!(at(40.0), [0.0, 0.5, 1.0, 0.5]);
+        assert_eq!(at(100.0), [0.0, 0.5, 1.0, 0.5]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn keyframes_order() {
+        let mut keyframes = PhaseAmpKeyframes::new(Easing::Linear);
+        keyframes.push(10.0, &[0.0, 0.0]);
+        keyframes.push(10.0, &[0.0, 0.0]);
+    }
+
+    #[test]
+    fn keyframes_errors() {
+        let mut keyframes = PhaseAmpKeyframes::new(Easing::Linear);
+        assert_eq!(keyframes.try_push(f32::NAN, &[0.0, 0.0]), Err(PlasmaError::InvalidKeyframeTime));
+        assert_eq!(keyframes.try_push(10.0, &[0.0, 0.0, 1.0]), Err(PlasmaError::OddPhaseAmpsData { len: 3 }));
+        assert!(keyframes.is_empty());
+        assert_eq!(keyframes.try_push(10.0, &[0.0, 0.0]), Ok(()));
+        assert_eq!(keyframes.try_push(5.0, &[0.0, 0.0]), Err(PlasmaError::InvalidKeyframeTime));
+        assert_eq!(keyframes.try_push(20.0, &[0.0, 0.0, 1.0, 1.0]),
+                   Err(PlasmaError::PhaseAmpsLength { expected: 1, actual: 2 }));
+        assert_eq!(keyframes.len(), 1);
+
+        let mut out = Vec::new();
+        assert_eq!(try_interpolate_phase_amps(&[0.0, 0.0], &[0.0, 0.0, 1.0, 1.0], 0.5, Easing::Linear, &mut out),
+                   Err(PlasmaError::PhaseAmpsLength { expected: 1, actual: 2 }));
+        assert_eq!(try_interpolate_phase_amps(&[0.0], &[0.0], 0.5, Easing::Linear, &mut out),
+                   Err(PlasmaError::OddPhaseAmpsData { len: 1 }));
+        assert!(out.is_empty());
+    }
+}
@@ -38,7 +38,13 @@ compile_error!("Currently use-simd feature requires x86, x86_64 or aarch64 targe
 #[cfg(not(feature = "std"))]
 extern crate alloc;
 
+mod adjust;
 mod color;
+mod dispatch;
+mod dither;
+mod easing;
+mod error;
+mod keyframes;
 #[cfg(not(feature = "std"))]
 mod m_polyfill;
 mod mixer;
@@ -47,5 +53,9 @@ mod phase_amp;
 mod pixel_buffer;
 mod plasma;
 mod simd_polyfill;
+mod timeline;
+mod yuv;
 
-pub use crate::{color::*, mixer::*, mixers::*, phase_amp::*, pixel_buffer::*, plasma::*};
+pub use crate::{adjust::*, color::*, dispatch::*, dither::*, easing::*, error::*, keyframes::*, mixer::*, mixers::*,
+                phase_amp::*, pixel_buffer::*, plasma::*, timeline::*, yuv::*};
+pub use crate::simd_polyfill::{csplat, Flt, SimdCompat};
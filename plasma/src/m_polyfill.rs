@@ -10,7 +10,8 @@ pub trait Mf32Ext: Copy {
     fn cos(self) -> Self;
     fn sin(self) -> Self;
     fn floor(self) -> Self;
-    fn powi(self, n: u16) -> Self;
+    fn sqrt(self) -> Self;
+    fn powf(self, n: Self) -> Self;
 }
 
 #[cfg(feature = "micromath")]
@@ -19,7 +20,8 @@ impl Mf32Ext for f32 {
     fn cos(self) -> Self { F32(self).cos().into() }
     fn sin(self) -> Self { F32(self).sin().into() }
     fn floor(self) -> Self { F32(self).floor().into() }
-    fn powi(self, n: u16) -> Self { F32(self).powi(n.into()).into() }
+    fn sqrt(self) -> Self { F32(self).sqrt().into() }
+    fn powf(self, n: Self) -> Self { F32(self).powf(F32(n)).into() }
 }
 #[cfg(feature = "libm")]
 type F32 = Libm<f32>;
@@ -30,31 +32,6 @@ impl Mf32Ext for f32 {
     fn cos(self) -> Self { F32::cos(self) }
     fn sin(self) -> Self { F32::sin(self) }
     fn floor(self) -> Self { F32::floor(self) }
-    fn powi(self, mut n: u16) -> Self {
-        let mut base = self;
-        let mut result = 1.0f32;
-
-        if n == 0 {
-            return const { 1.0f32 };
-        }
-
-        // 0.0 == 0.0 and -0.0 according to IEEE standards.
-        if self == const { 0.0f32 } {
-            return self;
-        }
-
-        loop {
-            if (n & 1) == 1 {
-                result *= base;
-            }
-
-            n >>= 1;
-
-            if n == 0 {
-                return result;
-            }
-
-            base *= base;
-        }
-    }
+    fn sqrt(self) -> Self { F32::sqrt(self) }
+    fn powf(self, n: Self) -> Self { F32::pow(self, n) }
 }
@@ -20,6 +20,9 @@ pub trait IntermediateCalculator<T> {
 ///
 /// The type `T` should be a `f32` or a packed simd `f32x8` if a "use-simd" crate feature is
 /// enabled.
+///
+/// The mixer instance may hold parameters of the rendering, e.g. a color palette. Mixers without
+/// parameters should implement [Default] so they can be used with [Plasma::render](crate::Plasma::render).
 pub trait Mixer<T: Sized + Default + Copy> {
     /// This type should be an array of the type T for an intermediate horizontal data.
     type IntermediateH: Sized + Default + Copy + BorrowMut<[T]> + Borrow<[T]>;
@@ -34,11 +37,49 @@ pub trait Mixer<T: Sized + Default + Copy> {
     #[inline]
     fn intermediate_v_len() -> usize { core::mem::size_of::<Self::IntermediateV>() / core::mem::size_of::<T>() }
 
+    /// Returns the number of [PhaseAmp] components required by this mixer.
+    ///
+    /// By default each of the intermediate values is computed from a pair of components.
+    #[inline]
+    fn phase_amps_len() -> usize { 2 * (Self::intermediate_h_len() + Self::intermediate_v_len()) }
+
+    /// Returns the number of the leading [PhaseAmp] components composed into the intermediate data.
+    ///
+    /// These are all of the [Mixer::phase_amps_len] components unless the mixer is a [PixelTermsMixer]
+    /// and the remaining components are used by its per-pixel terms.
+    #[inline]
+    fn separable_phase_amps_len() -> usize { Self::phase_amps_len() }
+
     /// The implementors should compute a pixel and send it as an instance of [PixelRgb] to the
     /// provided `next_pixel` function.
     ///
     /// The computation should be based on the provided combination of intermediate data.
-    fn mix_pixels(vxp: &Self::IntermediateH, vyp: &Self::IntermediateV, next_pixel: &mut dyn FnMut(PixelRgb));
+    fn mix_pixels(&self, vxp: &Self::IntermediateH, vyp: &Self::IntermediateV, next_pixel: &mut dyn FnMut(PixelRgb));
+}
+
+impl<T, M> Mixer<T> for &M
+    where T: Sized + Default + Copy,
+          M: Mixer<T>
+{
+    type IntermediateH = M::IntermediateH;
+    type IntermediateV = M::IntermediateV;
+
+    #[inline]
+    fn intermediate_h_len() -> usize { M::intermediate_h_len() }
+
+    #[inline]
+    fn intermediate_v_len() -> usize { M::intermediate_v_len() }
+
+    #[inline]
+    fn phase_amps_len() -> usize { M::phase_amps_len() }
+
+    #[inline]
+    fn separable_phase_amps_len() -> usize { M::separable_phase_amps_len() }
+
+    #[inline]
+    fn mix_pixels(&self, vxp: &Self::IntermediateH, vyp: &Self::IntermediateV, next_pixel: &mut dyn FnMut(PixelRgb)) {
+        (**self).mix_pixels(vxp, vyp, next_pixel)
+    }
 }
 
 /// Implementations of this trait should produce an iterator of an [IntermediateCalculator] tool.
@@ -69,3 +110,63 @@ pub trait IntermediateCalculatorProducer<'a, P, T>
     /// The input data references an implementation of [PhaseAmpsSelect] tool.
     fn compose_v_iter(pa: &'a P) -> Self::CalcIterV;
 }
+
+/// Implementations of this trait should compute the per-pixel terms for a [PixelTermsMixer].
+pub trait PixelTermCalculator<T> {
+    /// Computes a per-pixel term for given horizontal and vertical angles.
+    ///
+    /// The input values are given in radians in the range: `[0, 2PI)`. Unlike the
+    /// [IntermediateCalculator] the result may depend on both of them, e.g. on `x + y`.
+    fn calculate(&self, x: T, y: T) -> T;
+}
+
+/// The maximum number of the per-pixel terms of a [PixelTermsMixer].
+pub const MAX_PIXEL_TERMS: usize = 8;
+
+/// Implementations of this trait should compute the color of each pixel based on an intermediate
+/// data created by a [IntermediateCalculator] and the per-pixel terms created by a [PixelTermCalculator].
+///
+/// The per-pixel terms are computed for each pixel separately, so they are much more expensive
+/// than the separable intermediate data.
+pub trait PixelTermsMixer<T: Sized + Default + Copy>: Mixer<T> {
+    /// This type should be an array of the type T for the per-pixel terms.
+    ///
+    /// The array must not have more than [MAX_PIXEL_TERMS] elements.
+    type IntermediateP: Sized + Default + Copy + BorrowMut<[T]> + Borrow<[T]>;
+    /// Returns the number of per-pixel terms.
+    #[inline]
+    fn intermediate_p_len() -> usize { core::mem::size_of::<Self::IntermediateP>() / core::mem::size_of::<T>() }
+    /// Returns the number of [PhaseAmp] components used by the per-pixel terms.
+    ///
+    /// These are the last components of [Mixer::phase_amps_len], the preceding
+    /// [Mixer::separable_phase_amps_len] ones are used for the separable intermediate data.
+    /// By default each of the terms is computed from a pair of components.
+    #[inline]
+    fn pixel_phase_amps_len() -> usize { 2 * Self::intermediate_p_len() }
+    /// The implementors should compute a pixel and send it as an instance of [PixelRgb] to the
+    /// provided `next_pixel` function.
+    ///
+    /// The computation should be based on the provided combination of intermediate data and
+    /// the per-pixel terms.
+    fn mix_pixels_terms(&self, vxp: &Self::IntermediateH, vyp: &Self::IntermediateV, vpp: &Self::IntermediateP,
+                        next_pixel: &mut dyn FnMut(PixelRgb));
+}
+
+/// Implementations of this trait should produce an iterator of a [PixelTermCalculator] tool.
+///
+/// The type `T` should be a `f32` or a packed simd `f32x8` if a "use-simd" crate feature is
+/// enabled.
+pub trait PixelTermCalculatorProducer<'a, P, T>
+    where P: PhaseAmpsSelect<'a> + ?Sized,
+          T: Sized + Default + Copy
+{
+    /// Provide an iterator implementation which produce [PixelTermCalculator] tools.
+    /// The iterator must be a [ExactSizeIterator] with exactly the same length as
+    /// the associated [PixelTermsMixer::IntermediateP] array's number of elements.
+    type CalcIterP: ExactSizeIterator + Iterator<Item = Self::TermCalc> + Sized;
+    /// Provide an implementation of a [PixelTermCalculator].
+    type TermCalc: PixelTermCalculator<T> + Sized;
+    /// Should return an iterator of [PixelTermCalculatorProducer::TermCalc].
+    /// The input data references the components of the per-pixel terms only.
+    fn compose_p_iter(pa: &'a P) -> Self::CalcIterP;
+}
@@ -0,0 +1,384 @@
+//! Mixer combinators blending the pixels of two plasma mixers
+use core::{borrow::{Borrow, BorrowMut}, marker::PhantomData, mem::{align_of, size_of}, slice};
+use derive_more::{Debug, Constructor};
+use crate::{color::*, mixer::*, phase_amp::*, simd_polyfill::*};
+
+/// A convenient type to be used with [crate::plasma::Plasma::render_with] or
+/// [crate::plasma::Plasma::render_part_with] with the [BlendMixer].
+///
+/// `A` is the first mixer, `LA` and `LB` are the producers of the first and the second mixer.
+pub type BlendICP<'a, A, LA, LB> = LayeredInterCalcProducer<'a, [PhaseAmp], A, LA, LB>;
+/// A convenient type to be used with [crate::plasma::render_part_with] with the [BlendMixer].
+pub type BlendICPExtPa<'a, A, LA, LB> = LayeredInterCalcProducer<'a, [f32], A, LA, LB>;
+/// A convenient type to be used with [crate::plasma::Plasma::render_with] or
+/// [crate::plasma::Plasma::render_part_with] with the [LerpMixer].
+///
+/// `A` and `B` are the blended mixers, `LA`, `LB` and `LF` are the producers of the blended mixers
+/// and of the field mixer.
+pub type LerpICP<'a, A, LA, B, LB, LF> =
+    LayeredInterCalcProducer<'a, [PhaseAmp], A, LA, LayeredInterCalcProducer<'a, [PhaseAmp], B, LB, LF>>;
+/// A convenient type to be used with [crate::plasma::render_part_with] with the [LerpMixer].
+pub type LerpICPExtPa<'a, A, LA, B, LB, LF> =
+    LayeredInterCalcProducer<'a, [f32], A, LA, LayeredInterCalcProducer<'a, [f32], B, LB, LF>>;
+
+/// The blend modes of the [BlendMixer].
+///
+/// The color components of both pixels are expected to be in the range `[0, 1]`.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum BlendMode {
+    /// The sum of the pixels.
+    #[default]
+    Add,
+    /// The product of the pixels, darkens the image.
+    Multiply,
+    /// The inverted product of the inverted pixels, lightens the image.
+    Screen,
+    /// The absolute difference of the pixels.
+    Difference,
+}
+
+/// The intermediate data of two layered mixers.
+///
+/// Both parts should be arrays of the same type, in that case the layered data can be
+/// borrowed as a single slice with the elements of the first part followed by the second one.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+#[repr(C)]
+pub struct Layered<X, Y>(pub X, pub Y);
+
+/// A [Mixer] blending the pixels of the `first` and the `second` mixer with the [BlendMode].
+///
+/// Should be rendered with the [LayeredInterCalcProducer], e.g. [BlendICP].
+/// The first [Mixer::phase_amps_len] components are used by the first mixer and the following ones
+/// by the second mixer.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Constructor)]
+pub struct BlendMixer<A, B> {
+    pub first:  A,
+    pub second: B,
+    pub mode:   BlendMode,
+}
+
+/// A [Mixer] interpolating linearly between the pixels of the `first` and the `second` mixer.
+///
+/// The interpolation factor is the average of the color components of the `field` mixer's pixel,
+/// so e.g. the [MonochromeMixer](crate::MonochromeMixer) makes a good field.
+///
+/// Should be rendered with the [LayeredInterCalcProducer], e.g. [LerpICP].
+/// The components are used by the `first`, the `second` and the `field` mixer in that order.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Constructor)]
+pub struct LerpMixer<A, B, F> {
+    pub first:  A,
+    pub second: B,
+    pub field:  F,
+}
+
+/// Either of the [IntermediateCalculator]s of two layered mixers.
+pub enum LayeredCalc<CA, CB> {
+    First(CA),
+    Second(CB),
+}
+
+/// An iterator of [LayeredCalc] producing the calculators of the first mixer followed by
+/// the calculators of the second one.
+pub struct LayeredMixIter<IA, IB> {
+    first:  IA,
+    second: IB,
+}
+
+/// Provides the [IntermediateCalculatorProducer] for two layered mixers.
+///
+/// The first [Mixer::phase_amps_len] of the mixer `A` components are given to the producer `LA`
+/// and the remaining ones to the producer `LB`.
+pub struct LayeredInterCalcProducer<'a, P: 'a + PhaseAmpsSelect<'a> + ?Sized, A, LA, LB>(PhantomData<(&'a P, A, LA, LB)>);
+
+impl BlendMode {
+    /// All of the blend modes.
+    pub const ALL: [BlendMode; 4] = [BlendMode::Add, BlendMode::Multiply, BlendMode::Screen, BlendMode::Difference];
+
+    /// Blends the pixel `b` over the pixel `a`.
+    #[inline]
+    pub fn blend(self, a: PixelRgb, b: PixelRgb) -> PixelRgb {
+        match self {
+            BlendMode::Add => a + b,
+            BlendMode::Multiply => multiply(a, b),
+            BlendMode::Screen => a + b - multiply(a, b),
+            BlendMode::Difference => PixelRgb::new((a.r - b.r).abs(), (a.g - b.g).abs(), (a.b - b.b).abs()),
+        }
+    }
+}
+
+#[inline]
+fn multiply(a: PixelRgb, b: PixelRgb) -> PixelRgb { PixelRgb::new(a.r * b.r, a.g * b.g, a.b * b.b) }
+
+/// The layout of the [Layered] parts as a single array of `T`.
+trait LayeredLayout<T> {
+    /// The number of elements of `T` in both parts.
+    ///
+    /// Fails to compile if the parts are not laid out as a single array of `T`.
+    const LEN: usize;
+}
+
+impl<T, X, Y> LayeredLayout<T> for Layered<X, Y> {
+    const LEN: usize = {
+        assert!(size_of::<T>() != 0
+                && size_of::<X>().is_multiple_of(size_of::<T>())
+                && size_of::<Y>().is_multiple_of(size_of::<T>())
+                && align_of::<X>() == align_of::<T>()
+                && align_of::<Y>() == align_of::<T>()
+                && size_of::<X>() + size_of::<Y>() == size_of::<Self>(),
+                "the layered parts must be arrays of the same type");
+        size_of::<Self>() / size_of::<T>()
+    };
+}
+
+impl<T, X, Y> Borrow<[T]> for Layered<X, Y>
+    where X: Borrow<[T]>,
+          Y: Borrow<[T]>
+{
+    #[inline]
+    fn borrow(&self) -> &[T] {
+        let len = <Self as LayeredLayout<T>>::LEN;
+        debug_assert_eq!(len, self.0.borrow().len() + self.1.borrow().len());
+        // SAFETY: the struct is `repr(C)` and `LEN` asserts both parts are aligned to `T` and fill the whole struct
+        // with no padding, so it holds `len` consecutive elements of the arrays of `T` of both parts
+        unsafe { slice::from_raw_parts(self as *const Self as *const T, len) }
+    }
+}
+
+impl<T, X, Y> BorrowMut<[T]> for Layered<X, Y>
+    where X: BorrowMut<[T]>,
+          Y: BorrowMut<[T]>
+{
+    #[inline]
+    fn borrow_mut(&mut self) -> &mut [T] {
+        let len = <Self as LayeredLayout<T>>::LEN;
+        debug_assert_eq!(len, self.0.borrow().len() + self.1.borrow().len());
+        // SAFETY: see `borrow`, the exclusive borrow of the struct is the exclusive borrow of its elements
+        unsafe { slice::from_raw_parts_mut(self as *mut Self as *mut T, len) }
+    }
+}
+
+impl<CA, CB> IntermediateCalculator<Flt> for LayeredCalc<CA, CB>
+    where CA: IntermediateCalculator<Flt>,
+          CB: IntermediateCalculator<Flt>
+{
+    #[inline]
+    fn calculate(&self, v: Flt) -> Flt {
+        match self {
+            LayeredCalc::First(calc) => calc.calculate(v),
+            LayeredCalc::Second(calc) => calc.calculate(v),
+        }
+    }
+}
+
+impl<IA, IB> Iterator for LayeredMixIter<IA, IB>
+    where IA: ExactSizeIterator,
+          IB: ExactSizeIterator
+{
+    type Item = LayeredCalc<IA::Item, IB::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.first.next().map(LayeredCalc::First).or_else(|| self.second.next().map(LayeredCalc::Second))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl<IA, IB> ExactSizeIterator for LayeredMixIter<IA, IB>
+    where IA: ExactSizeIterator,
+          IB: ExactSizeIterator
+{
+    #[inline]
+    fn len(&self) -> usize { self.first.len() + self.second.len() }
+}
+
+impl<'a, P, A, LA, LB> LayeredInterCalcProducer<'a, P, A, LA, LB>
+    where P: PhaseAmpsSelect<'a> + ?Sized,
+          A: Mixer<Flt>
+{
+    fn split(pa: &'a P) -> (&'a P, &'a P) {
+        let len = pa.phase_amps_len();
+        let first = A::phase_amps_len().min(len);
+        (pa.select(0..first), pa.select(first..len))
+    }
+}
+
+impl<'a, P, A, LA, LB> IntermediateCalculatorProducer<'a, P, Flt> for LayeredInterCalcProducer<'a, P, A, LA, LB>
+    where P: PhaseAmpsSelect<'a> + ?Sized,
+          A: Mixer<Flt>,
+          LA: IntermediateCalculatorProducer<'a, P, Flt>,
+          LB: IntermediateCalculatorProducer<'a, P, Flt>
+{
+    type CalcIterH = LayeredMixIter<LA::CalcIterH, LB::CalcIterH>;
+    type CalcIterV = LayeredMixIter<LA::CalcIterV, LB::CalcIterV>;
+    type LineCalcH = LayeredCalc<LA::LineCalcH, LB::LineCalcH>;
+    type LineCalcV = LayeredCalc<LA::LineCalcV, LB::LineCalcV>;
+
+    fn compose_h_iter(pa: &'a P) -> Self::CalcIterH {
+        let (first, second) = Self::split(pa);
+        LayeredMixIter { first: LA::compose_h_iter(first), second: LB::compose_h_iter(second) }
+    }
+
+    fn compose_v_iter(pa: &'a P) -> Self::CalcIterV {
+        let (first, second) = Self::split(pa);
+        LayeredMixIter { first: LA::compose_v_iter(first), second: LB::compose_v_iter(second) }
+    }
+}
+
+impl<A, B> Mixer<Flt> for BlendMixer<A, B>
+    where A: Mixer<Flt>,
+          B: Mixer<Flt>
+{
+    type IntermediateH = Layered<A::IntermediateH, B::IntermediateH>;
+    type IntermediateV = Layered<A::IntermediateV, B::IntermediateV>;
+
+    #[inline]
+    fn phase_amps_len() -> usize { A::phase_amps_len() + B::phase_amps_len() }
+
+    #[inline]
+    fn mix_pixels(&self, vxp: &Self::IntermediateH, vyp: &Self::IntermediateV, next_pixel: &mut dyn FnMut(PixelRgb)) {
+        let mut first = PixelRgb::default();
+        self.first.mix_pixels(&vxp.0, &vyp.0, &mut |pixel| first = pixel);
+        self.second.mix_pixels(&vxp.1, &vyp.1, &mut |pixel| next_pixel(self.mode.blend(first, pixel)));
+    }
+}
+
+impl<A, B, F> Mixer<Flt> for LerpMixer<A, B, F>
+    where A: Mixer<Flt>,
+          B: Mixer<Flt>,
+          F: Mixer<Flt>
+{
+    type IntermediateH = Layered<A::IntermediateH, Layered<B::IntermediateH, F::IntermediateH>>;
+    type IntermediateV = Layered<A::IntermediateV, Layered<B::IntermediateV, F::IntermediateV>>;
+
+    #[inline]
+    fn phase_amps_len() -> usize { A::phase_amps_len() + B::phase_amps_len() + F::phase_amps_len() }
+
+    #[inline]
+    fn mix_pixels(&self, vxp: &Self::IntermediateH, vyp: &Self::IntermediateV, next_pixel: &mut dyn FnMut(PixelRgb)) {
+        const THIRD: Flt = csplat(1.0 / 3.0);
+        let (mut first, mut second) = (PixelRgb::default(), PixelRgb::default());
+        self.first.mix_pixels(&vxp.0, &vyp.0, &mut |pixel| first = pixel);
+        self.second.mix_pixels(&vxp.1 .0, &vyp.1 .0, &mut |pixel| second = pixel);
+        self.field.mix_pixels(&vxp.1 .1, &vyp.1 .1, &mut |pixel| {
+                      let t = ((pixel.r + pixel.g + pixel.b) * THIRD).sc_max(csplat(0.0)).sc_min(csplat(1.0));
+                      next_pixel(first + (second - first) * t)
+                  });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{mixers::*, simd_polyfill::*, *};
+    use core::borrow::{Borrow, BorrowMut};
+
+    #[test]
+    fn blend_modes_work() {
+        let a = PixelRgb::new(Flt::sc_splat(0.25), Flt::sc_splat(0.5), Flt::sc_splat(1.0));
+        let b = PixelRgb::new(Flt::sc_splat(0.5), Flt::sc_splat(0.5), Flt::sc_splat(0.0));
+        let res: Vec<[f32; 3]> = BlendMode::ALL.iter()
+                                              .map(|mode| mode.blend(a, b))
+                                              .map(|p| [p.r.sc_first_lane(), p.g.sc_first_lane(), p.b.sc_first_lane()])
+                                              .collect();
+        assert_eq!(res, [[0.75, 1.0, 1.0], [0.125, 0.25, 0.0], [0.625, 0.75, 1.0], [0.25, 0.0, 1.0]]);
+    }
+
+    #[test]
+    fn layered_works() {
+        let mut layered = Layered([Flt::sc_splat(1.0); 2], Layered([Flt::sc_splat(2.0); 3], [Flt::sc_splat(3.0)]));
+        assert_eq!(<BlendMixer<PlasmaMixer, LerpMixer<ContourMixer, MonochromeMixer, MonochromeMixer>>>::
+                   intermediate_h_len(), 6 + 2 + 4 + 4);
+        let slice: &mut [Flt] = layered.borrow_mut();
+        assert_eq!(slice.len(), 6);
+        slice[2] = Flt::sc_splat(5.0);
+        assert_eq!(layered.1 .0[0], Flt::sc_splat(5.0));
+        let slice: &[Flt] = layered.borrow();
+        assert_eq!(slice[5], Flt::sc_splat(3.0));
+    }
+
+    #[test]
+    fn blend_mixer_works() {
+        type Blend = BlendMixer<RgbChannelsMixer, ContourMixer>;
+        assert_eq!(Blend::phase_amps_len(), 18 + 16);
+        let (mut plasma, mut rng) = Plasma::new_seeded(32, 32, PhaseAmpCfg::default(), 5);
+        plasma.set_phase_amps_len(Blend::phase_amps_len(), &mut rng);
+        let mut phase_amps = Vec::new();
+        plasma.export_phase_amps(&mut phase_amps);
+        let pitch = 32 * PixelBufRGB24::PIXEL_BYTES;
+        let render_mixer = |mixer: &dyn Fn(&mut [u8], &[f32])| {
+            let mut buffer = vec![0u8; pitch * 32];
+            mixer(&mut buffer, &phase_amps);
+            buffer
+        };
+        let first = render_mixer(&|buffer, pa| {
+            render_part::<PixelBufRGB24, RgbChannelsICPExtPa, RgbChannelsMixer, _>(buffer, pitch, 32, 32, &pa[..36], 0,
+                                                                                   0, 32, 32, None)
+        });
+        let second = render_mixer(&|buffer, pa| {
+            render_part::<PixelBufRGB24, ContourICPExtPa, ContourMixer, _>(buffer, pitch, 32, 32, &pa[36..], 0, 0, 32,
+                                                                           32, None)
+        });
+        for mode in BlendMode::ALL {
+            let mixer = Blend::new(RgbChannelsMixer, ContourMixer, mode);
+            let mut buffer = vec![0u8; pitch * 32];
+            plasma.try_render_with::<PixelBufRGB24, BlendICP<RgbChannelsMixer, RgbChannelsICP, ContourICP>, _>(&mixer,
+                                                                                                              &mut buffer,
+                                                                                                              pitch,
+                                                                                                              None)
+                  .unwrap();
+            for ((&res, &a), &b) in buffer.iter().zip(first.iter()).zip(second.iter()) {
+                let (a, b) = (a as i32, b as i32);
+                let expected = match mode {
+                    BlendMode::Add => (a + b).min(255),
+                    BlendMode::Multiply => a * b / 255,
+                    BlendMode::Screen => a + b - a * b / 255,
+                    BlendMode::Difference => (a - b).abs(),
+                };
+                assert!((res as i32 - expected).abs() <= 2, "{:?}: {} != {}", mode, res, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn lerp_mixer_works() {
+        type Lerp = LerpMixer<RgbChannelsMixer, ContourMixer, MonochromeMixer>;
+        type Icp<'a> = LerpICP<'a, RgbChannelsMixer, RgbChannelsICP<'a>, ContourMixer, ContourICP<'a>, MonochromeICP<'a>>;
+        assert_eq!(Lerp::phase_amps_len(), 18 + 16 + 8);
+        let (mut plasma, mut rng) = Plasma::new_seeded(32, 32, PhaseAmpCfg::default(), 7);
+        plasma.set_phase_amps_len(Lerp::phase_amps_len(), &mut rng);
+        let pitch = 32 * PixelBufRGB24::PIXEL_BYTES;
+        let mut buffer = vec![0u8; pitch * 32];
+        plasma.try_render::<PixelBufRGB24, Icp, Lerp>(&mut buffer, pitch, None).unwrap();
+        // the field made of zero amplitudes is gray, so the result is the average of both mixers
+        let mut phase_amps = Vec::new();
+        plasma.export_phase_amps(&mut phase_amps);
+        for amplitude in phase_amps[2 * 34..].iter_mut().skip(1).step_by(2) {
+            *amplitude = 0.0;
+        }
+        render_part::<PixelBufRGB24, LerpICPExtPa<RgbChannelsMixer, RgbChannelsICPExtPa, ContourMixer,
+                                                  ContourICPExtPa, MonochromeICPExtPa>, Lerp, _>(&mut buffer,
+                                                                                                 pitch,
+                                                                                                 32,
+                                                                                                 32,
+                                                                                                 &phase_amps[..],
+                                                                                                 0,
+                                                                                                 0,
+                                                                                                 32,
+                                                                                                 32,
+                                                                                                 None);
+        let mut first = vec![0u8; pitch * 32];
+        render_part::<PixelBufRGB24, RgbChannelsICPExtPa, RgbChannelsMixer, _>(&mut first, pitch, 32, 32,
+                                                                               &phase_amps[..2 * 18], 0, 0, 32,
+                                                                               32, None);
+        let mut second = vec![0u8; pitch * 32];
+        render_part::<PixelBufRGB24, ContourICPExtPa, ContourMixer, _>(&mut second, pitch, 32, 32,
+                                                                       &phase_amps[2 * 18..2 * 34], 0, 0, 32, 32,
+                                                                       None);
+        for ((&res, &a), &b) in buffer.iter().zip(first.iter()).zip(second.iter()) {
+            assert!((res as i32 - (a as i32 + b as i32) / 2).abs() <= 2);
+        }
+    }
+}
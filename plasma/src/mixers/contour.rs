@@ -0,0 +1,68 @@
+//! Contour plasma mixer
+use derive_more::{Debug, Constructor};
+use crate::{color::*, mixers::wave::*, phase_amp::*, simd_polyfill::*};
+#[cfg(not(feature = "std"))]
+use crate::m_polyfill::*;
+
+/// A convenient type to be used with [crate::plasma::Plasma::render] or
+/// [crate::plasma::Plasma::render_part] with the [ContourMixer].
+pub type ContourICP<'a> = ContourInterCalcProducer<'a, [PhaseAmp]>;
+/// A convenient type to be used with [crate::plasma::render_part] with the [ContourMixer].
+pub type ContourICPExtPa<'a> = ContourInterCalcProducer<'a, [f32]>;
+
+/// Provides the [IntermediateCalculatorProducer](crate::IntermediateCalculatorProducer) for the [ContourMixer].
+///
+/// Each of the intermediate values is a sum of two sine and two cosine waves of a quad of components,
+/// see [WaveLineCalc].
+pub type ContourInterCalcProducer<'a, P> = WaveInterCalcProducer<'a, P, 4>;
+
+/// The number of color bands of the [ContourMixer].
+pub(crate) const BANDS: f32 = 8.0;
+
+/// A [Mixer](crate::Mixer) rendering the plasma as flat color bands separated by dark contour lines.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Constructor)]
+pub struct ContourMixer;
+
+crate::plasma_mixer! {
+    impl ContourMixer {
+        components = 4;
+        intermediate = [2, 2];
+        fn mix(&self, vxp, vyp) {
+            const THIRD: Flt = csplat(1.0 / 3.0);
+            const HALF: Flt = csplat(0.5);
+            const ONE: Flt = csplat(1.0);
+            const BANDS_FLT: Flt = csplat(BANDS);
+            const LINE_EDGE: Flt = csplat(0.8);
+            const LINE_SHARPNESS: Flt = csplat(5.0);
+            let field = ((vxp[0] + vyp[0] + vxp[1] * vyp[1]) * THIRD * HALF + HALF) * BANDS_FLT;
+            let band = field.floor();
+            // 0 in the middle of the band and 1 on its edges
+            let edge = ((field - band - HALF) * (ONE + ONE)).abs();
+            let shade = ONE - ((edge - LINE_EDGE) * LINE_SHARPNESS).sc_max(csplat(0.0));
+            let hue = band * (csplat(2.0) / BANDS_FLT);
+            PixelRgb::from_hsv(hue, csplat(0.75), shade)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{mixers::{contour::BANDS, *}, *};
+
+    #[test]
+    fn contour_works() {
+        assert_eq!(ContourMixer::phase_amps_len(), 16);
+        let (mut plasma, mut rng) = Plasma::new_seeded(64, 64, PhaseAmpCfg::default(), 3);
+        plasma.set_phase_amps_len(ContourMixer::phase_amps_len(), &mut rng);
+        let pitch = 64 * PixelBufRGB24::PIXEL_BYTES;
+        let mut buffer = vec![0u8; pitch * 64];
+        plasma.try_render::<PixelBufRGB24, ContourICP, ContourMixer>(&mut buffer, pitch, None).unwrap();
+        let mut colors: Vec<_> = buffer.chunks_exact(3).filter(|rgb| rgb.contains(&255)).collect();
+        colors.sort();
+        colors.dedup();
+        // the inner parts of the bands are flat
+        assert!(colors.len() > 1 && colors.len() <= BANDS as usize);
+        // there are dark contour lines
+        assert!(buffer.chunks_exact(3).any(|rgb| rgb.iter().all(|&c| c < 128)));
+    }
+}
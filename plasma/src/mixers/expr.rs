@@ -0,0 +1,476 @@
+//! Runtime expression-language mixer
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, vec, vec::Vec};
+use core::{f32::consts::PI, mem, ptr, str::FromStr, sync::atomic::{AtomicPtr, Ordering}};
+use crate::{color::*, error::*, mixer::*, simd_polyfill::*};
+#[cfg(not(feature = "std"))]
+use crate::m_polyfill::*;
+
+/// The maximum depth of the evaluation stack of the [ExprMixer] expressions.
+pub const EXPR_STACK_LEN: usize = 16;
+
+/// The maximum nesting depth of the parentheses, the function calls and the negations of the [ExprMixer]
+/// expressions.
+pub const EXPR_NESTING_LEN: usize = 32;
+
+/// The expression of the default [ExprMixer].
+pub const DEFAULT_EXPR: &str = "hsv(x0 * y1 + y0 * x1 + t * 0.1, 1 - abs(x2 + y2) * 0.25, x3 * y4 * 0.5 + 0.5)";
+
+/// The number of the horizontal and of the vertical intermediate values of the [ExprMixer].
+const INTERMEDIATE_LEN: usize = 6;
+
+/// A [Mixer] computing the color of each pixel from an expression parsed at runtime.
+///
+/// This mixer is available with the "expr" crate feature. It uses the same intermediate data as
+/// the [PlasmaMixer](crate::PlasmaMixer), so it should be used with the [PlasmaICP](crate::PlasmaICP)
+/// or [PlasmaICPExtPa](crate::PlasmaICPExtPa) producers.
+///
+/// The expression is either a scalar rendered as a shade of gray or one of the color constructors:
+///
+/// * `rgb(red, green, blue)` - the components should be in the range `[0, 1]`,
+/// * `hsv(hue, sat, val)` - the `hue` wraps around every `2.0`, see [PixelRgb::from_hsv].
+///
+/// The scalar expressions are made of numbers, the `+`, `-`, `*` and `/` operators, parentheses
+/// and the following variables and functions:
+///
+/// * `x0` to `x5` - the horizontal intermediate values in the range `[-1, 1]`,
+/// * `y0` to `y5` - the vertical intermediate values in the range `[-1, 1]`,
+/// * `t` - the [time](ExprMixer::set_time), `pi` - the π constant,
+/// * `sin(v)`, `cos(v)`, `abs(v)`, `sqrt(v)`, `floor(v)`, `fract(v)`,
+/// * `min(a, b)`, `max(a, b)`, `clamp(v, lo, hi)` and `mix(a, b, t)` - the linear interpolation.
+///
+/// The expressions are compiled to a postfix form with the constant subexpressions folded.
+///
+/// # Example
+///
+/// ```
+/// use plasma::*;
+///
+/// let mut mixer: ExprMixer = "hsv(x0 * y1 + t, 1, abs(x2 + y2) * 0.5 + 0.5)".parse().unwrap();
+/// mixer.set_time(0.25);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExprMixer {
+    model:    ColorModel,
+    channels: Vec<Vec<Op>>,
+    time:     f32,
+}
+
+/// How the results of the channel expressions are turned into a color.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum ColorModel {
+    Gray,
+    Rgb,
+    Hsv,
+}
+
+/// An instruction of the compiled expression.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Op {
+    Const(f32),
+    X(u8),
+    Y(u8),
+    Time,
+    Neg,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Sin,
+    Cos,
+    Abs,
+    Sqrt,
+    Floor,
+    Fract,
+    Min,
+    Max,
+    Clamp,
+    Mix,
+}
+
+/// A recursive descent parser emitting the instructions in the postfix order.
+struct Parser<'s> {
+    src:     &'s str,
+    pos:     usize,
+    ops:     Vec<Op>,
+    depth:   usize,
+    nesting: usize,
+}
+
+type ParseResult<T> = Result<T, PlasmaError>;
+
+impl ExprMixer {
+    /// Returns the value of the time variable `t`.
+    #[inline]
+    pub fn time(&self) -> f32 { self.time }
+
+    /// Sets the value of the time variable `t`, e.g. the number of seconds since the animation has started.
+    #[inline]
+    pub fn set_time(&mut self, time: f32) { self.time = time; }
+
+    /// Returns the mixer of the [DEFAULT_EXPR] rendered with [MixerKind::Expr](crate::MixerKind::Expr).
+    ///
+    /// The expression is parsed once, on the first call, and the mixer is shared by all of the renders.
+    pub(crate) fn shared_default() -> &'static ExprMixer {
+        static MIXER: AtomicPtr<ExprMixer> = AtomicPtr::new(ptr::null_mut());
+        let mut mixer = MIXER.load(Ordering::Acquire);
+        if mixer.is_null() {
+            let new = Box::into_raw(Box::new(ExprMixer::default()));
+            mixer = match MIXER.compare_exchange(ptr::null_mut(), new, Ordering::AcqRel, Ordering::Acquire) {
+                Ok(_) => new,
+                Err(other) => {
+                    // SAFETY: the mixer was created above and it was never shared, as another one was stored first
+                    drop(unsafe { Box::from_raw(new) });
+                    other
+                },
+            };
+        }
+        // SAFETY: the stored mixer is leaked, so it's never freed nor modified
+        unsafe { &*mixer }
+    }
+}
+
+impl Default for ExprMixer {
+    /// Returns the mixer of the [DEFAULT_EXPR].
+    fn default() -> Self { DEFAULT_EXPR.parse().expect("the default expression should be valid") }
+}
+
+impl FromStr for ExprMixer {
+    type Err = PlasmaError;
+
+    /// Parses the expression, returning [PlasmaError::InvalidExpression] with the byte position
+    /// of the offending part of the source if it's not valid.
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser { src, pos: 0, ops: Vec::new(), depth: 0, nesting: 0 };
+        let model = match parser.peek_ident() {
+            Some("rgb") => ColorModel::Rgb,
+            Some("hsv") => ColorModel::Hsv,
+            _ => ColorModel::Gray,
+        };
+        let channels = if model == ColorModel::Gray {
+            parser.expr()?;
+            vec![mem::take(&mut parser.ops)]
+        }
+        else {
+            parser.ident();
+            parser.expect('(')?;
+            let mut channels = Vec::with_capacity(3);
+            for sep in [',', ',', ')'] {
+                parser.expr()?;
+                parser.expect(sep)?;
+                channels.push(mem::take(&mut parser.ops));
+                parser.depth = 0;
+            }
+            channels
+        };
+        parser.skip_whitespace();
+        if parser.pos != src.len() {
+            return Err(parser.error());
+        }
+        Ok(ExprMixer { model, channels, time: 0.0 })
+    }
+}
+
+impl Op {
+    /// Returns the number of operands of the function `name` and its instruction.
+    fn function(name: &str) -> Option<(usize, Op)> {
+        Some(match name {
+            "sin" => (1, Op::Sin),
+            "cos" => (1, Op::Cos),
+            "abs" => (1, Op::Abs),
+            "sqrt" => (1, Op::Sqrt),
+            "floor" => (1, Op::Floor),
+            "fract" => (1, Op::Fract),
+            "min" => (2, Op::Min),
+            "max" => (2, Op::Max),
+            "clamp" => (3, Op::Clamp),
+            "mix" => (3, Op::Mix),
+            _ => return None,
+        })
+    }
+
+    /// Returns the number of operands taken from the evaluation stack.
+    fn arity(self) -> usize {
+        match self {
+            Op::Const(..) | Op::X(..) | Op::Y(..) | Op::Time => 0,
+            Op::Neg | Op::Sin | Op::Cos | Op::Abs | Op::Sqrt | Op::Floor | Op::Fract => 1,
+            Op::Add | Op::Sub | Op::Mul | Op::Div | Op::Min | Op::Max => 2,
+            Op::Clamp | Op::Mix => 3,
+        }
+    }
+}
+
+impl<'s> Parser<'s> {
+    fn error(&self) -> PlasmaError { PlasmaError::InvalidExpression { position: self.pos } }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.src[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.src[self.pos..].chars().next()
+    }
+
+    fn expect(&mut self, c: char) -> ParseResult<()> {
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            Ok(())
+        }
+        else {
+            Err(self.error())
+        }
+    }
+
+    fn peek_ident(&mut self) -> Option<&'s str> {
+        self.skip_whitespace();
+        let rest = &self.src[self.pos..];
+        if !rest.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+            return None;
+        }
+        let len = rest.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')).unwrap_or(rest.len());
+        Some(&rest[..len])
+    }
+
+    fn ident(&mut self) -> Option<&'s str> {
+        let ident = self.peek_ident()?;
+        self.pos += ident.len();
+        Some(ident)
+    }
+
+    /// Appends the instruction, folding it with its operands if all of them are constants.
+    fn emit(&mut self, op: Op) -> ParseResult<()> {
+        let arity = op.arity();
+        let start = self.ops.len() - arity;
+        if arity != 0 && self.ops[start..].iter().all(|op| matches!(op, Op::Const(..))) {
+            self.ops.push(op);
+            let value = eval(&self.ops[start..], &[], &[], csplat(0.0)).sc_first_lane();
+            self.ops.truncate(start);
+            self.ops.push(Op::Const(value));
+        }
+        else {
+            self.ops.push(op);
+        }
+        self.depth = self.depth + 1 - arity;
+        if self.depth > EXPR_STACK_LEN {
+            return Err(self.error());
+        }
+        Ok(())
+    }
+
+    /// Parses the nested part of the expression, limiting the depth of the recursion.
+    fn nested(&mut self, parse: impl FnOnce(&mut Self) -> ParseResult<()>) -> ParseResult<()> {
+        if self.nesting == EXPR_NESTING_LEN {
+            return Err(self.error());
+        }
+        self.nesting += 1;
+        let res = parse(self);
+        self.nesting -= 1;
+        res
+    }
+
+    /// expr := term (("+" | "-") term)*
+    fn expr(&mut self) -> ParseResult<()> {
+        self.term()?;
+        loop {
+            let op = match self.peek() {
+                Some('+') => Op::Add,
+                Some('-') => Op::Sub,
+                _ => return Ok(()),
+            };
+            self.pos += 1;
+            self.term()?;
+            self.emit(op)?;
+        }
+    }
+
+    /// term := unary (("*" | "/") unary)*
+    fn term(&mut self) -> ParseResult<()> {
+        self.unary()?;
+        loop {
+            let op = match self.peek() {
+                Some('*') => Op::Mul,
+                Some('/') => Op::Div,
+                _ => return Ok(()),
+            };
+            self.pos += 1;
+            self.unary()?;
+            self.emit(op)?;
+        }
+    }
+
+    /// unary := "-" unary | primary
+    fn unary(&mut self) -> ParseResult<()> {
+        if self.peek() == Some('-') {
+            self.nested(|parser| {
+                    parser.pos += 1;
+                    parser.unary()?;
+                    parser.emit(Op::Neg)
+                })
+        }
+        else {
+            self.primary()
+        }
+    }
+
+    /// primary := number | variable | function "(" expr ("," expr)* ")" | "(" expr ")"
+    fn primary(&mut self) -> ParseResult<()> {
+        match self.peek() {
+            Some('(') => {
+                self.nested(|parser| {
+                        parser.pos += 1;
+                        parser.expr()?;
+                        parser.expect(')')
+                    })
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' => {
+                let rest = &self.src[self.pos..];
+                let len = rest.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(rest.len());
+                let value = rest[..len].parse().map_err(|_| self.error())?;
+                self.pos += len;
+                self.emit(Op::Const(value))
+            }
+            _ => {
+                let start = self.pos;
+                let ident = self.ident().ok_or_else(|| self.error())?;
+                if let Some((arity, op)) = Op::function(ident) {
+                    return self.nested(|parser| {
+                                   parser.expect('(')?;
+                                   for i in 0..arity {
+                                       if i != 0 {
+                                           parser.expect(',')?;
+                                       }
+                                       parser.expr()?;
+                                   }
+                                   parser.expect(')')?;
+                                   parser.emit(op)
+                               });
+                }
+                let op = match ident.as_bytes() {
+                    b"t" => Op::Time,
+                    b"pi" => Op::Const(PI),
+                    &[axis @ (b'x' | b'y'), index @ b'0'..=b'9'] if ((index - b'0') as usize) < INTERMEDIATE_LEN => {
+                        if axis == b'x' { Op::X(index - b'0') } else { Op::Y(index - b'0') }
+                    }
+                    _ => {
+                        self.pos = start;
+                        return Err(self.error());
+                    }
+                };
+                self.emit(op)
+            }
+        }
+    }
+}
+
+/// Evaluates the compiled expression.
+#[inline]
+fn eval(ops: &[Op], vxp: &[Flt], vyp: &[Flt], time: Flt) -> Flt {
+    // the two extra slots allow to read the operands of any instruction without checking its arity
+    let mut stack = [csplat(0.0); EXPR_STACK_LEN + 2];
+    let mut sp = 0;
+    for &op in ops {
+        sp -= op.arity();
+        let (a, b, c) = (stack[sp], stack[sp + 1], stack[sp + 2]);
+        stack[sp] = match op {
+            Op::Const(value) => Flt::sc_splat(value),
+            Op::X(index) => vxp[index as usize],
+            Op::Y(index) => vyp[index as usize],
+            Op::Time => time,
+            Op::Neg => -a,
+            Op::Add => a + b,
+            Op::Sub => a - b,
+            Op::Mul => a * b,
+            Op::Div => a / b,
+            Op::Sin => a.sin(),
+            Op::Cos => a.cos(),
+            Op::Abs => a.abs(),
+            Op::Sqrt => a.sqrt(),
+            Op::Floor => a.floor(),
+            Op::Fract => a - a.floor(),
+            Op::Min => a.sc_min(b),
+            Op::Max => a.sc_max(b),
+            Op::Clamp => a.sc_max(b).sc_min(c),
+            Op::Mix => a + (b - a) * c,
+        };
+        sp += 1;
+    }
+    stack[0]
+}
+
+impl Mixer<Flt> for ExprMixer {
+    type IntermediateH = [Flt; INTERMEDIATE_LEN];
+    type IntermediateV = [Flt; INTERMEDIATE_LEN];
+
+    #[inline]
+    fn mix_pixels(&self, vxp: &Self::IntermediateH, vyp: &Self::IntermediateV, next_pixel: &mut dyn FnMut(PixelRgb)) {
+        let time = Flt::sc_splat(self.time);
+        let channel = |i: usize| eval(&self.channels[i], vxp, vyp, time);
+        next_pixel(match self.model {
+                       ColorModel::Gray => {
+                           let v = channel(0);
+                           PixelRgb::new(v, v, v)
+                       }
+                       ColorModel::Rgb => PixelRgb::new(channel(0), channel(1), channel(2)),
+                       ColorModel::Hsv => PixelRgb::from_hsv(channel(0), channel(1), channel(2)),
+                   });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{mixers::{expr::Op, *}, simd_polyfill::*, *};
+
+    #[test]
+    fn expr_parse_works() {
+        let mixer: ExprMixer = " 2 * (x0 + 1.5) - -y5/ 4 ".parse().unwrap();
+        assert_eq!(mixer.channels,
+                   [vec![Op::Const(2.0), Op::X(0), Op::Const(1.5), Op::Add, Op::Mul, Op::Y(5), Op::Neg, Op::Const(4.0),
+                         Op::Div, Op::Sub]]);
+        let mixer: ExprMixer = "rgb(sin(pi / 2), mix(x1, y1, 0.25), clamp(t * 3, 0, 1))".parse().unwrap();
+        assert_eq!(mixer.channels,
+                   [vec![Op::Const(1.0)],
+                    vec![Op::X(1), Op::Y(1), Op::Const(0.25), Op::Mix],
+                    vec![Op::Time, Op::Const(3.0), Op::Mul, Op::Const(0.0), Op::Const(1.0), Op::Clamp]]);
+        for (src, position) in [("", 0), ("x6", 0), ("1 +", 3), ("sin(1, 2)", 5), ("rgb(1, 2)", 8), ("(x0", 3),
+                                ("hsv(1, 2, 3) + 1", 13), ("tan(1)", 0), ("1..2", 0), ("x0 y0", 3)]
+        {
+            assert_eq!(src.parse::<ExprMixer>(), Err(PlasmaError::InvalidExpression { position }), "{:?}", src);
+        }
+        let deep = "(".repeat(EXPR_STACK_LEN) + &"x0 + (".repeat(EXPR_STACK_LEN) + "1" + &")".repeat(2 * EXPR_STACK_LEN);
+        assert!(deep.parse::<ExprMixer>().is_err());
+        let nested = "(".repeat(EXPR_NESTING_LEN) + "x0" + &")".repeat(EXPR_NESTING_LEN);
+        assert!(nested.parse::<ExprMixer>().is_ok());
+        for (prefix, offset) in [("(", 0), ("-", 0), ("abs(", 3)] {
+            let deep = prefix.repeat(100_000) + "1";
+            let position = EXPR_NESTING_LEN * prefix.len() + offset;
+            assert_eq!(deep.parse::<ExprMixer>(), Err(PlasmaError::InvalidExpression { position }), "{}", prefix);
+        }
+    }
+
+    #[test]
+    fn expr_mixer_works() {
+        assert_eq!(*ExprMixer::shared_default(), ExprMixer::default());
+        assert!(core::ptr::eq(ExprMixer::shared_default(), ExprMixer::shared_default()));
+        let mut mixer: ExprMixer = "rgb(x0 * y0 * 0.5 + 0.5, fract(x1 + 1.25), t)".parse().unwrap();
+        mixer.set_time(0.75);
+        assert_eq!(mixer.time(), 0.75);
+        let mut vxp = [Flt::sc_splat(0.0); 6];
+        let mut vyp = [Flt::sc_splat(0.0); 6];
+        vxp[0] = Flt::sc_splat(0.5);
+        vyp[0] = Flt::sc_splat(-1.0);
+        vxp[1] = Flt::sc_splat(0.5);
+        let mut pixel = PixelRgb::default();
+        mixer.mix_pixels(&vxp, &vyp, &mut |p| pixel = p);
+        assert_eq!(pixel, PixelRgb::new(Flt::sc_splat(0.25), Flt::sc_splat(0.75), Flt::sc_splat(0.75)));
+        // a scalar expression renders shades of gray
+        let mixer: ExprMixer = "(x0 + y0 + x1 * y1 + x2 * y3 + x3 * y2) / 5 * 0.5 + 0.5".parse().unwrap();
+        let (plasma, _) = Plasma::new_seeded(32, 32, PhaseAmpCfg::default(), 2);
+        let pitch = 32 * PixelBufRGB24::PIXEL_BYTES;
+        let mut buffer = vec![0u8; pitch * 32];
+        plasma.try_render_with::<PixelBufRGB24, PlasmaICP, _>(&mixer, &mut buffer, pitch, None).unwrap();
+        assert!(buffer.chunks_exact(3).all(|rgb| rgb[0] == rgb[1] && rgb[1] == rgb[2]));
+        assert!(buffer.chunks_exact(3).any(|rgb| rgb[0] != buffer[0]));
+    }
+}
@@ -0,0 +1,92 @@
+//! Lava lamp plasma mixer
+use derive_more::{Debug, Constructor};
+use crate::{color::*, mixer::*, mixers::wave::*, phase_amp::*, simd_polyfill::*};
+#[cfg(not(feature = "std"))]
+use crate::m_polyfill::*;
+
+/// A convenient type to be used with [crate::plasma::Plasma::render] or
+/// [crate::plasma::Plasma::render_part] with the [LavaLampMixer].
+pub type LavaLampICP<'a> = LavaLampInterCalcProducer<'a, [PhaseAmp]>;
+/// A convenient type to be used with [crate::plasma::render_part] with the [LavaLampMixer].
+pub type LavaLampICPExtPa<'a> = LavaLampInterCalcProducer<'a, [f32]>;
+
+/// Provides the [IntermediateCalculatorProducer] for the [LavaLampMixer].
+///
+/// Each [LavaLampLineCalc] is made of a pair of components.
+pub type LavaLampInterCalcProducer<'a, P> = GroupInterCalcProducer<'a, P, LavaLampLineCalc, 2>;
+
+/// A [Mixer] rendering the plasma as thresholded blobs of wax floating in a lava lamp.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Constructor)]
+pub struct LavaLampMixer;
+
+/// Computes a periodic bump of a pair of components for the [LavaLampMixer].
+///
+/// The phase of the first component places the bump and its amplitude scales it,
+/// the second component wobbles the bump's outline.
+pub struct LavaLampLineCalc {
+    center: Flt,
+    size:   Flt,
+    wobble: Flt,
+    phase:  Flt,
+}
+
+crate::plasma_mixer! {
+    impl LavaLampMixer {
+        components = 2;
+        intermediate = [4, 4];
+        fn mix(&self, vxp, vyp) {
+            const THRESHOLD: Flt = csplat(0.3);
+            const SHARPNESS: Flt = csplat(20.0);
+            const ZERO: Flt = csplat(0.0);
+            const ONE: Flt = csplat(1.0);
+            const LAVA: PixelRgb = PixelRgb { r: csplat(1.0), g: csplat(0.55), b: csplat(0.1) };
+            const FLUID: PixelRgb = PixelRgb { r: csplat(0.25), g: csplat(0.0), b: csplat(0.3) };
+            let field = vxp[0] * vyp[0] + vxp[1] * vyp[1] + vxp[2] * vyp[2] + vxp[3] * vyp[3];
+            let wax = ((field - THRESHOLD) * SHARPNESS).sc_max(ZERO).sc_min(ONE);
+            let glow = field.sc_min(THRESHOLD);
+            FLUID + (LAVA - FLUID) * wax + PixelRgb::new(glow, ZERO, ZERO)
+        }
+    }
+}
+
+impl GroupLineCalc<2> for LavaLampLineCalc {
+    #[inline]
+    fn from_phase_amps([phase1, phase2]: [f32; 2], [amplitude1, amplitude2]: [f32; 2]) -> Self {
+        LavaLampLineCalc { center: Flt::sc_splat(phase1),
+                           size:   Flt::sc_splat(0.5 + 0.5 * amplitude1),
+                           wobble: Flt::sc_splat(0.25 * amplitude2),
+                           phase:  Flt::sc_splat(phase2), }
+    }
+}
+
+impl IntermediateCalculator<Flt> for LavaLampLineCalc {
+    #[inline]
+    fn calculate(&self, v: Flt) -> Flt {
+        const HALF: Flt = csplat(0.5);
+        const TWO: Flt = csplat(2.0);
+        let bump = (v - self.center).cos() * HALF + HALF;
+        let bump = bump * bump;
+        let bump = bump * bump;
+        (bump + (v * TWO + self.phase).sin() * self.wobble * bump) * self.size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{mixers::*, *};
+
+    #[test]
+    fn lava_lamp_works() {
+        assert_eq!(LavaLampMixer::phase_amps_len(), 16);
+        let (mut plasma, mut rng) = Plasma::new_seeded(64, 64, PhaseAmpCfg::default(), 4);
+        plasma.set_phase_amps_len(LavaLampMixer::phase_amps_len(), &mut rng);
+        let pitch = 64 * PixelBufRGB24::PIXEL_BYTES;
+        let mut buffer = vec![0u8; pitch * 64];
+        plasma.try_render::<PixelBufRGB24, LavaLampICP, LavaLampMixer>(&mut buffer, pitch, None).unwrap();
+        let wax = buffer.chunks_exact(3).filter(|rgb| rgb[0] == 255 && rgb[1] == 140).count();
+        let fluid = buffer.chunks_exact(3).filter(|rgb| rgb[1] == 0).count();
+        assert!(wax > 0 && fluid > 0);
+        // the blobs have sharp edges
+        assert!(wax + fluid > buffer.len() / 3 * 3 / 4);
+    }
+}
@@ -1,4 +1,17 @@
 //! A module for plasma mixers
+mod blend;
+mod contour;
+#[cfg(feature = "expr")]
+mod expr;
+mod lava_lamp;
+mod monochrome;
+mod palette;
 mod plasma_default;
+mod radial;
+mod rgb_channels;
+mod wave;
 
-pub use self::plasma_default::*;
+pub use self::{blend::*, contour::*, lava_lamp::*, monochrome::*, palette::*, plasma_default::*, radial::*,
+               rgb_channels::*, wave::*};
+#[cfg(feature = "expr")]
+pub use self::expr::*;
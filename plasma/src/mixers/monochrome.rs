@@ -0,0 +1,71 @@
+//! Monochrome plasma mixer
+use derive_more::{Debug, Constructor};
+use crate::{color::*, mixer::*, mixers::wave::*, phase_amp::*, simd_polyfill::*};
+#[cfg(not(feature = "std"))]
+use crate::m_polyfill::*;
+
+/// A convenient type to be used with [crate::plasma::Plasma::render] or
+/// [crate::plasma::Plasma::render_part] with the [MonochromeMixer].
+pub type MonochromeICP<'a> = MonochromeInterCalcProducer<'a, [PhaseAmp]>;
+/// A convenient type to be used with [crate::plasma::render_part] with the [MonochromeMixer].
+pub type MonochromeICPExtPa<'a> = MonochromeInterCalcProducer<'a, [f32]>;
+
+/// Provides the [IntermediateCalculatorProducer] for the [MonochromeMixer].
+///
+/// Each [MonochromeLineCalc] is made of a single component.
+pub type MonochromeInterCalcProducer<'a, P> = GroupInterCalcProducer<'a, P, MonochromeLineCalc, 1>;
+
+/// A [Mixer] rendering the plasma as a grayscale intensity.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Constructor)]
+pub struct MonochromeMixer;
+
+/// Computes a single sine wave of a phase'n'amplitude component for the [MonochromeMixer].
+pub struct MonochromeLineCalc {
+    amplitude: Flt,
+    phase:     Flt,
+}
+
+crate::plasma_mixer! {
+    impl MonochromeMixer {
+        components = 1;
+        intermediate = [4, 4];
+        fn mix(&self, vxp, vyp) {
+            const FIFTH: Flt = csplat(1.0 / 5.0);
+            const HALF: Flt = csplat(0.5);
+            let field = (vxp[0] + vyp[0] + vxp[1] * vyp[1] + vxp[2] * vyp[3] + vxp[3] * vyp[2]) * FIFTH;
+            let intensity = field * HALF + HALF;
+            PixelRgb::new(intensity, intensity, intensity)
+        }
+    }
+}
+
+impl GroupLineCalc<1> for MonochromeLineCalc {
+    #[inline]
+    fn from_phase_amps([phase]: [f32; 1], [amplitude]: [f32; 1]) -> Self {
+        MonochromeLineCalc { amplitude: Flt::sc_splat(amplitude), phase: Flt::sc_splat(phase) }
+    }
+}
+
+impl IntermediateCalculator<Flt> for MonochromeLineCalc {
+    #[inline]
+    fn calculate(&self, v: Flt) -> Flt { (v + self.phase).sin() * self.amplitude }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{mixers::*, *};
+
+    #[test]
+    fn monochrome_works() {
+        assert_eq!(MonochromeMixer::phase_amps_len(), 8);
+        let (mut plasma, mut rng) = Plasma::new_seeded(32, 24, PhaseAmpCfg::default(), 1);
+        let pitch = 32 * PixelBufRGB24::PIXEL_BYTES;
+        let mut buffer = vec![0u8; pitch * 24];
+        assert!(plasma.try_render::<PixelBufRGB24, MonochromeICP, MonochromeMixer>(&mut buffer, pitch, None)
+                      .is_err());
+        plasma.set_phase_amps_len(MonochromeMixer::phase_amps_len(), &mut rng);
+        plasma.try_render::<PixelBufRGB24, MonochromeICP, MonochromeMixer>(&mut buffer, pitch, None).unwrap();
+        assert!(buffer.chunks_exact(3).all(|rgb| rgb[0] == rgb[1] && rgb[1] == rgb[2]));
+        assert!(buffer.chunks_exact(3).any(|rgb| rgb[0] != buffer[0]));
+    }
+}
@@ -0,0 +1,185 @@
+//! Palette plasma mixer
+use derive_more::Debug;
+use crate::{color::*, error::*, mixer::*, simd_polyfill::*};
+#[cfg(not(feature = "std"))]
+use crate::m_polyfill::*;
+
+/// The number of entries of the [PaletteMixer] color table.
+pub const PALETTE_LEN: usize = 256;
+
+/// A [Mixer] mapping the plasma scalar field through a color palette.
+///
+/// The palette is cyclic: the field wraps around it, so the last entry should blend smoothly
+/// into the first one. The palette may be cycled by changing its [offset](PaletteMixer::set_offset)
+/// between the rendered frames.
+///
+/// This mixer uses the same intermediate data as the [PlasmaMixer](crate::PlasmaMixer), so it
+/// should be used with the [PlasmaICP](crate::PlasmaICP) or [PlasmaICPExtPa](crate::PlasmaICPExtPa)
+/// producers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaletteMixer {
+    #[debug(skip)]
+    red:    [f32; PALETTE_LEN],
+    #[debug(skip)]
+    green:  [f32; PALETTE_LEN],
+    #[debug(skip)]
+    blue:   [f32; PALETTE_LEN],
+    offset: f32,
+}
+
+impl PaletteMixer {
+    /// Creates new [PaletteMixer] instance from the 256 RGB colors.
+    pub fn from_palette(palette: &[[u8; 3]; PALETTE_LEN]) -> Self {
+        let mut mixer = PaletteMixer { red:    [0.0; PALETTE_LEN],
+                                       green:  [0.0; PALETTE_LEN],
+                                       blue:   [0.0; PALETTE_LEN],
+                                       offset: 0.0, };
+        for (i, &[r, g, b]) in palette.iter().enumerate() {
+            mixer.red[i] = r as f32 / 255.0;
+            mixer.green[i] = g as f32 / 255.0;
+            mixer.blue[i] = b as f32 / 255.0;
+        }
+        mixer
+    }
+
+    /// Creates new [PaletteMixer] instance from the gradient color stops.
+    ///
+    /// Each stop is a position in the range `[0, 1]` and an RGB color. The colors between
+    /// the stops are interpolated linearly and the color after the last stop blends into
+    /// the color of the first one.
+    ///
+    /// # Panics
+    ///
+    /// __Panics__ if `stops` is empty or the stop positions are not ascending within the range `[0, 1]`.
+    pub fn from_gradient(stops: &[(f32, [u8; 3])]) -> Self {
+        match Self::try_from_gradient(stops) {
+            Ok(mixer) => mixer,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Creates new [PaletteMixer] instance from the gradient color stops.
+    ///
+    /// This is a fallible variant of [PaletteMixer::from_gradient], returning an error instead of panicking.
+    pub fn try_from_gradient(stops: &[(f32, [u8; 3])]) -> Result<Self, PlasmaError> {
+        let valid = !stops.is_empty()
+                    && stops.iter().all(|&(pos, _)| (0.0..=1.0).contains(&pos))
+                    && stops.windows(2).all(|w| w[0].0 <= w[1].0);
+        if !valid {
+            return Err(PlasmaError::InvalidGradient)
+        }
+        let (first, last) = (stops[0], stops[stops.len() - 1]);
+        let mut palette = [[0u8; 3]; PALETTE_LEN];
+        for (i, color) in palette.iter_mut().enumerate() {
+            let t = i as f32 / PALETTE_LEN as f32;
+            let next = stops.partition_point(|&(pos, _)| pos <= t);
+            let ((pos0, color0), (pos1, color1)) = match next {
+                0 => ((last.0 - 1.0, last.1), first),
+                n if n == stops.len() => (last, (first.0 + 1.0, first.1)),
+                n => (stops[n - 1], stops[n]),
+            };
+            let span = pos1 - pos0;
+            let k = if span > 0.0 { (t - pos0) / span } else { 0.0 };
+            for (c, (&c0, &c1)) in color.iter_mut().zip(color0.iter().zip(color1.iter())) {
+                *c = (c0 as f32 + (c1 as f32 - c0 as f32) * k + 0.5) as u8;
+            }
+        }
+        Ok(Self::from_palette(&palette))
+    }
+
+    /// Returns the palette offset in the range `[0, 1)`.
+    #[inline]
+    pub fn offset(&self) -> f32 { self.offset }
+
+    /// Sets the palette offset, the fraction of the palette the colors are shifted by.
+    #[inline]
+    pub fn set_offset(&mut self, offset: f32) { self.offset = offset - offset.floor(); }
+
+    /// Shifts the palette by `delta`, where `1.0` is the full palette cycle.
+    #[inline]
+    pub fn cycle(&mut self, delta: f32) { self.set_offset(self.offset + delta) }
+
+    #[cfg(feature = "use-simd")]
+    #[inline]
+    fn lookup(&self, t: Flt) -> PixelRgb {
+        const LAST: Flt = csplat((PALETTE_LEN - 1) as f32);
+        let index = ((t - t.floor()) * Flt::splat(PALETTE_LEN as f32)).simd_min(LAST).cast::<usize>();
+        PixelRgb::new(Flt::gather_or_default(&self.red, index),
+                      Flt::gather_or_default(&self.green, index),
+                      Flt::gather_or_default(&self.blue, index))
+    }
+
+    #[cfg(not(feature = "use-simd"))]
+    #[inline]
+    fn lookup(&self, t: Flt) -> PixelRgb {
+        let index = (((t - t.floor()) * PALETTE_LEN as f32) as usize).min(PALETTE_LEN - 1);
+        PixelRgb::new(self.red[index], self.green[index], self.blue[index])
+    }
+}
+
+impl Mixer<Flt> for PaletteMixer {
+    type IntermediateH = [Flt; 6];
+    type IntermediateV = [Flt; 6];
+
+    #[inline]
+    fn mix_pixels(&self, vxp: &Self::IntermediateH, vyp: &Self::IntermediateV, next_pixel: &mut dyn FnMut(PixelRgb)) {
+        const HALF: Flt = csplat(0.5);
+        let field = compose4(vxp[0], vxp[1], vyp[0], vyp[1])
+                    + compose4(vxp[2], vxp[3], vyp[2], vyp[3])
+                    + compose4(vxp[4], vxp[5], vyp[4], vyp[5]);
+        next_pixel(self.lookup(field * HALF + Flt::sc_splat(self.offset)));
+    }
+}
+
+#[inline]
+fn compose4(x1: Flt, x2: Flt, y1: Flt, y2: Flt) -> Flt {
+    const THIRD: Flt = csplat(1.0 / 3.0);
+    (x1 + y1 * x2 + y2) * THIRD
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{mixers::*, *};
+
+    #[test]
+    fn gradient_works() {
+        let mixer = PaletteMixer::from_gradient(&[(0.0, [0, 0, 0]), (0.5, [255, 128, 0])]);
+        assert_eq!(mixer.red[0], 0.0);
+        assert_eq!(mixer.red[64], 128.0 / 255.0);
+        assert_eq!(mixer.red[128], 1.0);
+        assert_eq!(mixer.green[128], 128.0 / 255.0);
+        assert_eq!(mixer.red[192], 128.0 / 255.0);
+        assert!(mixer.red[255] < mixer.red[254]);
+        let single = PaletteMixer::from_gradient(&[(0.3, [10, 20, 30])]);
+        assert!(single.blue.iter().all(|&b| b == 30.0 / 255.0));
+        assert_eq!(PaletteMixer::try_from_gradient(&[]), Err(PlasmaError::InvalidGradient));
+        assert_eq!(PaletteMixer::try_from_gradient(&[(0.5, [0; 3]), (0.2, [0; 3])]), Err(PlasmaError::InvalidGradient));
+        assert_eq!(PaletteMixer::try_from_gradient(&[(1.5, [0; 3])]), Err(PlasmaError::InvalidGradient));
+    }
+
+    #[test]
+    fn palette_cycling_works() {
+        let mut palette = [[0u8; 3]; PALETTE_LEN];
+        for (i, color) in palette.iter_mut().enumerate() {
+            *color = [i as u8, 255 - i as u8, 0];
+        }
+        let mut mixer = PaletteMixer::from_palette(&palette);
+        let (plasma, _) = Plasma::new_seeded(32, 32, PhaseAmpCfg::new(80.0, 200.0), 7);
+        let pitch = 32 * PixelBufRGB24::PIXEL_BYTES;
+        let render = |mixer: &PaletteMixer| {
+            let mut buffer = vec![0u8; pitch * 32];
+            plasma.render_with::<PixelBufRGB24, PlasmaICP, _>(mixer, &mut buffer, pitch, None);
+            buffer
+        };
+        let frame0 = render(&mixer);
+        assert!(frame0.chunks_exact(3).all(|rgb| rgb[0] as u32 + rgb[1] as u32 == 255 && rgb[2] == 0));
+        mixer.cycle(0.25);
+        assert_eq!(mixer.offset(), 0.25);
+        assert_ne!(render(&mixer), frame0);
+        mixer.cycle(0.75);
+        assert_eq!(mixer.offset(), 0.0);
+        assert_eq!(render(&mixer), frame0);
+        mixer.set_offset(-0.25);
+        assert_eq!(mixer.offset(), 0.75);
+    }
+}
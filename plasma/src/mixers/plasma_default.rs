@@ -10,64 +10,142 @@ use crate::m_polyfill::*;
 pub type PlasmaICP<'a> = PlasmaInterCalcProducer<'a, [PhaseAmp]>;
 /// A convenient type to be used with [crate::plasma::render_part].
 pub type PlasmaICPExtPa<'a> = PlasmaInterCalcProducer<'a, [f32]>;
+/// A convenient type to be used with [crate::plasma::Plasma::render] or
+/// [crate::plasma::Plasma::render_part] with the component frequencies multiplied by [Harmonics].
+pub type PlasmaHarmonicsICP<'a, H> = PlasmaInterCalcProducer<'a, [PhaseAmp], H>;
+/// A convenient type to be used with [crate::plasma::render_part] with the component frequencies
+/// multiplied by [Harmonics].
+pub type PlasmaHarmonicsICPExtPa<'a, H> = PlasmaInterCalcProducer<'a, [f32], H>;
+
+/// Provides integer frequency multipliers of the phase'n'amplitude components for the
+/// [PlasmaInterCalcProducer].
+///
+/// With the multiplier `n` a component makes `n` full waves across the plasma instead of one,
+/// so the plasma remains seamlessly tileable.
+pub trait Harmonics {
+    /// Returns the frequency multiplier of the component at `index`.
+    fn multiplier(index: usize) -> u32;
+}
+
+/// The [Harmonics] with all multipliers equal to 1.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Fundamental;
+
+/// The [Harmonics] with multipliers 1, 2 and 3 assigned to the subsequent pairs of components.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Overtones;
+
+impl Harmonics for Fundamental {
+    #[inline]
+    fn multiplier(_index: usize) -> u32 { 1 }
+}
+
+impl Harmonics for Overtones {
+    #[inline]
+    fn multiplier(index: usize) -> u32 { 1 + (index / 2 % 3) as u32 }
+}
 
 /// A default implementation of a [Mixer] is provided for this struct.
-#[derive(Debug, Copy, Clone, PartialEq, Constructor)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Constructor)]
 pub struct PlasmaMixer;
 
+/// A variant of the [PlasmaMixer] picking the colors in the Oklch color space.
+///
+/// Unlike with the HSV colors of the [PlasmaMixer] the perceived brightness doesn't change with
+/// the hue, so the gradients look smooth. The rendered colors are in the linear RGB color space,
+/// so the mixer should be rendered into the [PixelBufSrgb](crate::PixelBufSrgb) wrapped buffers,
+/// e.g. `PixelBufSrgb<PixelBufRGB24>`, which encode them to sRGB.
+///
+/// This mixer uses the same intermediate data as the [PlasmaMixer], so it should be used with
+/// the [PlasmaICP] or [PlasmaICPExtPa] producers.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Constructor)]
+pub struct PlasmaOklchMixer;
+
 /// Provides a default implementation of a [IntermediateCalculator].
 pub struct PlasmaLineCalc {
     amplitude1: Flt,
     phase1:     Flt,
+    frequency1: Flt,
     amplitude2: Flt,
     phase2:     Flt,
+    frequency2: Flt,
     normal:     Flt,
 }
 
 /// Provides a default implementation of an iterator of [PlasmaLineCalc].
-pub struct PlasmaMixIter<'a, P: PhaseAmpsSelect<'a> + ?Sized> {
+pub struct PlasmaMixIter<'a, P: PhaseAmpsSelect<'a> + ?Sized, H = Fundamental> {
     pa_pair_iter: <P as PhaseAmpsSelect<'a>>::IterPair,
+    index:        usize,
+    harmonics:    PhantomData<H>,
 }
 
 /// Provides a default implementation of a [IntermediateCalculatorProducer].
-pub struct PlasmaInterCalcProducer<'a, P: 'a + PhaseAmpsSelect<'a> + ?Sized>(PhantomData<&'a P>);
+///
+/// The first half of the phase'n'amplitude components is used for the horizontal intermediate data
+/// and the second half for the vertical one. Each [PlasmaLineCalc] is made of a pair of components.
+///
+/// The frequencies of the components are multiplied by the [Harmonics] `H`.
+pub struct PlasmaInterCalcProducer<'a, P: 'a + PhaseAmpsSelect<'a> + ?Sized, H = Fundamental>(PhantomData<(&'a P, H)>);
 
-impl<'a, P> Iterator for PlasmaMixIter<'a, P> where P: PhaseAmpsSelect<'a> + ?Sized
+impl<'a, P, H> PlasmaMixIter<'a, P, H>
+    where P: PhaseAmpsSelect<'a> + ?Sized,
+          H: Harmonics
+{
+    fn new(pa: &'a P, index: usize) -> Self {
+        PlasmaMixIter { pa_pair_iter: pa.iter_pa_pairs(), index, harmonics: PhantomData }
+    }
+}
+
+impl<'a, P, H> Iterator for PlasmaMixIter<'a, P, H>
+    where P: PhaseAmpsSelect<'a> + ?Sized,
+          H: Harmonics
 {
     type Item = PlasmaLineCalc;
 
     fn next(&mut self) -> Option<Self::Item> {
-        match self.pa_pair_iter.next() {
-            Some((pa1, pa2)) => Some(PlasmaLineCalc { amplitude1: Flt::sc_splat(pa1.amplitude()),
-                                                      phase1:     Flt::sc_splat(pa1.phase()),
-                                                      amplitude2: Flt::sc_splat(pa2.amplitude()),
-                                                      phase2:     Flt::sc_splat(pa2.phase()),
-                                                      normal:     Flt::sc_splat(pa1.amplitude() + pa2.amplitude()), }),
-            None => None,
-        }
+        let index = self.index;
+        self.pa_pair_iter.next().map(|(pa1, pa2)| {
+                                    self.index += 2;
+                                    PlasmaLineCalc { amplitude1: Flt::sc_splat(pa1.amplitude()),
+                                                     phase1:     Flt::sc_splat(pa1.phase()),
+                                                     frequency1: Flt::sc_splat(H::multiplier(index) as f32),
+                                                     amplitude2: Flt::sc_splat(pa2.amplitude()),
+                                                     phase2:     Flt::sc_splat(pa2.phase()),
+                                                     frequency2: Flt::sc_splat(H::multiplier(index + 1) as f32),
+                                                     normal:     Flt::sc_splat(pa1.amplitude() + pa2.amplitude()), }
+                                })
     }
 
     #[inline]
     fn size_hint(&self) -> (usize, Option<usize>) { self.pa_pair_iter.size_hint() }
 }
 
-impl<'a, P> ExactSizeIterator for PlasmaMixIter<'a, P> where P: PhaseAmpsSelect<'a> + ?Sized
+impl<'a, P, H> ExactSizeIterator for PlasmaMixIter<'a, P, H>
+    where P: PhaseAmpsSelect<'a> + ?Sized,
+          H: Harmonics
 {
     #[inline]
     fn len(&self) -> usize { self.pa_pair_iter.len() }
 }
 
-impl<'a, P> IntermediateCalculatorProducer<'a, P, Flt> for PlasmaInterCalcProducer<'a, P>
-    where P: PhaseAmpsSelect<'a> + ?Sized
+impl<'a, P, H> IntermediateCalculatorProducer<'a, P, Flt> for PlasmaInterCalcProducer<'a, P, H>
+    where P: PhaseAmpsSelect<'a> + ?Sized,
+          H: Harmonics
 {
-    type CalcIterH = PlasmaMixIter<'a, P>;
-    type CalcIterV = PlasmaMixIter<'a, P>;
+    type CalcIterH = PlasmaMixIter<'a, P, H>;
+    type CalcIterV = PlasmaMixIter<'a, P, H>;
     type LineCalcH = PlasmaLineCalc;
     type LineCalcV = PlasmaLineCalc;
 
-    fn compose_h_iter(pa: &'a P) -> Self::CalcIterH { PlasmaMixIter { pa_pair_iter: pa.select(0..12).iter_pa_pairs() } }
+    fn compose_h_iter(pa: &'a P) -> Self::CalcIterH {
+        let half = pa.phase_amps_len() / 2;
+        PlasmaMixIter::new(pa.select(0..half), 0)
+    }
 
-    fn compose_v_iter(pa: &'a P) -> Self::CalcIterV { PlasmaMixIter { pa_pair_iter: pa.select(12..24).iter_pa_pairs() } }
+    fn compose_v_iter(pa: &'a P) -> Self::CalcIterV {
+        let (half, len) = (pa.phase_amps_len() / 2, pa.phase_amps_len());
+        PlasmaMixIter::new(pa.select(half..len), half)
+    }
 }
 
 impl Mixer<Flt> for PlasmaMixer {
@@ -75,7 +153,7 @@ impl Mixer<Flt> for PlasmaMixer {
     type IntermediateV = [Flt; 6];
 
     #[inline]
-    fn mix_pixels(vxp: &Self::IntermediateH, vyp: &Self::IntermediateV, next_pixel: &mut dyn FnMut(PixelRgb)) {
+    fn mix_pixels(&self, vxp: &Self::IntermediateH, vyp: &Self::IntermediateV, next_pixel: &mut dyn FnMut(PixelRgb)) {
         let hue0 = compose4(vxp[0], vxp[1], vyp[0], vyp[1]);
         let hue1 = compose4(vxp[2], vxp[3], vyp[2], vyp[3]);
         let sat0 = compose4(vxp[4], vxp[5], vyp[4], vyp[5]);
@@ -88,6 +166,27 @@ impl Mixer<Flt> for PlasmaMixer {
     }
 }
 
+impl Mixer<Flt> for PlasmaOklchMixer {
+    type IntermediateH = [Flt; 6];
+    type IntermediateV = [Flt; 6];
+
+    #[inline]
+    fn mix_pixels(&self, vxp: &Self::IntermediateH, vyp: &Self::IntermediateV, next_pixel: &mut dyn FnMut(PixelRgb)) {
+        const ZERO: Flt = csplat(0.0);
+        const ONE: Flt = csplat(1.0);
+        let hue = compose4(vxp[0], vxp[1], vyp[0], vyp[1]);
+        let light = compose4(vxp[2], vxp[3], vyp[2], vyp[3]);
+        let chroma = compose4(vxp[4], vxp[5], vyp[4], vyp[5]);
+        let hue = ONE - hue * csplat(1.5);
+        let light = light * csplat(0.2) + csplat(0.7);
+        let chroma = (chroma * csplat(1.5)).abs().sc_min(ONE) * csplat(0.15);
+        let PixelRgb { r, g, b } = PixelRgb::from_oklch(light, chroma, hue);
+        // clip the colors outside of the sRGB gamut
+        let clip = |v: Flt| v.sc_max(ZERO).sc_min(ONE);
+        next_pixel(PixelRgb::new(clip(r), clip(g), clip(b)));
+    }
+}
+
 #[inline]
 fn compose4(x1: Flt, x2: Flt, y1: Flt, y2: Flt) -> Flt {
     const THIRD: Flt = csplat(1.0 / 3.0);
@@ -102,7 +201,65 @@ impl IntermediateCalculator<Flt> for PlasmaLineCalc {
             ZERO
         }
         else {
-            ((v + self.phase1).sin() * self.amplitude1 + (v + self.phase2).cos() * self.amplitude2) / self.normal
+            ((v * self.frequency1 + self.phase1).sin() * self.amplitude1
+             + (v * self.frequency2 + self.phase2).cos() * self.amplitude2)
+            / self.normal
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{color::*, mixer::*, mixers::*, simd_polyfill::*, *};
+    use core::f32::consts::PI;
+
+    #[test]
+    fn harmonics_works() {
+        let phase_amps: Vec<f32> = (0..48).map(|i| i as f32 / 10.0).collect();
+        let calcs: Vec<_> = PlasmaHarmonicsICPExtPa::<Overtones>::compose_h_iter(&phase_amps[..])
+                                .chain(PlasmaHarmonicsICPExtPa::<Overtones>::compose_v_iter(&phase_amps[..]))
+                                .collect();
+        let fundamental: Vec<_> = PlasmaICPExtPa::compose_h_iter(&phase_amps[..])
+                                      .chain(PlasmaICPExtPa::compose_v_iter(&phase_amps[..]))
+                                      .collect();
+        assert_eq!(calcs.len(), 12);
+        assert_eq!(Overtones::multiplier(12), 1);
+        assert_eq!(Overtones::multiplier(17), 3);
+        let mut differ = false;
+        for (calc, fcalc) in calcs.iter().zip(fundamental.iter()) {
+            for i in 0..16 {
+                let v = Flt::sc_splat(i as f32 * PI / 8.0);
+                let res = calc.calculate(v).sc_first_lane();
+                let res2 = calc.calculate(v + Flt::sc_splat(2.0 * PI)).sc_first_lane();
+                assert!((res - res2).abs() < 1e-4);
+                differ |= res != fcalc.calculate(v).sc_first_lane();
+            }
+        }
+        assert!(differ);
+    }
+
+    #[test]
+    fn oklch_works() {
+        // the standard deviation of the luminance of the rendered pixels
+        fn luminance_deviation(buffer: &[u8]) -> f32 {
+            let luminance: Vec<f32> = buffer.chunks_exact(3)
+                                            .map(|rgb| {
+                                                let v = |c: u8| Flt::sc_splat(c as f32 / 255.0);
+                                                let PixelRgb { r, g, b } = PixelRgb::new(v(rgb[0]), v(rgb[1]), v(rgb[2]))
+                                                                           .to_linear();
+                                                (r * Flt::sc_splat(0.2126) + g * Flt::sc_splat(0.7152)
+                                                 + b * Flt::sc_splat(0.0722)).sc_first_lane()
+                                            })
+                                            .collect();
+            let mean = luminance.iter().sum::<f32>() / luminance.len() as f32;
+            (luminance.iter().map(|l| (l - mean) * (l - mean)).sum::<f32>() / luminance.len() as f32).sqrt()
         }
+        let (plasma, _) = Plasma::new_seeded(48, 48, PhaseAmpCfg::default(), 4);
+        let pitch = 48 * PixelBufRGB24::PIXEL_BYTES;
+        let mut hsv = vec![0u8; pitch * 48];
+        plasma.render::<PixelBufRGB24, PlasmaICP, PlasmaMixer>(&mut hsv, pitch, None);
+        let mut oklch = vec![0u8; pitch * 48];
+        plasma.render::<PixelBufSrgb<PixelBufRGB24>, PlasmaICP, PlasmaOklchMixer>(&mut oklch, pitch, None);
+        assert!(luminance_deviation(&oklch) < luminance_deviation(&hsv) * 0.5);
     }
 }
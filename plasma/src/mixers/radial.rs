@@ -0,0 +1,247 @@
+//! Radial plasma mixer with non-separable per-pixel terms
+use core::{f32::consts::PI, marker::PhantomData};
+use derive_more::{Debug, Constructor};
+use crate::{color::*, mixer::*, phase_amp::*, simd_polyfill::*};
+#[cfg(not(feature = "std"))]
+use crate::m_polyfill::*;
+
+/// A convenient type to be used with [crate::plasma::Plasma::render_terms] or
+/// [crate::plasma::Plasma::render_part_terms].
+pub type PixelWaveTCP<'a> = PixelWaveProducer<'a, [PhaseAmp]>;
+/// A convenient type to be used with [crate::plasma::render_part_terms].
+pub type PixelWaveTCPExtPa<'a> = PixelWaveProducer<'a, [f32]>;
+
+/// The shape of a per-pixel wave computed by the [PixelWaveCalc].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PixelTerm {
+    /// The waves running along the `x + y` diagonal.
+    Diagonal,
+    /// The waves running along the `x - y` diagonal.
+    AntiDiagonal,
+    /// The rings around the center of the plasma.
+    ///
+    /// Unlike the other terms these waves are not periodic, so the plasma stops being tileable.
+    Radial,
+}
+
+/// A [PixelTermsMixer] combining the default plasma with the diagonal and radial waves.
+///
+/// Should be rendered with the [PlasmaICP](crate::PlasmaICP) intermediate data and the [PixelWaveTCP]
+/// per-pixel terms with [render_part_terms](crate::render_part_terms). If rendered with
+/// [render_part](crate::render_part) only the leading [separable](Mixer::separable_phase_amps_len)
+/// components are composed and the per-pixel terms are zero.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Constructor)]
+pub struct RadialMixer;
+
+/// Computes a pair of waves of the [PixelTerm] for a pair of components.
+pub struct PixelWaveCalc {
+    term:       PixelTerm,
+    amplitude1: Flt,
+    phase1:     Flt,
+    amplitude2: Flt,
+    phase2:     Flt,
+    normal:     Flt,
+}
+
+/// An iterator of [PixelWaveCalc].
+///
+/// The subsequent calculators are assigned the [PixelTerm::Diagonal], [PixelTerm::AntiDiagonal]
+/// and [PixelTerm::Radial] terms in turn.
+pub struct PixelWaveMixIter<'a, P: PhaseAmpsSelect<'a> + ?Sized> {
+    pa_pair_iter: <P as PhaseAmpsSelect<'a>>::IterPair,
+    index:        usize,
+}
+
+/// Provides the [PixelTermCalculatorProducer] of the [PixelWaveCalc].
+pub struct PixelWaveProducer<'a, P: 'a + PhaseAmpsSelect<'a> + ?Sized>(PhantomData<&'a P>);
+
+impl PixelTerm {
+    /// Returns the argument of the wave for the given horizontal and vertical angles.
+    #[inline]
+    pub fn value(self, x: Flt, y: Flt) -> Flt {
+        match self {
+            PixelTerm::Diagonal => x + y,
+            PixelTerm::AntiDiagonal => x - y,
+            PixelTerm::Radial => {
+                const CENTER: Flt = csplat(PI);
+                const TWO: Flt = csplat(2.0);
+                let (dx, dy) = (x - CENTER, y - CENTER);
+                (dx * dx + dy * dy).sqrt() * TWO
+            }
+        }
+    }
+
+    fn from_index(index: usize) -> Self {
+        match index % 3 {
+            0 => PixelTerm::Diagonal,
+            1 => PixelTerm::AntiDiagonal,
+            _ => PixelTerm::Radial,
+        }
+    }
+}
+
+impl<'a, P> Iterator for PixelWaveMixIter<'a, P>
+    where P: PhaseAmpsSelect<'a> + ?Sized
+{
+    type Item = PixelWaveCalc;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let term = PixelTerm::from_index(self.index);
+        self.pa_pair_iter.next().map(|(pa1, pa2)| {
+                                    self.index += 1;
+                                    PixelWaveCalc { term,
+                                                    amplitude1: Flt::sc_splat(pa1.amplitude()),
+                                                    phase1:     Flt::sc_splat(pa1.phase()),
+                                                    amplitude2: Flt::sc_splat(pa2.amplitude()),
+                                                    phase2:     Flt::sc_splat(pa2.phase()),
+                                                    normal:     Flt::sc_splat(pa1.amplitude() + pa2.amplitude()) }
+                                })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) { self.pa_pair_iter.size_hint() }
+}
+
+impl<'a, P> ExactSizeIterator for PixelWaveMixIter<'a, P>
+    where P: PhaseAmpsSelect<'a> + ?Sized
+{
+    #[inline]
+    fn len(&self) -> usize { self.pa_pair_iter.len() }
+}
+
+impl<'a, P> PixelTermCalculatorProducer<'a, P, Flt> for PixelWaveProducer<'a, P>
+    where P: PhaseAmpsSelect<'a> + ?Sized
+{
+    type CalcIterP = PixelWaveMixIter<'a, P>;
+    type TermCalc = PixelWaveCalc;
+
+    fn compose_p_iter(pa: &'a P) -> Self::CalcIterP {
+        PixelWaveMixIter { pa_pair_iter: pa.iter_pa_pairs(), index: 0 }
+    }
+}
+
+impl Mixer<Flt> for RadialMixer {
+    type IntermediateH = [Flt; 4];
+    type IntermediateV = [Flt; 4];
+
+    /// Includes the components of the per-pixel terms.
+    #[inline]
+    fn phase_amps_len() -> usize { Self::separable_phase_amps_len() + Self::pixel_phase_amps_len() }
+
+    #[inline]
+    fn separable_phase_amps_len() -> usize { 2 * (Self::intermediate_h_len() + Self::intermediate_v_len()) }
+
+    #[inline]
+    fn mix_pixels(&self, vxp: &Self::IntermediateH, vyp: &Self::IntermediateV, next_pixel: &mut dyn FnMut(PixelRgb)) {
+        self.mix_pixels_terms(vxp, vyp, &Default::default(), next_pixel)
+    }
+}
+
+impl PixelTermsMixer<Flt> for RadialMixer {
+    type IntermediateP = [Flt; 3];
+
+    #[inline]
+    fn mix_pixels_terms(&self, vxp: &Self::IntermediateH, vyp: &Self::IntermediateV, vpp: &Self::IntermediateP,
+                        next_pixel: &mut dyn FnMut(PixelRgb)) {
+        const QUARTER: Flt = csplat(0.25);
+        const HALF: Flt = csplat(0.5);
+        const ONE: Flt = csplat(1.0);
+        const HUE_SCALE: Flt = csplat(1.5);
+        let hue = (vxp[0] * vyp[1] + vyp[0] * vxp[1] + vpp[0] + vpp[1]) * QUARTER;
+        let sat = ((vxp[2] + vyp[2]) * HALF).abs().sc_min(ONE);
+        let val = (vpp[2] + vxp[3] * vyp[3]) * QUARTER + HALF;
+        next_pixel(PixelRgb::from_hsv(ONE - hue * HUE_SCALE, ONE - sat * HALF, val));
+    }
+}
+
+impl PixelTermCalculator<Flt> for PixelWaveCalc {
+    #[inline]
+    fn calculate(&self, x: Flt, y: Flt) -> Flt {
+        const ZERO: Flt = csplat(0.0);
+        if self.normal == ZERO {
+            ZERO
+        }
+        else {
+            let v = self.term.value(x, y);
+            ((v + self.phase1).sin() * self.amplitude1 + (v + self.phase2).cos() * self.amplitude2) / self.normal
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{mixers::*, simd_polyfill::*, *};
+
+    #[test]
+    fn radial_works() {
+        assert_eq!(RadialMixer::phase_amps_len(), 22);
+        assert_eq!(RadialMixer::pixel_phase_amps_len(), 6);
+        let (mut plasma, mut rng) = Plasma::new_seeded(48, 48, PhaseAmpCfg::default(), 6);
+        plasma.set_phase_amps_len(RadialMixer::phase_amps_len(), &mut rng);
+        let pitch = 48 * PixelBufRGB24::PIXEL_BYTES;
+        let mut buffer = vec![0u8; pitch * 48];
+        plasma.try_render_part_terms::<PixelBufRGB24, PlasmaICP, PixelWaveTCP, _>(&RadialMixer, &mut buffer, pitch, 0,
+                                                                                0, 48, 48, None)
+              .unwrap();
+        // the plasma is symmetric around the center if only the radial term is present
+        let mut phase_amps = Vec::new();
+        plasma.export_phase_amps(&mut phase_amps);
+        for amplitude in phase_amps[..2 * 20].iter_mut().skip(1).step_by(2) {
+            *amplitude = 0.0;
+        }
+        render_part_terms::<PixelBufRGB24, PlasmaICPExtPa, PixelWaveTCPExtPa, _, _>(&RadialMixer,
+                                                                                   &mut buffer,
+                                                                                   pitch,
+                                                                                   48,
+                                                                                   48,
+                                                                                   &phase_amps[..],
+                                                                                   0,
+                                                                                   0,
+                                                                                   48,
+                                                                                   48,
+                                                                                   None);
+        let pixel = |x: usize, y: usize| &buffer[y * pitch + x * 3..y * pitch + x * 3 + 3];
+        assert_eq!(pixel(24, 10), pixel(24, 38));
+        assert_eq!(pixel(10, 24), pixel(38, 24));
+        assert_ne!(pixel(24, 10), pixel(24, 20));
+        // the per-pixel terms of the rendered part are the same as of the whole plasma
+        let mut part = vec![0u8; 20 * 3 * 9];
+        render_part_terms::<PixelBufRGB24, PlasmaICPExtPa, PixelWaveTCPExtPa, _, _>(&RadialMixer,
+                                                                                   &mut part,
+                                                                                   20 * 3,
+                                                                                   48,
+                                                                                   48,
+                                                                                   &phase_amps[..],
+                                                                                   13,
+                                                                                   5,
+                                                                                   20,
+                                                                                   9,
+                                                                                   None);
+        for (i, line) in part.chunks_exact(20 * 3).enumerate() {
+            assert_eq!(line, &buffer[(5 + i) * pitch + 13 * 3..(5 + i) * pitch + 33 * 3]);
+        }
+        // without the per-pixel terms the plasma is rendered as if their amplitudes were zero
+        plasma.try_render_with::<PixelBufRGB24, PlasmaICP, _>(&RadialMixer, &mut buffer, pitch, None).unwrap();
+        let mut phase_amps = Vec::new();
+        plasma.export_phase_amps(&mut phase_amps);
+        for amplitude in phase_amps[2 * 16..].iter_mut().skip(1).step_by(2) {
+            *amplitude = 0.0;
+        }
+        let mut terms = vec![0u8; pitch * 48];
+        render_part_terms::<PixelBufRGB24, PlasmaICPExtPa, PixelWaveTCPExtPa, _, _>(&RadialMixer,
+                                                                                   &mut terms,
+                                                                                   pitch,
+                                                                                   48,
+                                                                                   48,
+                                                                                   &phase_amps[..],
+                                                                                   0,
+                                                                                   0,
+                                                                                   48,
+                                                                                   48,
+                                                                                   None);
+        assert_eq!(buffer, terms);
+        let calc = PixelWaveTCPExtPa::compose_p_iter(&[0.0, 1.0, 0.0, 0.0][..]).next().unwrap();
+        let v = Flt::sc_splat(1.0);
+        assert_eq!(calc.calculate(v, v), (v + v).sin());
+    }
+}
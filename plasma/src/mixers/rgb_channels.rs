@@ -0,0 +1,104 @@
+//! RGB channels plasma mixer
+use derive_more::{Debug, Constructor};
+use crate::{color::*, mixer::*, mixers::wave::*, phase_amp::*, simd_polyfill::*};
+#[cfg(not(feature = "std"))]
+use crate::m_polyfill::*;
+
+/// A convenient type to be used with [crate::plasma::Plasma::render] or
+/// [crate::plasma::Plasma::render_part] with the [RgbChannelsMixer].
+pub type RgbChannelsICP<'a> = RgbChannelsInterCalcProducer<'a, [PhaseAmp]>;
+/// A convenient type to be used with [crate::plasma::render_part] with the [RgbChannelsMixer].
+pub type RgbChannelsICPExtPa<'a> = RgbChannelsInterCalcProducer<'a, [f32]>;
+
+/// Provides the [IntermediateCalculatorProducer] for the [RgbChannelsMixer].
+///
+/// Each [RgbChannelsLineCalc] is made of a triple of components.
+pub type RgbChannelsInterCalcProducer<'a, P> = GroupInterCalcProducer<'a, P, RgbChannelsLineCalc, 3>;
+
+/// A [Mixer] rendering each of the red, green and blue channels from its own set of components.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Constructor)]
+pub struct RgbChannelsMixer;
+
+/// Computes a sum of the fundamental wave and its two harmonics of a triple of components
+/// for the [RgbChannelsMixer].
+pub struct RgbChannelsLineCalc {
+    amplitudes: [Flt; 3],
+    phases:     [Flt; 3],
+    normal:     Flt,
+}
+
+crate::plasma_mixer! {
+    impl RgbChannelsMixer {
+        components = 3;
+        intermediate = [3, 3];
+        fn mix(&self, vxp, vyp) {
+            const QUARTER: Flt = csplat(0.25);
+            const HALF: Flt = csplat(0.5);
+            let channel = |x: Flt, y: Flt| (x + y) * QUARTER + HALF;
+            PixelRgb::new(channel(vxp[0], vyp[0]), channel(vxp[1], vyp[1]), channel(vxp[2], vyp[2]))
+        }
+    }
+}
+
+impl GroupLineCalc<3> for RgbChannelsLineCalc {
+    #[inline]
+    fn from_phase_amps(phases: [f32; 3], amplitudes: [f32; 3]) -> Self {
+        RgbChannelsLineCalc { amplitudes: amplitudes.map(Flt::sc_splat),
+                              phases:     phases.map(Flt::sc_splat),
+                              normal:     Flt::sc_splat(amplitudes[0] + amplitudes[1] + amplitudes[2]) }
+    }
+}
+
+impl IntermediateCalculator<Flt> for RgbChannelsLineCalc {
+    #[inline]
+    fn calculate(&self, v: Flt) -> Flt {
+        const ZERO: Flt = csplat(0.0);
+        const TWO: Flt = csplat(2.0);
+        const THREE: Flt = csplat(3.0);
+        let (amplitudes, phases) = (&self.amplitudes, &self.phases);
+        if self.normal == ZERO {
+            ZERO
+        }
+        else {
+            ((v + phases[0]).sin() * amplitudes[0]
+             + (v * TWO + phases[1]).sin() * amplitudes[1]
+             + (v * THREE + phases[2]).sin() * amplitudes[2])
+            / self.normal
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{mixers::*, *};
+
+    #[test]
+    fn rgb_channels_works() {
+        assert_eq!(RgbChannelsMixer::phase_amps_len(), 18);
+        let (mut plasma, mut rng) = Plasma::new_seeded(32, 24, PhaseAmpCfg::default(), 2);
+        plasma.set_phase_amps_len(RgbChannelsMixer::phase_amps_len(), &mut rng);
+        let pitch = 32 * PixelBufRGB24::PIXEL_BYTES;
+        let mut buffer = vec![0u8; pitch * 24];
+        plasma.try_render::<PixelBufRGB24, RgbChannelsICP, RgbChannelsMixer>(&mut buffer, pitch, None).unwrap();
+        assert!(buffer.chunks_exact(3).any(|rgb| rgb[0] != rgb[1]));
+        assert!(buffer.chunks_exact(3).any(|rgb| rgb[1] != rgb[2]));
+        // the red channel depends only on the first triple of components of each direction
+        let mut phase_amps = Vec::new();
+        plasma.export_phase_amps(&mut phase_amps);
+        phase_amps[2 * 3 + 1] = 0.0;
+        phase_amps[2 * 12 + 1] = 0.0;
+        let mut buffer2 = vec![0u8; pitch * 24];
+        render_part::<PixelBufRGB24, RgbChannelsICPExtPa, RgbChannelsMixer, _>(&mut buffer2,
+                                                                                pitch,
+                                                                                32,
+                                                                                24,
+                                                                                &phase_amps[..],
+                                                                                0,
+                                                                                0,
+                                                                                32,
+                                                                                24,
+                                                                                None);
+        assert!(buffer.chunks_exact(3).zip(buffer2.chunks_exact(3)).all(|(a, b)| a[0] == b[0]));
+        assert_ne!(buffer, buffer2);
+    }
+}
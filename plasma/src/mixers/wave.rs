@@ -0,0 +1,254 @@
+//! Generic intermediate calculators for custom mixers
+use core::marker::PhantomData;
+use crate::{mixer::*, phase_amp::*, simd_polyfill::*};
+#[cfg(not(feature = "std"))]
+use crate::m_polyfill::*;
+
+/// A convenient type to be used with [crate::plasma::Plasma::render] or
+/// [crate::plasma::Plasma::render_part] with mixers defined by [plasma_mixer](crate::plasma_mixer).
+pub type WaveICP<'a, const N: usize = 2> = WaveInterCalcProducer<'a, [PhaseAmp], N>;
+/// A convenient type to be used with [crate::plasma::render_part] with mixers defined by
+/// [plasma_mixer](crate::plasma_mixer).
+pub type WaveICPExtPa<'a, const N: usize = 2> = WaveInterCalcProducer<'a, [f32], N>;
+
+/// Provides a generic [IntermediateCalculatorProducer] of the `C` calculators, each one made of a group
+/// of `N` subsequent components.
+///
+/// The first half of the phase'n'amplitude components is used for the horizontal intermediate data
+/// and the second half for the vertical one.
+pub struct GroupInterCalcProducer<'a, P: 'a + PhaseAmpsSelect<'a> + ?Sized, C, const N: usize>(PhantomData<(&'a P, C)>);
+
+/// An iterator of the `C` calculators made of the subsequent groups of `N` components.
+pub struct GroupMixIter<'a, P: PhaseAmpsSelect<'a> + ?Sized, C, const N: usize> {
+    pa_iter: <P as PhaseAmpsSelect<'a>>::IterOne,
+    calc:    PhantomData<C>,
+}
+
+/// Implementations of this trait should create an [IntermediateCalculator] from a group of `N`
+/// subsequent phase'n'amplitude components.
+///
+/// The calculators are produced by the [GroupInterCalcProducer].
+pub trait GroupLineCalc<const N: usize>: IntermediateCalculator<Flt> + Sized {
+    /// Creates the calculator from the `phases` and the `amplitudes` of the group of components.
+    fn from_phase_amps(phases: [f32; N], amplitudes: [f32; N]) -> Self;
+}
+
+/// Computes a sum of `N` waves of the subsequent components.
+///
+/// The even components are sine and the odd ones are cosine waves, the frequency of each
+/// subsequent pair of waves is increased by one. The sum is normalized by the sum of the amplitudes,
+/// so with a single component the amplitude has no effect.
+pub struct WaveLineCalc<const N: usize> {
+    amplitudes: [Flt; N],
+    phases:     [Flt; N],
+    normal:     Flt,
+}
+
+/// An iterator of [WaveLineCalc] made of the subsequent groups of `N` components.
+pub type WaveMixIter<'a, P, const N: usize> = GroupMixIter<'a, P, WaveLineCalc<N>, N>;
+
+/// Provides a generic [IntermediateCalculatorProducer] of [WaveLineCalc].
+///
+/// Each [WaveLineCalc] is made of `N` components, see [GroupInterCalcProducer].
+pub type WaveInterCalcProducer<'a, P, const N: usize = 2> = GroupInterCalcProducer<'a, P, WaveLineCalc<N>, N>;
+
+impl<'a, P, C, const N: usize> Iterator for GroupMixIter<'a, P, C, N>
+    where P: PhaseAmpsSelect<'a> + ?Sized,
+          C: GroupLineCalc<N>
+{
+    type Item = C;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pa_iter.len() < N {
+            return None;
+        }
+        let (mut phases, mut amplitudes) = ([0.0; N], [0.0; N]);
+        for ((phase, amplitude), pa) in phases.iter_mut().zip(amplitudes.iter_mut()).zip(&mut self.pa_iter) {
+            *phase = pa.phase();
+            *amplitude = pa.amplitude();
+        }
+        Some(C::from_phase_amps(phases, amplitudes))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl<'a, P, C, const N: usize> ExactSizeIterator for GroupMixIter<'a, P, C, N>
+    where P: PhaseAmpsSelect<'a> + ?Sized,
+          C: GroupLineCalc<N>
+{
+    #[inline]
+    fn len(&self) -> usize { self.pa_iter.len() / N }
+}
+
+impl<'a, P, C, const N: usize> IntermediateCalculatorProducer<'a, P, Flt> for GroupInterCalcProducer<'a, P, C, N>
+    where P: PhaseAmpsSelect<'a> + ?Sized,
+          C: GroupLineCalc<N>
+{
+    type CalcIterH = GroupMixIter<'a, P, C, N>;
+    type CalcIterV = GroupMixIter<'a, P, C, N>;
+    type LineCalcH = C;
+    type LineCalcV = C;
+
+    fn compose_h_iter(pa: &'a P) -> Self::CalcIterH {
+        let half = pa.phase_amps_len() / 2;
+        GroupMixIter { pa_iter: pa.select(0..half).iter_phase_amps(), calc: PhantomData }
+    }
+
+    fn compose_v_iter(pa: &'a P) -> Self::CalcIterV {
+        let (half, len) = (pa.phase_amps_len() / 2, pa.phase_amps_len());
+        GroupMixIter { pa_iter: pa.select(half..len).iter_phase_amps(), calc: PhantomData }
+    }
+}
+
+impl<const N: usize> GroupLineCalc<N> for WaveLineCalc<N> {
+    fn from_phase_amps(phases: [f32; N], amplitudes: [f32; N]) -> Self {
+        WaveLineCalc { amplitudes: amplitudes.map(Flt::sc_splat),
+                       phases:     phases.map(Flt::sc_splat),
+                       normal:     Flt::sc_splat(amplitudes.iter().sum()) }
+    }
+}
+
+impl<const N: usize> IntermediateCalculator<Flt> for WaveLineCalc<N> {
+    #[inline]
+    fn calculate(&self, v: Flt) -> Flt {
+        const ZERO: Flt = csplat(0.0);
+        if self.normal == ZERO {
+            return ZERO;
+        }
+        let mut sum = ZERO;
+        let mut frequency = csplat(1.0);
+        for (i, (&amplitude, &phase)) in self.amplitudes.iter().zip(self.phases.iter()).enumerate() {
+            if i & 1 == 0 {
+                sum += (v * frequency + phase).sin() * amplitude;
+            }
+            else {
+                sum += (v * frequency + phase).cos() * amplitude;
+                frequency += csplat(1.0);
+            }
+        }
+        sum / self.normal
+    }
+}
+
+/// Implements a [Mixer] for the given type from the number of components per intermediate value,
+/// the lengths of the intermediate data and a `mix` function returning a [PixelRgb](crate::PixelRgb).
+///
+/// The mixer should be rendered with the [GroupInterCalcProducer] with the same number of components,
+/// e.g. [WaveICP]. The `mix` function works on the [Flt] type, so the same definition compiles
+/// with and without the "use-simd" feature.
+///
+/// # Example
+///
+/// ```
+/// use plasma::*;
+///
+/// #[derive(Debug, Clone, Copy, Default)]
+/// pub struct StripesMixer;
+///
+/// plasma_mixer! {
+///     impl StripesMixer {
+///         components = 2;
+///         intermediate = [2, 2];
+///         fn mix(&self, vxp, vyp) {
+///             const HALF: Flt = csplat(0.5);
+///             let v = (vxp[0] * vyp[0] + vxp[1] * vyp[1]) * HALF + HALF;
+///             PixelRgb::new(v, v * HALF, HALF)
+///         }
+///     }
+/// }
+///
+/// let (mut plasma, mut rng) = Plasma::new_seeded(32, 32, PhaseAmpCfg::default(), 1);
+/// plasma.set_phase_amps_len(StripesMixer::phase_amps_len(), &mut rng);
+/// let mut buffer = vec![0u8; 32 * 32 * PixelBufRGB24::PIXEL_BYTES];
+/// plasma.render::<PixelBufRGB24, WaveICP<2>, StripesMixer>(&mut buffer, 32 * PixelBufRGB24::PIXEL_BYTES, None);
+/// ```
+#[macro_export]
+macro_rules! plasma_mixer {
+    (
+        impl $mixer:ty {
+            components = $n:literal;
+            intermediate = [$h:literal, $v:literal];
+            fn mix(&$slf:ident, $vxp:ident, $vyp:ident) $body:block
+        }
+    ) => {
+        impl $crate::Mixer<$crate::Flt> for $mixer {
+            type IntermediateH = [$crate::Flt; $h];
+            type IntermediateV = [$crate::Flt; $v];
+
+            #[inline]
+            fn phase_amps_len() -> usize { $n * ($h + $v) }
+
+            #[inline]
+            fn mix_pixels(&$slf, $vxp: &Self::IntermediateH, $vyp: &Self::IntermediateV,
+                          next_pixel: &mut dyn FnMut($crate::PixelRgb)) {
+                next_pixel($body)
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{mixers::*, simd_polyfill::*, *};
+
+    #[derive(Debug, Clone, Copy, Default)]
+    struct DefaultLikeMixer;
+
+    crate::plasma_mixer! {
+        impl DefaultLikeMixer {
+            components = 2;
+            intermediate = [6, 6];
+            fn mix(&self, vxp, vyp) {
+                const THIRD: Flt = csplat(1.0 / 3.0);
+                let compose4 = |x1: Flt, x2: Flt, y1: Flt, y2: Flt| (x1 + y1 * x2 + y2) * THIRD;
+                let hue0 = compose4(vxp[0], vxp[1], vyp[0], vyp[1]);
+                let hue1 = compose4(vxp[2], vxp[3], vyp[2], vyp[3]);
+                let sat0 = compose4(vxp[4], vxp[5], vyp[4], vyp[5]);
+                let hue0 = Flt::sc_splat(1.0) - hue0 * Flt::sc_splat(1.5);
+                let hue1 = hue1 * Flt::sc_splat(3.0);
+                let sat0 = (sat0 * Flt::sc_splat(1.5)).abs().sc_min(Flt::sc_splat(1.0));
+                let rgb0 = PixelRgb::from_hsv(hue0, Flt::sc_splat(1.0), Flt::sc_splat(1.0));
+                let rgb1 = PixelRgb::from_hsv(hue1, sat0, Flt::sc_splat(1.0));
+                rgb0 - rgb1
+            }
+        }
+    }
+
+    #[test]
+    fn plasma_mixer_works() {
+        assert_eq!(DefaultLikeMixer::phase_amps_len(), PlasmaMixer::phase_amps_len());
+        let (plasma, _) = Plasma::new_seeded(40, 24, PhaseAmpCfg::default(), 9);
+        let pitch = 40 * PixelBufRGB24::PIXEL_BYTES;
+        let mut expected = vec![0u8; pitch * 24];
+        plasma.render::<PixelBufRGB24, PlasmaICP, PlasmaMixer>(&mut expected, pitch, None);
+        let mut buffer = vec![0u8; pitch * 24];
+        plasma.render::<PixelBufRGB24, WaveICP, DefaultLikeMixer>(&mut buffer, pitch, None);
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    fn wave_calc_works() {
+        let phase_amps = [0.5, 1.0, 0.25, 0.5, 0.0, 0.25, 1.0, 0.25];
+        let mut iter = WaveICPExtPa::<4>::compose_h_iter(&phase_amps[..]);
+        assert_eq!(iter.len(), 0);
+        assert!(iter.next().is_none());
+        let mut iter = WaveICPExtPa::<1>::compose_v_iter(&phase_amps[..]);
+        assert_eq!(iter.len(), 2);
+        let calc = iter.next().unwrap();
+        let v = Flt::sc_splat(1.0);
+        assert_eq!(calc.calculate(v), (v + Flt::sc_splat(0.0)).sin());
+        let phase_amps: Vec<f32> = phase_amps.iter().chain(phase_amps.iter()).copied().collect();
+        let calc = WaveICPExtPa::<4>::compose_h_iter(&phase_amps[..]).next().unwrap();
+        let expected = ((v + Flt::sc_splat(0.5)).sin() * Flt::sc_splat(1.0)
+                        + (v + Flt::sc_splat(0.25)).cos() * Flt::sc_splat(0.5)
+                        + (v * Flt::sc_splat(2.0)).sin() * Flt::sc_splat(0.25)
+                        + (v * Flt::sc_splat(2.0) + Flt::sc_splat(1.0)).cos() * Flt::sc_splat(0.25))
+                       / Flt::sc_splat(2.0);
+        assert!((calc.calculate(v).sc_first_lane() - expected.sc_first_lane()).abs() < 1e-6);
+    }
+}
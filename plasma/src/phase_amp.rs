@@ -1,15 +1,15 @@
 use core::{
-    f32::{consts::PI, EPSILON},
+    f32::consts::PI,
     ops::Range,
     slice::ChunksExact
 };
 #[cfg(not(feature = "std"))]
 use alloc::vec::Vec;
-#[cfg(not(feature = "std"))]
-use crate::m_polyfill::*;
 
 use rand::Rng;
 
+use crate::{easing::Easing, error::PlasmaError};
+
 const PI2: f32 = 2.0 * PI;
 const PI05: f32 = 0.5 * PI;
 const MIN_STEPS: f32 = 100.0;
@@ -21,6 +21,15 @@ pub trait PhaseAmpConfig {
     fn max_steps(&self) -> f32;
     fn delta_phase_abs_max(&self) -> f32;
     fn delta_delta_phase_abs_max(&self) -> f32;
+    /// The lower bound of the random amplitudes.
+    #[inline]
+    fn amplitude_min(&self) -> f32 { 0.0 }
+    /// The upper bound (exclusive) of the random amplitudes.
+    #[inline]
+    fn amplitude_max(&self) -> f32 { 1.0 }
+    /// The easing curve of the amplitude transitions.
+    #[inline]
+    fn easing(&self) -> Easing { Easing::SineQuartic }
 }
 
 /// Holds parameters of phase and amplitude changes for [PhaseAmpConfig] trait.
@@ -30,6 +39,23 @@ pub struct PhaseAmpCfg {
     max_steps:                 f32,
     delta_phase_abs_max:       f32,
     delta_delta_phase_abs_max: f32,
+    amplitude_min:             f32,
+    amplitude_max:             f32,
+    easing:                    Easing,
+}
+
+/// A builder of the [PhaseAmpCfg] with all of its parameters adjustable.
+///
+/// Create it with [PhaseAmpCfg::builder].
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct PhaseAmpCfgBuilder {
+    min_steps:                 f32,
+    max_steps:                 f32,
+    delta_phase_abs_max:       Option<f32>,
+    delta_delta_phase_abs_max: Option<f32>,
+    amplitude_min:             f32,
+    amplitude_max:             f32,
+    easing:                    Easing,
 }
 
 /// Holds a phase and an amplitude along with their animation state.
@@ -42,6 +68,9 @@ pub struct PhaseAmp {
     delta_amplitude:      f32,
     step_amplitude:       f32,
     transition_amplitude: f32,
+    /// The part of the animation step accumulated by [PhaseAmp::update_by], the `phase` and
+    /// the `transition_amplitude` don't include it, while the `amplitude` does.
+    step_fraction:        f32,
 }
 
 /// A trait for querying and updating phase'n'amplitude
@@ -75,6 +104,11 @@ pub trait PhaseAmpsSelect<'a> {
     ///
     /// __Panics__ if range exceeds the underlying data boundaries.
     fn select(&self, range: Range<usize>) -> &Self;
+    /// Returns the number of phase'n'amplitude components.
+    fn phase_amps_len(&self) -> usize;
+    /// Returns `true` if the data ends with an incomplete phase'n'amplitude component.
+    #[inline]
+    fn has_incomplete_phase_amp(&self) -> bool { false }
     fn iter_phase_amps(&'a self) -> Self::IterOne;
     fn iter_pa_pairs(&'a self) -> Self::IterPair;
     fn iter_pa_triples(&'a self) -> Self::IterTriple;
@@ -83,10 +117,10 @@ pub trait PhaseAmpsSelect<'a> {
 
 impl PhaseAmpAccess for PhaseAmp {
     #[inline]
-    fn phase(&self) -> f32 { self.phase }
+    fn phase(&self) -> f32 { self.phase + self.delta_phase * self.step_fraction }
 
     #[inline]
-    fn set_phase(&mut self, phase: f32) { self.phase = phase; }
+    fn set_phase(&mut self, phase: f32) { self.phase = phase - self.delta_phase * self.step_fraction; }
 
     #[inline]
     fn amplitude(&self) -> f32 { self.amplitude }
@@ -215,6 +249,9 @@ impl<'a> PhaseAmpsSelect<'a> for [PhaseAmp] {
 
     #[inline]
     fn select(&self, range: Range<usize>) -> &[PhaseAmp] { &self[range] }
+
+    #[inline]
+    fn phase_amps_len(&self) -> usize { self.len() }
 }
 
 impl<'a> PhaseAmpsSelect<'a> for [f32] {
@@ -235,6 +272,12 @@ impl<'a> PhaseAmpsSelect<'a> for [f32] {
 
     #[inline]
     fn select(&self, range: Range<usize>) -> &[f32] { &self[range.start * 2..range.end * 2] }
+
+    #[inline]
+    fn phase_amps_len(&self) -> usize { self.len() / 2 }
+
+    #[inline]
+    fn has_incomplete_phase_amp(&self) -> bool { !self.len().is_multiple_of(2) }
 }
 
 impl PhaseAmpDataExp for [PhaseAmp] {
@@ -263,18 +306,25 @@ impl PhaseAmpConfig for PhaseAmpCfg {
     fn delta_phase_abs_max(&self) -> f32 { self.delta_phase_abs_max }
 
     fn delta_delta_phase_abs_max(&self) -> f32 { self.delta_delta_phase_abs_max }
+
+    fn amplitude_min(&self) -> f32 { self.amplitude_min }
+
+    fn amplitude_max(&self) -> f32 { self.amplitude_max }
+
+    fn easing(&self) -> Easing { self.easing }
 }
 
 impl Default for PhaseAmpCfg {
-    fn default() -> Self {
-        PhaseAmpCfg { min_steps:                 MIN_STEPS,
-                      max_steps:                 MAX_STEPS,
-                      delta_phase_abs_max:       PI05 / MIN_STEPS,
-                      delta_delta_phase_abs_max: PI05 / MAX_STEPS / 8.0, }
-    }
+    fn default() -> Self { PhaseAmpCfg::new(MIN_STEPS, MAX_STEPS) }
 }
 
 impl PhaseAmpCfg {
+    /// The number of 32bit floats written by [PhaseAmpCfg::export_state].
+    pub const STATE_LEN: usize = 7;
+    /// The number of 32bit floats written by [PhaseAmpCfg::export_state] of the previous versions
+    /// of this crate, before the amplitude range and the easing were added.
+    pub const STATE_LEN_V1: usize = 4;
+
     /// Creates new [PhaseAmpCfg] instance from the provided arguments.
     ///
     /// The arguments define the range `[min, max)` for a number of animation steps
@@ -283,19 +333,160 @@ impl PhaseAmpCfg {
     ///
     /// # Panics
     ///
-    /// __Panics__ if `min_steps` is equal or larger than `max_steps` or
+    /// __Panics__ if `min_steps` is equal or larger than `max_steps`, if `max_steps` is not finite or
     /// if `min_steps` is less than or equal to `1.0`.
-    pub fn new(min_steps: f32, max_steps: f32) -> Self {
-        assert!(min_steps < max_steps, "min steps must be lower than max steps");
-        assert!(min_steps > 1.0, "min steps must be larger than 1.0");
-        PhaseAmpCfg { min_steps,
-                      max_steps,
-                      delta_phase_abs_max: PI05 / min_steps,
-                      delta_delta_phase_abs_max: PI05 / max_steps / 8.0 }
+    pub fn new(min_steps: f32, max_steps: f32) -> Self { PhaseAmpCfg::builder(min_steps, max_steps).build() }
+
+    /// Creates new [PhaseAmpCfg] instance from the provided arguments.
+    ///
+    /// This is a fallible variant of [PhaseAmpCfg::new], returning [PlasmaError::InvalidSteps]
+    /// instead of panicking.
+    pub fn try_new(min_steps: f32, max_steps: f32) -> Result<Self, PlasmaError> {
+        PhaseAmpCfg::builder(min_steps, max_steps).try_build()
+    }
+
+    /// Creates new [PhaseAmpCfgBuilder] instance with the range `[min, max)` for a number of
+    /// animation steps between phase and amplitude transitions.
+    ///
+    /// The other parameters are initialized the same way as by [PhaseAmpCfg::new].
+    pub fn builder(min_steps: f32, max_steps: f32) -> PhaseAmpCfgBuilder {
+        PhaseAmpCfgBuilder { min_steps,
+                             max_steps,
+                             delta_phase_abs_max: None,
+                             delta_delta_phase_abs_max: None,
+                             amplitude_min: 0.0,
+                             amplitude_max: 1.0,
+                             easing: Easing::SineQuartic }
+    }
+
+    /// Exports all of the configuration parameters into the [Vec] of 32bit floats.
+    ///
+    /// Exactly [PhaseAmpCfg::STATE_LEN] values are appended to `out`.
+    pub fn export_state(&self, out: &mut Vec<f32>) {
+        out.extend(&[self.min_steps,
+                     self.max_steps,
+                     self.delta_phase_abs_max,
+                     self.delta_delta_phase_abs_max,
+                     self.amplitude_min,
+                     self.amplitude_max,
+                     self.easing as u32 as f32]);
+    }
+
+    /// Creates new [PhaseAmpCfg] instance from the data exported with [PhaseAmpCfg::export_state].
+    ///
+    /// The data of [PhaseAmpCfg::STATE_LEN_V1] length exported by the previous versions of this crate
+    /// is also accepted.
+    ///
+    /// Returns `None` if the length of `source` is not valid or if the parameters are not valid.
+    pub fn from_state(source: &[f32]) -> Option<Self> {
+        let (amplitude_min, amplitude_max, easing) = match *source {
+            [_, _, _, _] => (0.0, 1.0, Easing::SineQuartic),
+            [_, _, _, _, amplitude_min, amplitude_max, easing] if easing >= 0.0 && easing as u32 as f32 == easing => {
+                (amplitude_min, amplitude_max, Easing::from_index(easing as u32)?)
+            },
+            _ => return None,
+        };
+        PhaseAmpCfgBuilder { min_steps: source[0],
+                             max_steps: source[1],
+                             delta_phase_abs_max: Some(source[2]),
+                             delta_delta_phase_abs_max: Some(source[3]),
+                             amplitude_min,
+                             amplitude_max,
+                             easing }.try_build()
+                                      .ok()
+    }
+}
+
+impl PhaseAmpCfgBuilder {
+    /// Sets the range `[min, max)` of the random amplitudes.
+    ///
+    /// By default the range is `[0, 1)`. Only the relative amplitudes of the components matter, so
+    /// the larger the `min` relative to the `max`, the more evenly the components are mixed together.
+    pub fn amplitude_range(mut self, min: f32, max: f32) -> Self {
+        self.amplitude_min = min;
+        self.amplitude_max = max;
+        self
+    }
+
+    /// Sets the largest absolute phase change in a single animation step - the phase velocity limit.
+    ///
+    /// By default it's `PI / 2 / min_steps`.
+    pub fn delta_phase_abs_max(mut self, delta_phase_abs_max: f32) -> Self {
+        self.delta_phase_abs_max = Some(delta_phase_abs_max);
+        self
+    }
+
+    /// Sets the largest absolute change of the phase velocity in a single animation step - the phase
+    /// acceleration limit.
+    ///
+    /// By default it's `PI / 2 / max_steps / 8`.
+    pub fn delta_delta_phase_abs_max(mut self, delta_delta_phase_abs_max: f32) -> Self {
+        self.delta_delta_phase_abs_max = Some(delta_delta_phase_abs_max);
+        self
+    }
+
+    /// Sets the easing curve of the amplitude transitions.
+    ///
+    /// By default it's [Easing::SineQuartic].
+    pub fn easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Creates new [PhaseAmpCfg] instance from the builder parameters.
+    ///
+    /// # Panics
+    ///
+    /// __Panics__ if `min_steps` is equal or larger than `max_steps`,
+    /// if `min_steps` is less than or equal to `1.0`,
+    /// if the phase velocity or acceleration limit is not a positive number,
+    /// if the amplitude `min` is negative or if it's equal or larger than the amplitude `max`.
+    pub fn build(self) -> PhaseAmpCfg {
+        match self.try_build() {
+            Ok(cfg) => cfg,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Creates new [PhaseAmpCfg] instance from the builder parameters.
+    ///
+    /// This is a fallible variant of [PhaseAmpCfgBuilder::build], returning an error instead of panicking.
+    pub fn try_build(self) -> Result<PhaseAmpCfg, PlasmaError> {
+        let PhaseAmpCfgBuilder { min_steps, max_steps, amplitude_min, amplitude_max, easing, .. } = self;
+        if !(min_steps > 1.0 && min_steps < max_steps && max_steps.is_finite()) {
+            return Err(PlasmaError::InvalidSteps);
+        }
+        let delta_phase_abs_max = self.delta_phase_abs_max.unwrap_or(PI05 / min_steps);
+        let delta_delta_phase_abs_max = self.delta_delta_phase_abs_max.unwrap_or(PI05 / max_steps / 8.0);
+        if !(delta_phase_abs_max > 0.0
+             && delta_phase_abs_max.is_finite()
+             && delta_delta_phase_abs_max > 0.0
+             && delta_delta_phase_abs_max.is_finite())
+        {
+            return Err(PlasmaError::InvalidPhaseLimits);
+        }
+        if !(amplitude_min >= 0.0 && amplitude_min < amplitude_max && amplitude_max.is_finite()) {
+            return Err(PlasmaError::InvalidAmplitudeRange);
+        }
+        Ok(PhaseAmpCfg { min_steps,
+                         max_steps,
+                         delta_phase_abs_max,
+                         delta_delta_phase_abs_max,
+                         amplitude_min,
+                         amplitude_max,
+                         easing })
     }
 }
 
 impl PhaseAmp {
+    /// The number of 32bit floats written by [PhaseAmp::export_state].
+    pub const STATE_LEN: usize = 8;
+    /// The number of 32bit floats written by [PhaseAmp::export_state] of the previous versions
+    /// of this crate, before the accumulated part of the step was added.
+    pub const STATE_LEN_V1: usize = 7;
+    /// The maximum number of steps performed by a single [PhaseAmp::update_by] call.
+    pub const MAX_UPDATE_STEPS: f32 = 1000.0;
+
     /// Creates randomized single phase and amplitude pair.
     pub fn new<C, R>(cfg: &C, rng: &mut R) -> Self
         where C: PhaseAmpConfig,
@@ -304,20 +495,107 @@ impl PhaseAmp {
         let phase = rng.gen_range(0.0..PI2);
         let delta_phase = rng.gen_range(-cfg.delta_phase_abs_max()..cfg.delta_phase_abs_max());
 
-        let amplitude = rng.gen_range(0.0..1.0);
+        let amplitude = rng.gen_range(cfg.amplitude_min()..cfg.amplitude_max());
         let source_amplitude = amplitude;
-        let target_amplitude = rng.gen_range(0.0..1.0);
+        let target_amplitude = rng.gen_range(cfg.amplitude_min()..cfg.amplitude_max());
         let delta_amplitude = target_amplitude - source_amplitude;
         let step_amplitude = rng.gen_range(cfg.min_steps()..cfg.max_steps()).recip();
         let transition_amplitude = 0.0;
 
-        PhaseAmp { phase, delta_phase, amplitude, source_amplitude, delta_amplitude, step_amplitude, transition_amplitude }
+        PhaseAmp { phase,
+                   delta_phase,
+                   amplitude,
+                   source_amplitude,
+                   delta_amplitude,
+                   step_amplitude,
+                   transition_amplitude,
+                   step_fraction: 0.0 }
+    }
+
+    /// Creates a phase and amplitude pair in the middle of the amplitude transition.
+    pub(crate) fn with_transition(phase: f32, delta_phase: f32, source_amplitude: f32, target_amplitude: f32,
+                                  step_amplitude: f32, transition_amplitude: f32, easing: Easing)
+                                  -> Self {
+        let delta_amplitude = target_amplitude - source_amplitude;
+        let amplitude = source_amplitude + easing.ease(transition_amplitude) * delta_amplitude;
+        PhaseAmp { phase,
+                   delta_phase,
+                   amplitude,
+                   source_amplitude,
+                   delta_amplitude,
+                   step_amplitude,
+                   transition_amplitude,
+                   step_fraction: 0.0 }
     }
 
     /// Performs a one step update of the phase and amplitude pair animation.
+    ///
+    /// The part of the step accumulated by [PhaseAmp::update_by] is preserved.
     pub fn update<C, R>(&mut self, cfg: &C, rng: &mut R)
         where C: PhaseAmpConfig,
               R: Rng + ?Sized
+    {
+        self.step(cfg, rng);
+        /* without the accumulated part of the step the amplitude is left exactly as computed by the step */
+        if self.step_fraction != 0.0 {
+            self.set_step_fraction(cfg, self.step_fraction);
+        }
+    }
+
+    /// Performs an update of the phase and amplitude pair animation by a number of steps.
+    ///
+    /// The `steps` argument should be a non-negative number of animation steps, e.g. the time
+    /// elapsed since the last update multiplied by the desired number of steps per second.
+    /// The `steps` larger than [PhaseAmp::MAX_UPDATE_STEPS] are limited to it, so a long pause between
+    /// the updates doesn't stall the animation.
+    ///
+    /// The animation advances in whole steps and the remaining part of the step is accumulated until
+    /// the next call. So the animation depends only on the total number of steps and not on how they are
+    /// split between the calls, e.g. on the frame rate. The accumulated part of the step is reflected
+    /// in the phase and the amplitude.
+    ///
+    /// Unlike [PhaseAmp::update] the steps past the end of an amplitude transition carry over to the next one,
+    /// so the animation diverges from the one created with [PhaseAmp::update] after the first transition.
+    pub fn update_by<C, R>(&mut self, cfg: &C, steps: f32, rng: &mut R)
+        where C: PhaseAmpConfig,
+              R: Rng + ?Sized
+    {
+        let (whole, fraction) = split_steps(self.step_fraction, steps);
+        for _ in 0..whole {
+            self.step_carrying(cfg, rng);
+        }
+        self.set_step_fraction(cfg, fraction);
+    }
+
+    /// Returns the part of the animation step accumulated by [PhaseAmp::update_by].
+    #[inline]
+    pub fn step_fraction(&self) -> f32 { self.step_fraction }
+
+    /// Sets the accumulated part of the animation step and updates the amplitude accordingly.
+    pub(crate) fn set_step_fraction<C: PhaseAmpConfig>(&mut self, cfg: &C, fraction: f32) {
+        self.step_fraction = fraction;
+        let transition = (self.transition_amplitude + self.step_amplitude * fraction).min(1.0);
+        self.amplitude = self.source_amplitude + cfg.easing().ease(transition) * self.delta_amplitude;
+    }
+
+    /// Performs a whole step of the animation like [PhaseAmp::step], carrying the part of the step past
+    /// the end of the amplitude transition over to the next one.
+    pub(crate) fn step_carrying<C, R>(&mut self, cfg: &C, rng: &mut R)
+        where C: PhaseAmpConfig,
+              R: Rng + ?Sized
+    {
+        let overshoot = self.step(cfg, rng);
+        if overshoot > 0.0 {
+            self.transition_amplitude = overshoot * self.step_amplitude;
+        }
+    }
+
+    /// Performs a whole step of the animation, excluding the accumulated part of the step.
+    ///
+    /// Returns the part of the step past the end of the amplitude transition if it has just ended.
+    pub(crate) fn step<C, R>(&mut self, cfg: &C, rng: &mut R) -> f32
+        where C: PhaseAmpConfig,
+              R: Rng + ?Sized
     {
         let delta_phase = self.delta_phase;
         self.phase += delta_phase;
@@ -334,25 +612,104 @@ impl PhaseAmp {
             },
         };
         self.transition_amplitude += self.step_amplitude;
-        self.amplitude = self.source_amplitude + transform(self.transition_amplitude) * self.delta_amplitude;
-        if self.transition_amplitude > 1.0 - EPSILON {
-            self.source_amplitude = self.source_amplitude + transform(1.0) * self.delta_amplitude;
-            let target_amplitude = rng.gen_range(0.0..1.0);
+        let easing = cfg.easing();
+        self.amplitude = self.source_amplitude + easing.ease(self.transition_amplitude) * self.delta_amplitude;
+        let mut overshoot = 0.0;
+        if self.transition_amplitude > 1.0 - f32::EPSILON {
+            overshoot = (self.transition_amplitude - 1.0).max(0.0) / self.step_amplitude;
+            self.source_amplitude += easing.ease(1.0) * self.delta_amplitude;
+            let target_amplitude = rng.gen_range(cfg.amplitude_min()..cfg.amplitude_max());
             self.delta_amplitude = target_amplitude - self.source_amplitude;
             self.step_amplitude = rng.gen_range(cfg.min_steps()..cfg.max_steps()).recip();
             self.transition_amplitude = 0.0;
         }
+        overshoot
+    }
+
+    /// Exports the complete animation state into the [Vec] of 32bit floats.
+    ///
+    /// Unlike [PhaseAmpAccess::export] this includes the phase velocity and the amplitude transition
+    /// state, so the animation restored with [PhaseAmp::from_state] continues along the same trajectory.
+    /// Exactly [PhaseAmp::STATE_LEN] values are appended to `out`.
+    pub fn export_state(&self, out: &mut Vec<f32>) {
+        out.extend(&[self.phase,
+                     self.delta_phase,
+                     self.amplitude,
+                     self.source_amplitude,
+                     self.delta_amplitude,
+                     self.step_amplitude,
+                     self.transition_amplitude,
+                     self.step_fraction]);
+    }
+
+    /// Creates new [PhaseAmp] instance from the data exported with [PhaseAmp::export_state].
+    ///
+    /// The data of [PhaseAmp::STATE_LEN_V1] length exported by the previous versions of this crate
+    /// is also accepted.
+    ///
+    /// Returns `None` if the length of `source` is not valid, if any of the values is not finite
+    /// or if the accumulated part of the step is not in the range `[0, 1)`.
+    pub fn from_state(source: &[f32]) -> Option<Self> {
+        if !source.iter().all(|v| v.is_finite()) {
+            return None;
+        }
+        let (state, step_fraction) = match *source {
+            [.., step_fraction] if source.len() == Self::STATE_LEN => (&source[..Self::STATE_LEN_V1], step_fraction),
+            _ => (source, 0.0),
+        };
+        if !(0.0..1.0).contains(&step_fraction) {
+            return None;
+        }
+        match *state {
+            [phase, delta_phase, amplitude, source_amplitude, delta_amplitude, step_amplitude, transition_amplitude] => {
+                Some(PhaseAmp { phase,
+                                delta_phase,
+                                amplitude,
+                                source_amplitude,
+                                delta_amplitude,
+                                step_amplitude,
+                                transition_amplitude,
+                                step_fraction })
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Splits the accumulated `fraction` of the step and the new `steps` into the number of whole steps
+/// and the remaining part of the step. The `steps` which are not positive finite numbers are ignored
+/// and the `steps` are limited to [PhaseAmp::MAX_UPDATE_STEPS].
+#[inline]
+pub(crate) fn split_steps(fraction: f32, steps: f32) -> (u32, f32) {
+    if !(steps > 0.0 && steps.is_finite()) {
+        return (0, fraction);
     }
+    let total = fraction + steps.min(PhaseAmp::MAX_UPDATE_STEPS);
+    // this is saturating conversion which truncates the non-negative numbers like floor
+    let whole = total as u32;
+    (whole, (total - whole as f32).clamp(0.0, 1.0 - f32::EPSILON))
 }
 
+/// Computes `sin(PI/2 * val)^4`.
+///
+/// The sine is evaluated with a polynomial using only the basic arithmetic operations,
+/// so the result is exactly the same on every platform and with every math library.
 #[inline]
-fn transform(val: f32) -> f32 { (PI05 * val).sin().powi(4) }
+pub(crate) fn transform(val: f32) -> f32 {
+    let x = PI05 * val;
+    let x2 = x * x;
+    let sin = x * (1.0 - x2 / 6.0 * (1.0 - x2 / 20.0 * (1.0 - x2 / 42.0 * (1.0 - x2 / 72.0 * (1.0 - x2 / 110.0)))));
+    let sin2 = sin * sin;
+    sin2 * sin2
+}
 
 #[cfg(test)]
 mod tests {
     use crate::phase_amp::*;
+    use rand::{rngs::StdRng, SeedableRng};
 
     #[test]
+    #[allow(clippy::type_complexity)]
     fn it_works() {
         let pa = [PhaseAmp { phase: 1.0, amplitude: 1.5, ..Default::default() },
                   PhaseAmp { phase: 2.0, amplitude: 2.5, ..Default::default() },
@@ -375,6 +732,101 @@ mod tests {
 
         test_iterators(pa.as_ref());
 
+        let mut pastate: Vec<f32> = Vec::new();
+        let pa = PhaseAmp { phase: 1.0,
+                            delta_phase: 0.25,
+                            amplitude: 0.5,
+                            source_amplitude: 0.75,
+                            delta_amplitude: -0.5,
+                            step_amplitude: 0.01,
+                            transition_amplitude: 0.125,
+                            step_fraction: 0.5 };
+        pa.export_state(&mut pastate);
+        assert_eq!(pastate, [1.0, 0.25, 0.5, 0.75, -0.5, 0.01, 0.125, 0.5]);
+        assert_eq!(PhaseAmp::from_state(&pastate), Some(pa.clone()));
+        assert_eq!(PhaseAmp::from_state(&pastate[..PhaseAmp::STATE_LEN_V1]),
+                   Some(PhaseAmp { step_fraction: 0.0, ..pa }));
+        assert_eq!(PhaseAmp::from_state(&pastate[2..]), None);
+
+        let mut cfgstate: Vec<f32> = Vec::new();
+        let cfg = PhaseAmpCfg::new(80.0, 200.0);
+        cfg.export_state(&mut cfgstate);
+        assert_eq!(cfgstate.len(), PhaseAmpCfg::STATE_LEN);
+        assert_eq!(PhaseAmpCfg::from_state(&cfgstate), Some(cfg));
+        assert_eq!(PhaseAmpCfg::from_state(&[200.0, 80.0, 0.1, 0.1]), None);
+        assert_eq!(PhaseAmpCfg::from_state(&[80.0, 200.0, 0.1]), None);
+        assert_eq!(PhaseAmpCfg::from_state(&cfgstate[..PhaseAmpCfg::STATE_LEN_V1]), Some(cfg));
+        assert_eq!(PhaseAmpCfg::builder(80.0, 200.0).build(), cfg);
+        assert_eq!(PhaseAmpCfg::default(), PhaseAmpCfg::new(MIN_STEPS, MAX_STEPS));
+        assert_eq!(PhaseAmpCfg::try_new(80.0, 200.0), Ok(cfg));
+        assert_eq!(PhaseAmpCfg::try_new(200.0, 80.0), Err(PlasmaError::InvalidSteps));
+        assert_eq!(PhaseAmpCfg::try_new(1.0, 80.0), Err(PlasmaError::InvalidSteps));
+        assert_eq!(PhaseAmpCfg::try_new(f32::NAN, 80.0), Err(PlasmaError::InvalidSteps));
+        assert_eq!(PhaseAmpCfg::builder(80.0, 200.0).delta_phase_abs_max(0.0).try_build(),
+                   Err(PlasmaError::InvalidPhaseLimits));
+        assert_eq!(PhaseAmpCfg::builder(80.0, 200.0).amplitude_range(0.5, 0.5).try_build(),
+                   Err(PlasmaError::InvalidAmplitudeRange));
+
+        let custom = PhaseAmpCfg::builder(80.0, 200.0).amplitude_range(0.5, 0.75)
+                                                      .delta_phase_abs_max(0.05)
+                                                      .delta_delta_phase_abs_max(0.001)
+                                                      .easing(Easing::Linear)
+                                                      .build();
+        assert_eq!((custom.amplitude_min(), custom.amplitude_max(), custom.easing()), (0.5, 0.75, Easing::Linear));
+        assert_eq!((custom.delta_phase_abs_max(), custom.delta_delta_phase_abs_max()), (0.05, 0.001));
+        cfgstate.clear();
+        custom.export_state(&mut cfgstate);
+        assert_eq!(PhaseAmpCfg::from_state(&cfgstate), Some(custom));
+        cfgstate[6] = 99.0;
+        assert_eq!(PhaseAmpCfg::from_state(&cfgstate), None);
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut pa = PhaseAmp::new(&custom, &mut rng);
+        for _ in 0..1000 {
+            pa.update(&custom, &mut rng);
+            assert!((0.5..=0.75).contains(&pa.amplitude()));
+            assert!(pa.delta_phase.abs() <= 0.05 + 0.001);
+        }
+
+        for i in 0..=100 {
+            let val = i as f32 / 100.0;
+            assert!((transform(val) - (PI05 * val).sin().powi(4)).abs() < 1e-6);
+        }
+        assert_eq!(transform(0.0), 0.0);
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let pa = PhaseAmp::new(&cfg, &mut rng);
+        let (mut pa1, mut pa2) = (pa.clone(), pa.clone());
+        let mut rng2 = rng.clone();
+        pa1.update(&cfg, &mut rng);
+        pa2.update_by(&cfg, 1.0, &mut rng2);
+        assert_eq!(pa1, pa2);
+        let mut pa3 = pa.clone();
+        pa3.update_by(&cfg, 0.0, &mut rng);
+        assert_eq!((pa3.phase, pa3.delta_phase, pa3.transition_amplitude),
+                   (pa.phase, pa.delta_phase, pa.transition_amplitude));
+        let (mut pa4, mut pa5) = (pa.clone(), pa.clone());
+        let mut rng2 = rng.clone();
+        pa4.update_by(&cfg, 2.0, &mut rng);
+        pa5.update_by(&cfg, 1.0, &mut rng2);
+        pa5.update_by(&cfg, 1.0, &mut rng2);
+        assert_eq!(pa4, pa5);
+        let (mut pa4, mut pa5) = (pa.clone(), pa.clone());
+        let mut rng2 = rng.clone();
+        pa4.update_by(&cfg, 2.5, &mut rng);
+        for _ in 0..5 {
+            pa5.update_by(&cfg, 0.5, &mut rng2);
+        }
+        assert_eq!(pa4, pa5);
+        let mut pa6 = pa.clone();
+        pa6.update_by(&cfg, 0.5, &mut rng);
+        assert_eq!(pa6.step_fraction(), 0.5);
+        assert_eq!(pa6.phase(), pa.phase + pa.delta_phase * 0.5);
+        let (mut pa7, mut pa8) = (pa.clone(), pa.clone());
+        let mut rng2 = rng.clone();
+        pa7.update_by(&cfg, f32::MAX, &mut rng);
+        pa8.update_by(&cfg, PhaseAmp::MAX_UPDATE_STEPS, &mut rng2);
+        assert_eq!(pa7, pa8);
+
         fn test_iterators<'a, P: PhaseAmpsSelect<'a> + ?Sized>(pa: &'a P) {
             let res: Vec<(f32, f32)> = pa.iter_phase_amps().map(|pa| (pa.amplitude(), pa.phase())).collect();
             assert_eq!(res, [(1.5, 1.0), (2.5, 2.0), (3.5, 3.0), (4.5, 4.0), (5.5, 5.0), (6.5, 6.0)]);
@@ -1,6 +1,17 @@
-use crate::color::PixelRgb;
+use core::marker::PhantomData;
+use crate::color::{PixelRgb, SRGB_LINEAR_CUTOFF};
 #[cfg(not(feature = "std"))]
 use crate::m_polyfill::*;
+use crate::simd_polyfill::*;
+
+/// The number of entries of the sRGB encoding table.
+const SRGB_LUT_LEN: usize = 1024;
+
+/// The sRGB encoded values of the linear values `(i / (SRGB_LUT_LEN - 1))²`.
+///
+/// The table is indexed with a square root of the linear value, so it's denser in the dark tones
+/// where the sRGB encoding is the steepest.
+static SRGB_LUT: [f32; SRGB_LUT_LEN] = srgb_lut();
 
 /// The trait for putting pixels into byte buffers.
 pub trait PixelBuffer {
@@ -42,18 +53,44 @@ impl PixelBuffer for PixelBufRGBA32 {
     }
 }
 
-#[cfg(not(feature = "use-simd"))]
-#[cfg_attr(docsrs, doc(cfg(not(feature = "use-simd"))))]
+/// A [PixelBuffer] tool for a BGR24 buffer (3 bytes/pixel: blue, green, red).
+pub struct PixelBufBGR24;
+
+/// A [PixelBuffer] tool for a BGRA32 buffer (4 bytes/pixel: blue, green, red, alpha).
+pub struct PixelBufBGRA32;
+
+/// A [PixelBuffer] tool for a ARGB32 buffer (4 bytes/pixel: alpha, red, green, blue).
+pub struct PixelBufARGB32;
+
+/// A [PixelBuffer] tool for a 8-bit luminance buffer (1 byte/pixel).
+///
+/// The luminance is computed from the absolute values of the color components with
+/// the BT.709 coefficients.
+pub struct PixelBufLuma8;
+
+/// A [PixelBuffer] tool for a monochrome buffer (1 byte/pixel: `0` or `255`).
+///
+/// The pixels with the luminance, computed like with [PixelBufLuma8], above the half become white.
+pub struct PixelBufMono8;
+
+/// A [PixelBuffer] tool for a RGB332 buffer (3-3-2 bits per color channel: red, green, blue).
+pub struct PixelBufRGB332;
+
+/// A [PixelBuffer] tool for a RGB555 buffer (1-5-5-5 bits: unused, red, green, blue) in the big-endian
+/// byte order like [PixelBufRGB16].
+pub struct PixelBufRGB555;
+
+/// A [PixelBuffer] tool for a RGB16 buffer (5-6-5 bits per color channel: red, green, blue)
+/// in the little-endian byte order.
+pub struct PixelBufRGB16LE;
+
 /// A [PixelBuffer] tool for a RGB16 buffer (5-6-5 bits per color channel: red, green, blue).
 pub struct PixelBufRGB16;
 
-#[cfg(not(feature = "use-simd"))]
-#[cfg_attr(docsrs, doc(cfg(not(feature = "use-simd"))))]
 /// A [PixelBuffer] tool for a RGB16 x2 buffer (5-6-5 bits per color channel: red, green, blue)
 /// where each plasma pixel is being written to 2 consecutive pixels.
 pub struct PixelBufRGB16x2;
 
-#[cfg(not(feature = "use-simd"))]
 impl PixelBuffer for PixelBufRGB16 {
     const PIXEL_BYTES: usize = 2;
 
@@ -62,17 +99,12 @@ impl PixelBuffer for PixelBufRGB16 {
         where I: Iterator<Item = &'a mut u8>
     {
         let PixelRgb { r, g, b } = pixel;
-        let [r, g, b] = [r, g, b].map(ToColor8::to_color_u8clamped);
-        let rgb16 = (((r & 0b11111000) as u16) << 8)|
-                    (((g & 0b11111100) as u16) << 3)|
-                    (((b & 0b11111000) as u16) >> 3);
-        for (v, ptr) in rgb16.to_be_bytes().into_iter().zip(writer) {
-            *ptr = v;
+        for rgb in lanes_u8([r, g, b]) {
+            put_bytes(writer, &rgb565(rgb).to_be_bytes());
         }
     }
 }
 
-#[cfg(not(feature = "use-simd"))]
 impl PixelBuffer for PixelBufRGB16x2 {
     const PIXEL_BYTES: usize = 4;
 
@@ -81,15 +113,465 @@ impl PixelBuffer for PixelBufRGB16x2 {
         where I: Iterator<Item = &'a mut u8>
     {
         let PixelRgb { r, g, b } = pixel;
-        let [r, g, b] = [r, g, b].map(ToColor8::to_color_u8clamped);
-        let rgb16 = (((r & 0b11111000) as u16) << 8)|
-                    (((g & 0b11111100) as u16) << 3)|
-                    (((b & 0b11111000) as u16) >> 3);
-        let [hi, lo] = rgb16.to_be_bytes();
-        for (v, ptr) in [hi, lo, hi, lo].into_iter().zip(writer) {
-            *ptr = v;
+        for rgb in lanes_u8([r, g, b]) {
+            let [hi, lo] = rgb565(rgb).to_be_bytes();
+            put_bytes(writer, &[hi, lo, hi, lo]);
+        }
+    }
+}
+
+impl PixelBuffer for PixelBufBGR24 {
+    const PIXEL_BYTES: usize = 3;
+
+    #[inline]
+    fn put_pixel<'a, I>(writer: &mut I, pixel: PixelRgb)
+        where I: Iterator<Item = &'a mut u8>
+    {
+        let PixelRgb { r, g, b } = pixel;
+        for [r, g, b] in lanes_u8([r, g, b]) {
+            put_bytes(writer, &[b, g, r]);
+        }
+    }
+}
+
+impl PixelBuffer for PixelBufBGRA32 {
+    const PIXEL_BYTES: usize = 4;
+
+    #[inline]
+    fn put_pixel<'a, I>(writer: &mut I, pixel: PixelRgb)
+        where I: Iterator<Item = &'a mut u8>
+    {
+        let PixelRgb { r, g, b } = pixel;
+        for [r, g, b] in lanes_u8([r, g, b]) {
+            put_bytes(writer, &[b, g, r, u8::MAX]);
+        }
+    }
+}
+
+impl PixelBuffer for PixelBufARGB32 {
+    const PIXEL_BYTES: usize = 4;
+
+    #[inline]
+    fn put_pixel<'a, I>(writer: &mut I, pixel: PixelRgb)
+        where I: Iterator<Item = &'a mut u8>
+    {
+        let PixelRgb { r, g, b } = pixel;
+        for [r, g, b] in lanes_u8([r, g, b]) {
+            put_bytes(writer, &[u8::MAX, r, g, b]);
+        }
+    }
+}
+
+impl PixelBuffer for PixelBufLuma8 {
+    const PIXEL_BYTES: usize = 1;
+
+    #[inline]
+    fn put_pixel<'a, I>(writer: &mut I, pixel: PixelRgb)
+        where I: Iterator<Item = &'a mut u8>
+    {
+        for bytes in lanes_u8([luma(pixel)]) {
+            put_bytes(writer, &bytes);
+        }
+    }
+}
+
+impl PixelBuffer for PixelBufMono8 {
+    const PIXEL_BYTES: usize = 1;
+
+    #[inline]
+    fn put_pixel<'a, I>(writer: &mut I, pixel: PixelRgb)
+        where I: Iterator<Item = &'a mut u8>
+    {
+        for [luma] in lanes_u8([luma(pixel)]) {
+            put_bytes(writer, &[mono8(luma >> 7)]);
+        }
+    }
+}
+
+impl PixelBuffer for PixelBufRGB332 {
+    const PIXEL_BYTES: usize = 1;
+
+    #[inline]
+    fn put_pixel<'a, I>(writer: &mut I, pixel: PixelRgb)
+        where I: Iterator<Item = &'a mut u8>
+    {
+        let PixelRgb { r, g, b } = pixel;
+        for [r, g, b] in lanes_u8([r, g, b]) {
+            put_bytes(writer, &[rgb332([r >> 5, g >> 5, b >> 6])]);
+        }
+    }
+}
+
+impl PixelBuffer for PixelBufRGB555 {
+    const PIXEL_BYTES: usize = 2;
+
+    #[inline]
+    fn put_pixel<'a, I>(writer: &mut I, pixel: PixelRgb)
+        where I: Iterator<Item = &'a mut u8>
+    {
+        let PixelRgb { r, g, b } = pixel;
+        for [r, g, b] in lanes_u8([r, g, b]) {
+            put_bytes(writer, &rgb555([r >> 3, g >> 3, b >> 3]).to_be_bytes());
+        }
+    }
+}
+
+impl PixelBuffer for PixelBufRGB16LE {
+    const PIXEL_BYTES: usize = 2;
+
+    #[inline]
+    fn put_pixel<'a, I>(writer: &mut I, pixel: PixelRgb)
+        where I: Iterator<Item = &'a mut u8>
+    {
+        let PixelRgb { r, g, b } = pixel;
+        for rgb in lanes_u8([r, g, b]) {
+            put_bytes(writer, &rgb565(rgb).to_le_bytes());
+        }
+    }
+}
+
+/// The maximum values of the quantized color components of a [DitherPixelBuffer].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QuantizedMax {
+    /// A single component of the luminance for the grayscale buffers.
+    Luma(u8),
+    /// The red, green and blue components.
+    Rgb([u8; 3]),
+}
+
+impl QuantizedMax {
+    /// Returns the maximum values of the components.
+    #[inline]
+    pub const fn values(&self) -> &[u8] {
+        match self {
+            QuantizedMax::Luma(luma) => core::slice::from_ref(luma),
+            QuantizedMax::Rgb(rgb) => rgb,
+        }
+    }
+}
+
+/// The trait for putting the pixels of the quantized color components into the byte buffers of the low
+/// bit depth, implemented by the [PixelBuffer]s which can be rendered with the [Dither](crate::Dither).
+pub trait DitherPixelBuffer: PixelBuffer {
+    /// The maximum values of the quantized components.
+    const MAX_VALUES: QuantizedMax;
+    /// Puts a pixel of the quantized `components`, not larger than the corresponding [Self::MAX_VALUES],
+    /// into the buffer.
+    fn put_quantized<'a, I>(writer: &mut I, components: &[u8])
+        where I: Iterator<Item = &'a mut u8>;
+}
+
+impl DitherPixelBuffer for PixelBufRGB16 {
+    const MAX_VALUES: QuantizedMax = QuantizedMax::Rgb([31, 63, 31]);
+
+    #[inline]
+    fn put_quantized<'a, I>(writer: &mut I, components: &[u8])
+        where I: Iterator<Item = &'a mut u8>
+    {
+        put_bytes(writer, &pack565([components[0], components[1], components[2]]).to_be_bytes());
+    }
+}
+
+impl DitherPixelBuffer for PixelBufRGB16LE {
+    const MAX_VALUES: QuantizedMax = QuantizedMax::Rgb([31, 63, 31]);
+
+    #[inline]
+    fn put_quantized<'a, I>(writer: &mut I, components: &[u8])
+        where I: Iterator<Item = &'a mut u8>
+    {
+        put_bytes(writer, &pack565([components[0], components[1], components[2]]).to_le_bytes());
+    }
+}
+
+impl DitherPixelBuffer for PixelBufRGB16x2 {
+    const MAX_VALUES: QuantizedMax = QuantizedMax::Rgb([31, 63, 31]);
+
+    #[inline]
+    fn put_quantized<'a, I>(writer: &mut I, components: &[u8])
+        where I: Iterator<Item = &'a mut u8>
+    {
+        let [hi, lo] = pack565([components[0], components[1], components[2]]).to_be_bytes();
+        put_bytes(writer, &[hi, lo, hi, lo]);
+    }
+}
+
+impl DitherPixelBuffer for PixelBufRGB555 {
+    const MAX_VALUES: QuantizedMax = QuantizedMax::Rgb([31, 31, 31]);
+
+    #[inline]
+    fn put_quantized<'a, I>(writer: &mut I, components: &[u8])
+        where I: Iterator<Item = &'a mut u8>
+    {
+        put_bytes(writer, &rgb555([components[0], components[1], components[2]]).to_be_bytes());
+    }
+}
+
+impl DitherPixelBuffer for PixelBufRGB332 {
+    const MAX_VALUES: QuantizedMax = QuantizedMax::Rgb([7, 7, 3]);
+
+    #[inline]
+    fn put_quantized<'a, I>(writer: &mut I, components: &[u8])
+        where I: Iterator<Item = &'a mut u8>
+    {
+        put_bytes(writer, &[rgb332([components[0], components[1], components[2]])]);
+    }
+}
+
+impl DitherPixelBuffer for PixelBufLuma8 {
+    const MAX_VALUES: QuantizedMax = QuantizedMax::Luma(255);
+
+    #[inline]
+    fn put_quantized<'a, I>(writer: &mut I, components: &[u8])
+        where I: Iterator<Item = &'a mut u8>
+    {
+        put_bytes(writer, &components[..1]);
+    }
+}
+
+impl DitherPixelBuffer for PixelBufMono8 {
+    const MAX_VALUES: QuantizedMax = QuantizedMax::Luma(1);
+
+    #[inline]
+    fn put_quantized<'a, I>(writer: &mut I, components: &[u8])
+        where I: Iterator<Item = &'a mut u8>
+    {
+        put_bytes(writer, &[mono8(components[0])]);
+    }
+}
+
+/// A [PixelBuffer] tool for a RGB buffer of `f32` components (12 bytes/pixel: red, green, blue).
+///
+/// The components are written in the native byte order, so the buffer may be reinterpreted as a slice of `f32`.
+/// Like with the 8-bit buffers the absolute values are written, clamped to `1.0` unless `CLAMP` is `false`,
+/// e.g. `PixelBufRGBF32<false>` preserves the values above `1.0` for the HDR output.
+pub struct PixelBufRGBF32<const CLAMP: bool = true>;
+
+/// A [PixelBuffer] tool for a RGBA buffer of `f32` components (16 bytes/pixel: red, green, blue, alpha).
+///
+/// See [PixelBufRGBF32] for the description of the components and the `CLAMP` parameter.
+pub struct PixelBufRGBAF32<const CLAMP: bool = true>;
+
+/// A [PixelBuffer] tool for a RGB buffer of the half precision `f16` components (6 bytes/pixel: red, green, blue).
+///
+/// The components are written in the native byte order, see [PixelBufRGBF32] for the description of the values
+/// and the `CLAMP` parameter. The values too large for `f16` become the infinity.
+pub struct PixelBufRGBF16<const CLAMP: bool = true>;
+
+/// A [PixelBuffer] tool for a RGBA buffer of the half precision `f16` components (8 bytes/pixel: red, green, blue,
+/// alpha).
+///
+/// See [PixelBufRGBF16] for the description of the components and the `CLAMP` parameter.
+pub struct PixelBufRGBAF16<const CLAMP: bool = true>;
+
+impl<const CLAMP: bool> PixelBuffer for PixelBufRGBF32<CLAMP> {
+    const PIXEL_BYTES: usize = 3 * 4;
+
+    #[inline]
+    fn put_pixel<'a, I>(writer: &mut I, pixel: PixelRgb)
+        where I: Iterator<Item = &'a mut u8>
+    {
+        for rgb in lanes_f32::<CLAMP>(pixel) {
+            put_bytes(writer, rgb.map(f32::to_ne_bytes).as_flattened());
+        }
+    }
+}
+
+impl<const CLAMP: bool> PixelBuffer for PixelBufRGBAF32<CLAMP> {
+    const PIXEL_BYTES: usize = 4 * 4;
+
+    #[inline]
+    fn put_pixel<'a, I>(writer: &mut I, pixel: PixelRgb)
+        where I: Iterator<Item = &'a mut u8>
+    {
+        for [r, g, b] in lanes_f32::<CLAMP>(pixel) {
+            put_bytes(writer, [r, g, b, 1.0].map(f32::to_ne_bytes).as_flattened());
+        }
+    }
+}
+
+impl<const CLAMP: bool> PixelBuffer for PixelBufRGBF16<CLAMP> {
+    const PIXEL_BYTES: usize = 3 * 2;
+
+    #[inline]
+    fn put_pixel<'a, I>(writer: &mut I, pixel: PixelRgb)
+        where I: Iterator<Item = &'a mut u8>
+    {
+        for rgb in lanes_f32::<CLAMP>(pixel) {
+            put_bytes(writer, rgb.map(|v| f16_bits(v).to_ne_bytes()).as_flattened());
+        }
+    }
+}
+
+impl<const CLAMP: bool> PixelBuffer for PixelBufRGBAF16<CLAMP> {
+    const PIXEL_BYTES: usize = 4 * 2;
+
+    #[inline]
+    fn put_pixel<'a, I>(writer: &mut I, pixel: PixelRgb)
+        where I: Iterator<Item = &'a mut u8>
+    {
+        for [r, g, b] in lanes_f32::<CLAMP>(pixel) {
+            put_bytes(writer, [r, g, b, 1.0].map(|v| f16_bits(v).to_ne_bytes()).as_flattened());
+        }
+    }
+}
+
+/// Returns the bits of the half precision float nearest to `v`, rounding ties to even.
+fn f16_bits(v: f32) -> u16 {
+    let bits = v.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exp = ((bits >> 23) & 0xff) as i32 - 127 + 15;
+    let man = bits & 0x7f_ffff;
+    if exp >= 0x1f {
+        // too large, the infinity or NaN
+        let nan = if bits & 0x7fff_ffff > 0x7f80_0000 { 0x200 } else { 0 };
+        return sign | 0x7c00 | nan;
+    }
+    let (half, man, shift) = if exp > 0 {
+        (((exp as u32) << 10) | (man >> 13), man, 13)
+    }
+    else if exp >= -10 {
+        // a subnormal, the implicit leading bit becomes explicit
+        let (man, shift) = (man | 0x80_0000, (14 - exp) as u32);
+        (man >> shift, man, shift)
+    }
+    else {
+        return sign;
+    };
+    let (rem, tie) = (man & ((1 << shift) - 1), 1 << (shift - 1));
+    // the carry of the rounding correctly overflows into the exponent
+    let round = (rem > tie || (rem == tie && half & 1 == 1)) as u32;
+    sign | (half + round) as u16
+}
+
+/// Returns the absolute color components, clamped to `1.0` if `CLAMP` is `true`, grouped by each pixel.
+#[inline]
+fn lanes_f32<const CLAMP: bool>(pixel: PixelRgb) -> impl Iterator<Item = [f32; 3]> {
+    let PixelRgb { r, g, b } = pixel;
+    let values = [r, g, b].map(|v| if CLAMP { v.abs().sc_min(csplat(1.0)) } else { v.abs() });
+    lanes(values)
+}
+
+/// Returns the components grouped by each pixel.
+#[cfg(feature = "use-simd")]
+#[inline]
+fn lanes<const N: usize>(values: [Flt; N]) -> impl Iterator<Item = [f32; N]> {
+    let values = values.map(Flt::to_array);
+    (0..Flt::LANES).map(move |lane| values.map(|v| v[lane]))
+}
+
+/// Returns the components grouped by each pixel.
+#[cfg(not(feature = "use-simd"))]
+#[inline]
+fn lanes<const N: usize>(values: [Flt; N]) -> impl Iterator<Item = [f32; N]> { core::iter::once(values) }
+
+/// Packs the color components into the 5-6-5 bits: red, green, blue.
+#[inline]
+fn rgb565([r, g, b]: [u8; 3]) -> u16 { pack565([r >> 3, g >> 2, b >> 3]) }
+
+/// Packs the components already reduced to the 5-6-5 bits.
+#[inline]
+fn pack565([r, g, b]: [u8; 3]) -> u16 { ((r as u16) << 11) | ((g as u16) << 5) | b as u16 }
+
+/// Packs the components already reduced to the 5-5-5 bits.
+#[inline]
+fn rgb555([r, g, b]: [u8; 3]) -> u16 { ((r as u16) << 10) | ((g as u16) << 5) | b as u16 }
+
+/// Packs the components already reduced to the 3-3-2 bits.
+#[inline]
+fn rgb332([r, g, b]: [u8; 3]) -> u8 { (r << 5) | (g << 2) | b }
+
+/// Expands the 1-bit luminance to the black or white byte.
+#[inline]
+fn mono8(luma: u8) -> u8 { luma.wrapping_neg() }
+
+/// Returns the luminance of the absolute color components with the BT.709 coefficients.
+#[inline]
+fn luma(pixel: PixelRgb) -> Flt {
+    let PixelRgb { r, g, b } = pixel;
+    r.abs() * csplat(0.2126) + g.abs() * csplat(0.7152) + b.abs() * csplat(0.0722)
+}
+
+/// Converts the color components with [ToColor8] and returns them grouped by each pixel.
+#[cfg(feature = "use-simd")]
+#[inline]
+fn lanes_u8<const N: usize>(values: [Flt; N]) -> impl Iterator<Item = [u8; N]> {
+    let values = values.map(|v| (v.abs() * csplat(255.0)).cast::<u8>().to_array());
+    (0..Flt::LANES).map(move |lane| values.map(|v| v[lane]))
+}
+
+/// Converts the color components with [ToColor8] and returns them grouped by each pixel.
+#[cfg(not(feature = "use-simd"))]
+#[inline]
+fn lanes_u8<const N: usize>(values: [Flt; N]) -> impl Iterator<Item = [u8; N]> {
+    core::iter::once(values.map(ToColor8::to_color_u8clamped))
+}
+
+/// Writes the `bytes` of a single pixel, as many as there is room for in the `writer`.
+#[inline]
+fn put_bytes<'a, I>(writer: &mut I, bytes: &[u8])
+    where I: Iterator<Item = &'a mut u8>
+{
+    for (&v, ptr) in bytes.iter().zip(writer) {
+        *ptr = v;
+    }
+}
+
+/// A [PixelBuffer] tool encoding the linear colors to sRGB before putting them into the buffer `B`,
+/// e.g. `PixelBufSrgb<PixelBufRGB24>`.
+///
+/// The unwrapped buffers write the color components as they are, which keeps the legacy look
+/// of the mixers tuned for it. The mixers producing the linear colors, e.g. the
+/// [PlasmaOklchMixer](crate::PlasmaOklchMixer), should be rendered with this buffer instead.
+/// The encoding uses a lookup table, so it's almost as fast.
+pub struct PixelBufSrgb<B>(PhantomData<B>);
+
+impl<B: PixelBuffer> PixelBuffer for PixelBufSrgb<B> {
+    const PIXEL_BYTES: usize = B::PIXEL_BYTES;
+
+    #[inline]
+    fn put_pixel<'a, I>(writer: &mut I, pixel: PixelRgb)
+        where I: Iterator<Item = &'a mut u8>
+    {
+        let PixelRgb { r, g, b } = pixel;
+        B::put_pixel(writer, PixelRgb::new(srgb_lookup(r), srgb_lookup(g), srgb_lookup(b)));
+    }
+}
+
+/// Returns the approximated sRGB encoded value of the absolute linear value clamped to `1.0`.
+#[cfg(feature = "use-simd")]
+#[inline]
+fn srgb_lookup(v: Flt) -> Flt {
+    const SCALE: Flt = csplat((SRGB_LUT_LEN - 1) as f32);
+    let index = (v.abs().simd_min(csplat(1.0)).sqrt() * SCALE + csplat(0.5)).cast::<usize>();
+    Flt::gather_or_default(&SRGB_LUT, index)
+}
+
+/// Returns the approximated sRGB encoded value of the absolute linear value clamped to `1.0`.
+#[cfg(not(feature = "use-simd"))]
+#[inline]
+fn srgb_lookup(v: f32) -> f32 { SRGB_LUT[(v.abs().min(1.0).sqrt() * (SRGB_LUT_LEN - 1) as f32 + 0.5) as usize] }
+
+const fn srgb_lut() -> [f32; SRGB_LUT_LEN] {
+    let mut lut = [0.0; SRGB_LUT_LEN];
+    let mut i = 0;
+    while i < SRGB_LUT_LEN {
+        let t = i as f32 / (SRGB_LUT_LEN - 1) as f32;
+        lut[i] = if t * t <= SRGB_LINEAR_CUTOFF {
+            t * t * 12.92
         }
+        else {
+            // (t²)^(1/2.4) = t / t^(1/6), the sixth root is found with the Newton's method
+            let mut root = 1.0;
+            let mut n = 0;
+            while n < 16 {
+                root = (5.0 * root + t / (root * root * root * root * root)) / 6.0;
+                n += 1;
+            }
+            t / root * 1.055 - 0.055
+        };
+        i += 1;
     }
+    lut
 }
 
 /// Provides a method of converting color part from a `f32` type to a `u8`.
@@ -104,3 +586,146 @@ impl ToColor8 for f32 {
         (self.abs() * 255.0) as u8
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{pixel_buffer::*, simd_polyfill::{Flt, SimdCompat}, Plasma, PhaseAmpCfg, PlasmaICP, PlasmaMixer};
+
+    fn render<B: PixelBuffer>(plasma: &Plasma) -> Vec<u8> {
+        let pitch = plasma.pixel_width as usize * B::PIXEL_BYTES;
+        let mut buffer = vec![0u8; pitch * plasma.pixel_height as usize];
+        plasma.render::<B, PlasmaICP, PlasmaMixer>(&mut buffer, pitch, None);
+        buffer
+    }
+
+    #[test]
+    fn formats_work() {
+        let (plasma, _) = Plasma::new_seeded(21, 5, PhaseAmpCfg::default(), 8);
+        let rgb24 = render::<PixelBufRGB24>(&plasma);
+        let pixels = || rgb24.chunks_exact(3).map(|rgb| [rgb[0], rgb[1], rgb[2]]);
+        let expected: Vec<u8> = pixels().flat_map(|[r, g, b]| [b, g, r]).collect();
+        assert_eq!(render::<PixelBufBGR24>(&plasma), expected);
+        let expected: Vec<u8> = pixels().flat_map(|[r, g, b]| [b, g, r, 255]).collect();
+        assert_eq!(render::<PixelBufBGRA32>(&plasma), expected);
+        let expected: Vec<u8> = pixels().flat_map(|[r, g, b]| [255, r, g, b]).collect();
+        assert_eq!(render::<PixelBufARGB32>(&plasma), expected);
+        let expected: Vec<u8> =
+            pixels().flat_map(|[r, g, b]| ((r as u16 >> 3) << 10 | (g as u16 >> 3) << 5 | b as u16 >> 3).to_be_bytes())
+                    .collect();
+        assert_eq!(render::<PixelBufRGB555>(&plasma), expected);
+        let expected: Vec<u8> =
+            pixels().flat_map(|[r, g, b]| ((r as u16 >> 3) << 11 | (g as u16 >> 2) << 5 | b as u16 >> 3).to_le_bytes())
+                    .collect();
+        assert_eq!(render::<PixelBufRGB16LE>(&plasma), expected);
+        let luma8 = render::<PixelBufLuma8>(&plasma);
+        assert_eq!(luma8.len(), 21 * 5);
+        for (&luma, [r, g, b]) in luma8.iter().zip(pixels()) {
+            let expected = r as f32 * 0.2126 + g as f32 * 0.7152 + b as f32 * 0.0722;
+            assert!((luma as f32 - expected).abs() <= 1.0, "{} != {}", luma, expected);
+        }
+        let expected: Vec<u8> = luma8.iter().map(|&luma| if luma >= 128 { 255 } else { 0 }).collect();
+        assert_eq!(render::<PixelBufMono8>(&plasma), expected);
+        let expected: Vec<u8> = pixels().map(|[r, g, b]| (r & 0b11100000) | (g >> 5) << 2 | b >> 6).collect();
+        assert_eq!(render::<PixelBufRGB332>(&plasma), expected);
+    }
+
+    #[test]
+    fn rgb16_works() {
+        // the expected values are computed from the RGB24 output the same way as in the scalar build
+        let (plasma, _) = Plasma::new_seeded(19, 6, PhaseAmpCfg::default(), 4);
+        let rgb565 = |rgb: &[u8]| {
+            ((rgb[0] as u16 & 0b11111000) << 8 | (rgb[1] as u16 & 0b11111100) << 3 | (rgb[2] as u16 & 0b11111000) >> 3)
+            .to_be_bytes()
+        };
+        let rgb24 = render::<PixelBufRGB24>(&plasma);
+        let expected: Vec<u8> = rgb24.chunks_exact(3).flat_map(rgb565).collect();
+        assert_eq!(render::<PixelBufRGB16>(&plasma), expected);
+        let expected: Vec<u8> = rgb24.chunks_exact(3).flat_map(|rgb| rgb565(rgb).repeat(2)).collect();
+        assert_eq!(render::<PixelBufRGB16x2>(&plasma), expected);
+        let le: Vec<u8> = render::<PixelBufRGB16LE>(&plasma).chunks_exact(2).flat_map(|v| [v[1], v[0]]).collect();
+        assert_eq!(le, render::<PixelBufRGB16>(&plasma));
+
+        // every lane of the pixel is of a different color
+        let pixel = PixelRgb::new(lanes_flt(0), lanes_flt(2), lanes_flt(5));
+        let mut buffer = [0u8; 2 * Flt::LANES];
+        PixelBufRGB16::put_pixel(&mut buffer.iter_mut(), pixel);
+        let mut rgb24 = [0u8; 3 * Flt::LANES];
+        PixelBufRGB24::put_pixel(&mut rgb24.iter_mut(), pixel);
+        let expected: Vec<u8> = rgb24.chunks_exact(3).flat_map(rgb565).collect();
+        assert_eq!(buffer[..], expected[..]);
+    }
+
+    #[cfg(feature = "use-simd")]
+    fn lanes_flt(n: usize) -> Flt { Flt::from_array(core::array::from_fn(|i| ((i + n) % 8) as f32 / 7.0)) }
+
+    #[cfg(not(feature = "use-simd"))]
+    fn lanes_flt(n: usize) -> Flt { (n % 8) as f32 / 7.0 }
+
+    #[test]
+    fn f16_works() {
+        for (v, bits) in [(0.0, 0x0000), (-2.0, 0xc000), (1.0, 0x3c00), (0.5, 0x3800), (1.0 / 3.0, 0x3555),
+                          (65504.0, 0x7bff), (65519.0, 0x7bff), (65520.0, 0x7c00), (1e10, 0x7c00),
+                          (f32::INFINITY, 0x7c00), (6.1035156e-5, 0x0400), (5.9604645e-8, 0x0001),
+                          (2.9802322e-8, 0x0000), (4.4703484e-8, 0x0001), (1.0 + 1.0 / 2048.0, 0x3c00),
+                          (1.0 + 3.0 / 2048.0, 0x3c02), (1e-10, 0x0000)]
+        {
+            assert_eq!(f16_bits(v), bits, "{}", v);
+        }
+        assert_eq!(f16_bits(f32::NAN) & 0x7e00, 0x7e00);
+    }
+
+    #[test]
+    fn float_works() {
+        let (plasma, _) = Plasma::new_seeded(11, 3, PhaseAmpCfg::default(), 2);
+        let as_f32 = |bytes: Vec<u8>| -> Vec<f32> {
+            bytes.chunks_exact(4).map(|v| f32::from_ne_bytes([v[0], v[1], v[2], v[3]])).collect()
+        };
+        let rgb = as_f32(render::<PixelBufRGBF32>(&plasma));
+        let unclamped = as_f32(render::<PixelBufRGBF32<false>>(&plasma));
+        assert_eq!(rgb.len(), 11 * 3 * 3);
+        for (&v, &u) in rgb.iter().zip(unclamped.iter()) {
+            assert!(v >= 0.0 && u >= 0.0);
+            assert_eq!(v, u.min(1.0));
+        }
+        let rgb24 = render::<PixelBufRGB24>(&plasma);
+        assert!(rgb.iter().zip(rgb24.iter()).all(|(&v, &c)| v.to_color_u8clamped() == c));
+        let rgba = as_f32(render::<PixelBufRGBAF32>(&plasma));
+        let expected: Vec<f32> = rgb.chunks_exact(3).flat_map(|v| [v[0], v[1], v[2], 1.0]).collect();
+        assert_eq!(rgba, expected);
+
+        let pixel = PixelRgb::new(Flt::sc_splat(2.5), Flt::sc_splat(-0.5), Flt::sc_splat(0.25));
+        let mut buffer = vec![0u8; 12 * Flt::LANES];
+        PixelBufRGBF32::<false>::put_pixel(&mut buffer.iter_mut(), pixel);
+        assert_eq!(as_f32(buffer.clone())[..3], [2.5, 0.5, 0.25]);
+        PixelBufRGBF32::<true>::put_pixel(&mut buffer.iter_mut(), pixel);
+        assert_eq!(as_f32(buffer)[..3], [1.0, 0.5, 0.25]);
+
+        let half = |values: &[f32]| -> Vec<u8> { values.iter().flat_map(|&v| f16_bits(v).to_ne_bytes()).collect() };
+        assert_eq!(render::<PixelBufRGBF16<false>>(&plasma), half(&unclamped));
+        assert_eq!(render::<PixelBufRGBAF16>(&plasma), half(&rgba));
+    }
+
+    #[test]
+    fn srgb_works() {
+        let mut srgb = [0u8; 3];
+        let mut expected = [0u8; 3];
+        for i in 0..=1000 {
+            let v = Flt::sc_splat(i as f32 / 1000.0);
+            let pixel = PixelRgb::new(v, -v, v * Flt::sc_splat(0.01));
+            PixelBufSrgb::<PixelBufRGB24>::put_pixel(&mut srgb.iter_mut(), pixel);
+            let PixelRgb { r, g, b } = pixel;
+            PixelBufRGB24::put_pixel(&mut expected.iter_mut(), PixelRgb::new(r, g.abs(), b).to_srgb());
+            for (&res, &exp) in srgb.iter().zip(expected.iter()) {
+                assert!(res.abs_diff(exp) <= 1, "{}: {:?} != {:?}", i, srgb, expected);
+            }
+        }
+        let gray = Flt::sc_splat(0.2);
+        let mut rgba = [0u8; 4];
+        PixelBufSrgb::<PixelBufRGBA32>::put_pixel(&mut rgba.iter_mut(), PixelRgb::new(gray, gray, gray));
+        assert_eq!(rgba, [123, 123, 123, 255]);
+        assert_eq!(PixelBufSrgb::<PixelBufRGBA32>::PIXEL_BYTES, 4);
+        let mut rgb16 = [0u8; 2 * Flt::LANES];
+        PixelBufSrgb::<PixelBufRGB16>::put_pixel(&mut rgb16.iter_mut(), PixelRgb::new(gray, gray, gray));
+        assert_eq!(rgb16[..2], [0b0111_1011, 0b1100_1111]);
+    }
+}
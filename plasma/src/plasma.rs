@@ -1,19 +1,25 @@
 use core::{
     borrow::BorrowMut,
-    cmp::min,
-    f32::consts::PI
+    cmp::{max, min},
+    f32::consts::PI,
+    mem
 };
 #[cfg(not(feature = "std"))]
-use alloc::vec::Vec;
-use rand::Rng;
+use alloc::{vec, vec::Vec};
+use rand::{Rng, SeedableRng};
 
 use cfg_if::cfg_if;
 
-use crate::{mixer::*, phase_amp::*, pixel_buffer::*, simd_polyfill::*};
+use crate::{error::*, mixer::*, phase_amp::*, pixel_buffer::*, simd_polyfill::*, timeline::*};
 
 const PI2: f32 = 2.0 * PI;
 
-type PhaseAmpsT = [PhaseAmp; 24];
+/// The random number generator used by the seeded plasma animations.
+///
+/// The algorithm is not going to change between versions of this crate and it produces
+/// exactly the same sequence of numbers on every platform, so the same seed always yields
+/// the same animation.
+pub type PlasmaRng = rand_xoshiro::Xoshiro128PlusPlus;
 
 /// A convenient trait alias for plasma render methods.
 pub trait ICProducer<'a>: IntermediateCalculatorProducer<'a, [PhaseAmp], Flt> {}
@@ -27,20 +33,195 @@ pub struct Plasma {
     /// The plasma pixel height
     pub pixel_height: u32,
     config: PhaseAmpCfg,
-    phase_amps: PhaseAmpsT,
+    pub(crate) phase_amps: Vec<PhaseAmp>,
+    timeline_seed: Option<u64>,
+    loop_steps: Option<f32>,
+    time: f64,
 }
 
 impl Plasma {
+    /// The version of the data layout written by [Plasma::export_state].
+    pub const STATE_VERSION: u32 = 6;
+    /// The number of [PhaseAmp] components of the newly created plasma instances.
+    ///
+    /// This is the number of components required by the [PlasmaMixer](crate::PlasmaMixer).
+    pub const DEFAULT_PHASE_AMPS_LEN: usize = 24;
+
     /// Creates new plasma instance.
     ///
     /// Provide the initial `pixel_width` and `pixel_height`,
     /// initialized [PhaseAmpCfg] and an instance of [Rng].
     pub fn new<R: Rng + ?Sized>(pixel_width: u32, pixel_height: u32, config: PhaseAmpCfg, rng: &mut R) -> Self {
-        let mut phase_amps = PhaseAmpsT::default();
-        for p in phase_amps.iter_mut() {
-            *p = PhaseAmp::new(&config, rng);
+        let phase_amps = (0..Self::DEFAULT_PHASE_AMPS_LEN).map(|_| PhaseAmp::new(&config, rng)).collect();
+        Plasma { pixel_width, pixel_height, config, phase_amps, timeline_seed: None, loop_steps: None, time: 0.0 }
+    }
+
+    /// Creates new plasma instance from the provided `seed`.
+    ///
+    /// Provide the initial `pixel_width` and `pixel_height` and initialized [PhaseAmpCfg].
+    ///
+    /// Returns the plasma along with the [PlasmaRng] instance that should be passed to
+    /// [Plasma::update]. The same `seed` and [PhaseAmpCfg] always produce the same animation,
+    /// regardless of the target platform or the crate features.
+    pub fn new_seeded(pixel_width: u32, pixel_height: u32, config: PhaseAmpCfg, seed: u64) -> (Self, PlasmaRng) {
+        let mut rng = PlasmaRng::seed_from_u64(seed);
+        let plasma = Plasma::new(pixel_width, pixel_height, config, &mut rng);
+        (plasma, rng)
+    }
+
+    /// Creates new plasma instance in the timeline mode from the provided `seed`.
+    ///
+    /// Provide the initial `pixel_width` and `pixel_height` and initialized [PhaseAmpCfg].
+    ///
+    /// In the timeline mode each [PhaseAmp] is a pure function of time, so the plasma can be
+    /// set to any moment of the animation with [Plasma::at_time] without computing the preceding
+    /// frames. The created plasma is set to the time `0.0`.
+    pub fn new_timeline(pixel_width: u32, pixel_height: u32, config: PhaseAmpCfg, seed: u64) -> Self {
+        let mut plasma = Plasma { pixel_width,
+                                  pixel_height,
+                                  config,
+                                  phase_amps: vec![PhaseAmp::default(); Self::DEFAULT_PHASE_AMPS_LEN],
+                                  timeline_seed: Some(seed),
+                                  loop_steps: None,
+                                  time: 0.0 };
+        plasma.at_time(0.0);
+        plasma
+    }
+
+    /// Creates new plasma instance in the timeline mode with the animation looping seamlessly
+    /// every `loop_steps` animation steps.
+    ///
+    /// Provide the initial `pixel_width` and `pixel_height` and initialized [PhaseAmpCfg].
+    ///
+    /// The state of the plasma at the time `t + loop_steps` is the same as at the time `t`,
+    /// so the last frame of the loop flows smoothly back into the first one.
+    /// Use [Plasma::at_loop_frame] to set the plasma to a frame of an N-frame sequence, or
+    /// [Plasma::at_time] to play the loop continuously.
+    /// The created plasma is set to the time `0.0`.
+    ///
+    /// # Panics
+    ///
+    /// __Panics__ if `loop_steps` is not a positive finite number.
+    pub fn new_looped(pixel_width: u32, pixel_height: u32, config: PhaseAmpCfg, seed: u64, loop_steps: f32) -> Self {
+        match Self::try_new_looped(pixel_width, pixel_height, config, seed, loop_steps) {
+            Ok(plasma) => plasma,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Creates new plasma instance in the timeline mode with the animation looping seamlessly
+    /// every `loop_steps` animation steps.
+    ///
+    /// This is a fallible variant of [Plasma::new_looped], returning [PlasmaError::InvalidLoopSteps]
+    /// instead of panicking.
+    pub fn try_new_looped(pixel_width: u32, pixel_height: u32, config: PhaseAmpCfg, seed: u64, loop_steps: f32)
+                          -> Result<Self, PlasmaError> {
+        if !(loop_steps > 0.0 && loop_steps.is_finite()) {
+            return Err(PlasmaError::InvalidLoopSteps);
+        }
+        let mut plasma = Plasma { pixel_width,
+                                  pixel_height,
+                                  config,
+                                  phase_amps: vec![PhaseAmp::default(); Self::DEFAULT_PHASE_AMPS_LEN],
+                                  timeline_seed: Some(seed),
+                                  loop_steps: Some(loop_steps),
+                                  time: 0.0 };
+        plasma.try_at_time(0.0)?;
+        Ok(plasma)
+    }
+
+    /// Returns the seed of the plasma created with [Plasma::new_timeline] or [Plasma::new_looped].
+    #[inline]
+    pub fn timeline_seed(&self) -> Option<u64> { self.timeline_seed }
+
+    /// Returns the length of the loop in animation steps of the plasma created with [Plasma::new_looped].
+    #[inline]
+    pub fn loop_steps(&self) -> Option<f32> { self.loop_steps }
+
+    /// Returns the time the plasma in the timeline mode was last set to with [Plasma::at_time]
+    /// or [Plasma::at_loop_frame].
+    #[inline]
+    pub fn time(&self) -> f64 { self.time }
+
+    /// Sets the plasma created with [Plasma::new_timeline] to the state at the time `t`.
+    ///
+    /// The time is expressed in animation steps and may be any finite number, so the animation
+    /// can be played in both directions or any frame can be rendered directly.
+    /// The plasma set this way may be also animated further with [Plasma::update].
+    ///
+    /// # Panics
+    ///
+    /// __Panics__ if the plasma was not created in the timeline mode.
+    pub fn at_time(&mut self, t: f64) {
+        if let Err(err) = self.try_at_time(t) {
+            panic!("{}", err);
+        }
+    }
+
+    /// Sets the plasma created with [Plasma::new_timeline] to the state at the time `t`.
+    ///
+    /// This is a fallible variant of [Plasma::at_time], returning [PlasmaError::NotTimeline]
+    /// instead of panicking.
+    pub fn try_at_time(&mut self, t: f64) -> Result<(), PlasmaError> {
+        let seed = self.timeline_seed.ok_or(PlasmaError::NotTimeline)?;
+        for (index, pa) in self.phase_amps.iter_mut().enumerate() {
+            let seed = component_seed(seed, index);
+            let timeline = match self.loop_steps {
+                Some(loop_steps) => PhaseAmpTimeline::new_looped(&self.config, seed, loop_steps),
+                None => PhaseAmpTimeline::new(&self.config, seed),
+            };
+            *pa = timeline.phase_amp_at(t);
+        }
+        self.time = t;
+        Ok(())
+    }
+
+    /// Sets the plasma created with [Plasma::new_looped] to the frame at `index` of the loop
+    /// divided evenly into `frames` frames.
+    ///
+    /// The frame following the last one is the first frame, so rendering frames from `0` to
+    /// `frames - 1` produces a seamlessly looping sequence. The `index` may exceed `frames`,
+    /// in this instance the loop is simply repeated.
+    ///
+    /// # Panics
+    ///
+    /// __Panics__ if the plasma was not created with [Plasma::new_looped] or `frames` is `0`.
+    pub fn at_loop_frame(&mut self, index: u32, frames: u32) {
+        if let Err(err) = self.try_at_loop_frame(index, frames) {
+            panic!("{}", err);
+        }
+    }
+
+    /// Sets the plasma created with [Plasma::new_looped] to the frame at `index` of the loop
+    /// divided evenly into `frames` frames.
+    ///
+    /// This is a fallible variant of [Plasma::at_loop_frame], returning [PlasmaError::NotLooped]
+    /// or [PlasmaError::InvalidFrames] instead of panicking.
+    pub fn try_at_loop_frame(&mut self, index: u32, frames: u32) -> Result<(), PlasmaError> {
+        let loop_steps = self.loop_steps.ok_or(PlasmaError::NotLooped)?;
+        if frames == 0 {
+            return Err(PlasmaError::InvalidFrames);
+        }
+        let t = (index % frames) as f64 * loop_steps as f64 / frames as f64;
+        self.try_at_time(t)
+    }
+
+    /// Returns the number of [PhaseAmp] components of the plasma.
+    #[inline]
+    pub fn phase_amps_len(&self) -> usize { self.phase_amps.len() }
+
+    /// Changes the number of [PhaseAmp] components of the plasma.
+    ///
+    /// The number of components should match [Mixer::phase_amps_len] of the mixer used for rendering.
+    /// The existing components are preserved and new ones are created randomly with the provided [Rng].
+    /// In the timeline mode call [Plasma::at_time] afterwards to set all of the components
+    /// to the same moment of the animation.
+    pub fn set_phase_amps_len<R: Rng + ?Sized>(&mut self, len: usize, rng: &mut R) {
+        let config = &self.config;
+        self.phase_amps.truncate(len);
+        while self.phase_amps.len() < len {
+            self.phase_amps.push(PhaseAmp::new(config, rng));
         }
-        Plasma { pixel_width, pixel_height, config, phase_amps }
     }
 
     /// Animates the plasma by modifying the internal [PhaseAmp] variables.
@@ -53,6 +234,34 @@ impl Plasma {
         }
     }
 
+    /// Animates the plasma by a fraction of a step, modifying the internal [PhaseAmp] variables.
+    ///
+    /// The `steps` argument should be a non-negative number of animation steps.
+    /// To make the animation speed independent of the frame rate pass the time elapsed
+    /// since the previous update multiplied by the desired number of steps per second.
+    /// The `steps` larger than [PhaseAmp::MAX_UPDATE_STEPS] are limited to it.
+    ///
+    /// Like with [PhaseAmp::update_by] the animation advances in whole steps and the remaining part of the step
+    /// is accumulated until the next call, so the same seed produces the same animation at any frame rate.
+    /// The amplitude transitions carry the steps past their end over to the next ones, so the animation
+    /// diverges from the one created with [Plasma::update].
+    ///
+    /// Provide an instance of initialized [Rng] instance.
+    pub fn update_by<R: Rng + ?Sized>(&mut self, steps: f32, rng: &mut R) {
+        let fraction = self.phase_amps.first().map_or(0.0, PhaseAmp::step_fraction);
+        let (whole, fraction) = split_steps(fraction, steps);
+        let config = &self.config;
+        /* the components are updated step by step to draw the random numbers in the same order as update */
+        for _ in 0..whole {
+            for pa in self.phase_amps.iter_mut() {
+                pa.step_carrying(config, rng);
+            }
+        }
+        for pa in self.phase_amps.iter_mut() {
+            pa.set_step_fraction(config, fraction);
+        }
+    }
+
     /// Renders the plasma into the provided `buffer`.
     ///
     /// You must also provide a struct implementing [PixelBuffer] trait.
@@ -61,19 +270,35 @@ impl Plasma {
     ///
     /// The `wrkspc` is an optional temporary memory scractchpad.
     /// If None is provided the new memory will be allocated.
+    ///
+    /// The default instance of the mixer is used, see [Plasma::render_with].
     #[inline]
     pub fn render<'a, B, L, M>(&'a self, buffer: &mut [u8], pitch: usize, wrkspc: Option<&mut Vec<u8>>)
+        where B: PixelBuffer,
+              L: ICProducer<'a>,
+              M: Mixer<Flt> + Default
+    {
+        self.render_with::<B, L, M>(&M::default(), buffer, pitch, wrkspc)
+    }
+
+    /// Renders the plasma into the provided `buffer` with the provided `mixer` instance.
+    ///
+    /// See [Plasma::render].
+    #[inline]
+    pub fn render_with<'a, B, L, M>(&'a self, mixer: &M, buffer: &mut [u8], pitch: usize,
+                                    wrkspc: Option<&mut Vec<u8>>)
         where B: PixelBuffer,
               L: ICProducer<'a>,
               M: Mixer<Flt>
     {
-        self.render_part::<B, L, M>(buffer,
-                                    pitch,
-                                    0,
-                                    0,
-                                    self.pixel_width as usize,
-                                    self.pixel_height as usize,
-                                    wrkspc)
+        self.render_part_with::<B, L, M>(mixer,
+                                         buffer,
+                                         pitch,
+                                         0,
+                                         0,
+                                         self.pixel_width as usize,
+                                         self.pixel_height as usize,
+                                         wrkspc)
     }
 
     /// Renders the part of the plasma into the provided `buffer`.
@@ -87,9 +312,26 @@ impl Plasma {
     ///
     /// The `wrkspc` is an optional temporary memory scractchpad.
     /// If None is provided the new memory will be allocated.
+    ///
+    /// The default instance of the mixer is used, see [Plasma::render_part_with].
     #[inline]
+    #[allow(clippy::too_many_arguments)]
     pub fn render_part<'a, B, L, M>(&'a self, buffer: &mut [u8], pitch: usize, x: usize, y: usize, w: usize,
                                     h: usize, wrkspc: Option<&mut Vec<u8>>)
+        where B: PixelBuffer,
+              L: ICProducer<'a>,
+              M: Mixer<Flt> + Default
+    {
+        self.render_part_with::<B, L, M>(&M::default(), buffer, pitch, x, y, w, h, wrkspc)
+    }
+
+    /// Renders the part of the plasma into the provided `buffer` with the provided `mixer` instance.
+    ///
+    /// See [Plasma::render_part].
+    #[inline]
+    #[allow(clippy::too_many_arguments)]
+    pub fn render_part_with<'a, B, L, M>(&'a self, mixer: &M, buffer: &mut [u8], pitch: usize, x: usize, y: usize,
+                                         w: usize, h: usize, wrkspc: Option<&mut Vec<u8>>)
         where B: PixelBuffer,
               L: ICProducer<'a>,
               M: Mixer<Flt>
@@ -97,17 +339,263 @@ impl Plasma {
         let pw = self.pixel_width as usize;
         let ph = self.pixel_height as usize;
         let phase_amps = &self.phase_amps[..];
-        render_part::<B, L, M, _>(buffer, pitch, pw, ph, phase_amps, x, y, w, h, wrkspc)
+        render_part_with::<B, L, M, _>(mixer, buffer, pitch, pw, ph, phase_amps, x, y, w, h, wrkspc)
     }
 
-    /// Import the internal plasma state from a slice of 32bit floats.
+    /// Renders the plasma into the provided `buffer`.
+    ///
+    /// This is a fallible variant of [Plasma::render], see [try_render_part] for the list of errors.
+    #[inline]
+    pub fn try_render<'a, B, L, M>(&'a self, buffer: &mut [u8], pitch: usize, wrkspc: Option<&mut Vec<u8>>)
+                                   -> Result<(), PlasmaError>
+        where B: PixelBuffer,
+              L: ICProducer<'a>,
+              M: Mixer<Flt> + Default
+    {
+        self.try_render_with::<B, L, M>(&M::default(), buffer, pitch, wrkspc)
+    }
+
+    /// Renders the plasma into the provided `buffer` with the provided `mixer` instance.
+    ///
+    /// This is a fallible variant of [Plasma::render_with], see [try_render_part] for the list of errors.
+    #[inline]
+    pub fn try_render_with<'a, B, L, M>(&'a self, mixer: &M, buffer: &mut [u8], pitch: usize,
+                                        wrkspc: Option<&mut Vec<u8>>)
+                                        -> Result<(), PlasmaError>
+        where B: PixelBuffer,
+              L: ICProducer<'a>,
+              M: Mixer<Flt>
+    {
+        self.try_render_part_with::<B, L, M>(mixer,
+                                             buffer,
+                                             pitch,
+                                             0,
+                                             0,
+                                             self.pixel_width as usize,
+                                             self.pixel_height as usize,
+                                             wrkspc)
+    }
+
+    /// Renders the part of the plasma into the provided `buffer`.
+    ///
+    /// This is a fallible variant of [Plasma::render_part], see [try_render_part] for the list of errors.
+    #[inline]
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_render_part<'a, B, L, M>(&'a self, buffer: &mut [u8], pitch: usize, x: usize, y: usize, w: usize,
+                                        h: usize, wrkspc: Option<&mut Vec<u8>>)
+                                        -> Result<(), PlasmaError>
+        where B: PixelBuffer,
+              L: ICProducer<'a>,
+              M: Mixer<Flt> + Default
+    {
+        self.try_render_part_with::<B, L, M>(&M::default(), buffer, pitch, x, y, w, h, wrkspc)
+    }
+
+    /// Renders the part of the plasma into the provided `buffer` with the provided `mixer` instance.
+    ///
+    /// This is a fallible variant of [Plasma::render_part_with], see [try_render_part] for the list of errors.
+    #[inline]
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_render_part_with<'a, B, L, M>(&'a self, mixer: &M, buffer: &mut [u8], pitch: usize, x: usize,
+                                             y: usize, w: usize, h: usize, wrkspc: Option<&mut Vec<u8>>)
+                                             -> Result<(), PlasmaError>
+        where B: PixelBuffer,
+              L: ICProducer<'a>,
+              M: Mixer<Flt>
+    {
+        let pw = self.pixel_width as usize;
+        let ph = self.pixel_height as usize;
+        let phase_amps = &self.phase_amps[..];
+        try_render_part_with::<B, L, M, _>(mixer, buffer, pitch, pw, ph, phase_amps, x, y, w, h, wrkspc)
+    }
+
+    /// Renders the plasma into the provided `buffer` with the provided [PixelTermsMixer] instance.
+    ///
+    /// See [render_part_terms].
     #[inline]
-    pub fn import_phase_amps(&mut self, source: &[f32]) { self.phase_amps.import_phase_amps(source); }
+    pub fn render_terms<'a, B, L, T, M>(&'a self, mixer: &M, buffer: &mut [u8], pitch: usize,
+                                        wrkspc: Option<&mut Vec<u8>>)
+        where B: PixelBuffer,
+              L: ICProducer<'a>,
+              T: PixelTermCalculatorProducer<'a, [PhaseAmp], Flt>,
+              M: PixelTermsMixer<Flt>
+    {
+        self.render_part_terms::<B, L, T, M>(mixer,
+                                             buffer,
+                                             pitch,
+                                             0,
+                                             0,
+                                             self.pixel_width as usize,
+                                             self.pixel_height as usize,
+                                             wrkspc)
+    }
+
+    /// Renders the part of the plasma into the provided `buffer` with the provided [PixelTermsMixer] instance.
+    ///
+    /// See [render_part_terms].
+    #[inline]
+    #[allow(clippy::too_many_arguments)]
+    pub fn render_part_terms<'a, B, L, T, M>(&'a self, mixer: &M, buffer: &mut [u8], pitch: usize, x: usize,
+                                             y: usize, w: usize, h: usize, wrkspc: Option<&mut Vec<u8>>)
+        where B: PixelBuffer,
+              L: ICProducer<'a>,
+              T: PixelTermCalculatorProducer<'a, [PhaseAmp], Flt>,
+              M: PixelTermsMixer<Flt>
+    {
+        let pw = self.pixel_width as usize;
+        let ph = self.pixel_height as usize;
+        let phase_amps = &self.phase_amps[..];
+        render_part_terms::<B, L, T, M, _>(mixer, buffer, pitch, pw, ph, phase_amps, x, y, w, h, wrkspc)
+    }
+
+    /// Renders the part of the plasma into the provided `buffer` with the provided [PixelTermsMixer] instance.
+    ///
+    /// This is a fallible variant of [Plasma::render_part_terms], see [try_render_part] for the list of errors.
+    #[inline]
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_render_part_terms<'a, B, L, T, M>(&'a self, mixer: &M, buffer: &mut [u8], pitch: usize, x: usize,
+                                                 y: usize, w: usize, h: usize, wrkspc: Option<&mut Vec<u8>>)
+                                                 -> Result<(), PlasmaError>
+        where B: PixelBuffer,
+              L: ICProducer<'a>,
+              T: PixelTermCalculatorProducer<'a, [PhaseAmp], Flt>,
+              M: PixelTermsMixer<Flt>
+    {
+        let pw = self.pixel_width as usize;
+        let ph = self.pixel_height as usize;
+        let phase_amps = &self.phase_amps[..];
+        try_render_part_terms::<B, L, T, M, _>(mixer, buffer, pitch, pw, ph, phase_amps, x, y, w, h, wrkspc)
+    }
+
+    /// Checks if the area given by `x`, `y`, `w` and `h` pixel coordinates is within the plasma boundaries.
+    ///
+    /// Returns [PlasmaError::AreaOutOfBounds] if it's not.
+    #[inline]
+    pub fn check_area(&self, x: usize, y: usize, w: usize, h: usize) -> Result<(), PlasmaError> {
+        check_area(self.pixel_width as usize, self.pixel_height as usize, x, y, w, h)
+    }
+
+    /// Import the internal plasma state from a slice of 32bit floats.
+    ///
+    /// # Panics
+    ///
+    /// __Panics__ if the length of `source` doesn't match the number of [PhaseAmp] components.
+    pub fn import_phase_amps(&mut self, source: &[f32]) {
+        if let Err(err) = self.try_import_phase_amps(source) {
+            panic!("{}", err);
+        }
+    }
+
+    /// Import the internal plasma state from a slice of 32bit floats.
+    ///
+    /// This is a fallible variant of [Plasma::import_phase_amps], returning [PlasmaError::OddPhaseAmpsData]
+    /// or [PlasmaError::PhaseAmpsLength] instead of panicking. The plasma is left unchanged on error.
+    pub fn try_import_phase_amps(&mut self, source: &[f32]) -> Result<(), PlasmaError> {
+        if !source.len().is_multiple_of(2) {
+            return Err(PlasmaError::OddPhaseAmpsData { len: source.len() });
+        }
+        if source.len() != 2 * self.phase_amps.len() {
+            return Err(PlasmaError::PhaseAmpsLength { expected: self.phase_amps.len(), actual: source.len() / 2 });
+        }
+        self.phase_amps[..].import_phase_amps(source);
+        Ok(())
+    }
 
     /// Exports the internal plasma state into the [Vec] of 32bit floats.
     #[inline]
     pub fn export_phase_amps(&self, out: &mut Vec<f32>) { self.phase_amps.export_phase_amps(out); }
 
+    /// Exports the complete plasma animation state into the [Vec] of 32bit floats.
+    ///
+    /// The exported data includes the layout version, the pixel dimensions, the [PhaseAmpCfg],
+    /// the seed, the loop length and the time of the timeline mode and the full animation state of each [PhaseAmp].
+    /// The plasma restored from this data with [Plasma::from_state] animates exactly the same way
+    /// as the original one, given the same sequence of random numbers is provided to [Plasma::update].
+    pub fn export_state(&self, out: &mut Vec<f32>) {
+        let count = self.phase_amps.len();
+        out.reserve_exact(14 + PhaseAmpCfg::STATE_LEN + count * PhaseAmp::STATE_LEN);
+        out.extend(&[Self::STATE_VERSION as f32, self.pixel_width as f32, self.pixel_height as f32]);
+        self.config.export_state(out);
+        match self.timeline_seed {
+            Some(seed) => {
+                out.push(1.0);
+                export_u64(seed, out);
+            },
+            None => out.extend(&[0.0; 5]),
+        }
+        out.push(self.loop_steps.unwrap_or(0.0));
+        export_u64(self.time.to_bits(), out);
+        out.push(count as f32);
+        for pa in self.phase_amps.iter() {
+            pa.export_state(out);
+        }
+    }
+
+    /// Creates new plasma instance from the data exported with [Plasma::export_state].
+    ///
+    /// The data exported by the previous versions of this crate is also accepted.
+    ///
+    /// Returns `None` if the data was exported with an unsupported layout version or if it's
+    /// malformed.
+    pub fn from_state(source: &[f32]) -> Option<Self> {
+        let (header, source) = source.split_at_checked(3)?;
+        let version = f32_to_u32_exact(header[0])?;
+        if version == 0 || version > Self::STATE_VERSION {
+            return None;
+        }
+        let pixel_width = f32_to_u32_exact(header[1])?;
+        let pixel_height = f32_to_u32_exact(header[2])?;
+        let config_len = match version {
+            1..=3 => PhaseAmpCfg::STATE_LEN_V1,
+            _ => PhaseAmpCfg::STATE_LEN,
+        };
+        let (config, source) = source.split_at_checked(config_len)?;
+        let config = PhaseAmpCfg::from_state(config)?;
+        let (timeline_seed, source) = match version {
+            1 => (None, source),
+            _ => {
+                let (timeline, source) = source.split_at_checked(5)?;
+                let seed = import_u64(&timeline[1..])?;
+                match timeline[0] {
+                    0.0 => (None, source),
+                    1.0 => (Some(seed), source),
+                    _ => return None,
+                }
+            },
+        };
+        let (loop_steps, source) = match version {
+            1 | 2 => (None, source),
+            _ => {
+                let (loop_steps, source) = source.split_at_checked(1)?;
+                match loop_steps[0] {
+                    0.0 => (None, source),
+                    l if l > 0.0 && l.is_finite() && timeline_seed.is_some() => (Some(l), source),
+                    _ => return None,
+                }
+            },
+        };
+        let (time, source) = match version {
+            1..=5 => (0.0, source),
+            _ => {
+                let (time, source) = source.split_at_checked(4)?;
+                match f64::from_bits(import_u64(time)?) {
+                    t if t.is_finite() && (t == 0.0 || timeline_seed.is_some()) => (t, source),
+                    _ => return None,
+                }
+            },
+        };
+        let phase_amp_len = match version {
+            1..=4 => PhaseAmp::STATE_LEN_V1,
+            _ => PhaseAmp::STATE_LEN,
+        };
+        let (count, source) = source.split_at_checked(1)?;
+        if source.len() != f32_to_u32_exact(count[0])? as usize * phase_amp_len {
+            return None;
+        }
+        let phase_amps = source.chunks_exact(phase_amp_len).map(PhaseAmp::from_state).collect::<Option<_>>()?;
+        Some(Plasma { pixel_width, pixel_height, config, phase_amps, timeline_seed, loop_steps, time })
+    }
+
     #[inline]
     pub fn min_steps(&self) -> f32 { self.config.min_steps() }
 
@@ -133,20 +621,41 @@ impl Plasma {
 /// The `wrkspc` is an optional temporary memory scractchpad.
 /// If None is provided the new memory will be allocated.
 ///
+/// The default instance of the mixer is used, see [render_part_with].
+///
 /// # Panics
 ///
 /// __Panics__ if [PhaseAmpsSelect::select] panics.
+#[allow(clippy::too_many_arguments)]
+#[inline]
 pub fn render_part<'a, B, L, M, P>(buffer: &mut [u8], pitch: usize, pw: usize, ph: usize, phase_amps: &'a P,
                                    x: usize, y: usize, w: usize, h: usize, wrkspc: Option<&mut Vec<u8>>)
+    where B: PixelBuffer,
+          L: IntermediateCalculatorProducer<'a, P, Flt>,
+          M: Mixer<Flt> + Default,
+          P: PhaseAmpsSelect<'a> + ?Sized
+{
+    render_part_with::<B, L, M, P>(&M::default(), buffer, pitch, pw, ph, phase_amps, x, y, w, h, wrkspc)
+}
+
+/// Renders the part of the plasma into the provided `buffer` without the [Plasma] instance
+/// with the provided `mixer` instance.
+///
+/// See [render_part].
+///
+/// # Panics
+///
+/// __Panics__ if [PhaseAmpsSelect::select] panics.
+#[allow(clippy::too_many_arguments)]
+pub fn render_part_with<'a, B, L, M, P>(mixer: &M, buffer: &mut [u8], pitch: usize, pw: usize, ph: usize,
+                                        phase_amps: &'a P, x: usize, y: usize, w: usize, h: usize,
+                                        wrkspc: Option<&mut Vec<u8>>)
     where B: PixelBuffer,
           L: IntermediateCalculatorProducer<'a, P, Flt>,
           M: Mixer<Flt>,
           P: PhaseAmpsSelect<'a> + ?Sized
 {
-    if x >= pw {
-        return;
-    }
-    else if y >= ph {
+    if x >= pw || y >= ph {
         return;
     }
     /* let's ensure we have some workspace */
@@ -159,16 +668,199 @@ pub fn render_part<'a, B, L, M, P>(buffer: &mut [u8], pitch: usize, pw: usize, p
         },
     };
     /* make sure dimensions are ok */
-    let x2 = min(pw, x + w);
-    let y2 = min(ph, y + h);
-    let dx = x2 - x;
-    let dy = y2 - y;
-    let wr = PI2 / pw as f32;
-    let hr = PI2 / ph as f32;
+    let dx = min(pw, x + w) - x;
+    let dy = min(ph, y + h) - y;
     /* limit buffer view to the requested height */
     let buffer = &mut buffer[0..pitch * dy];
+    /* skip the components of the per-pixel terms, if any */
+    let (separable, _) = split_phase_amps::<M, P>(phase_amps);
+    let (vxps, vyps, _) = prepare_intermediates::<L, M, P>(wrkspc, separable, pw, ph, x, y, dx, dy, 0);
+    /* render lines */
+    for (lines, vyp) in buffer.chunks_mut(Flt::LANES * pitch).zip(vyps.iter()) {
+        gen_lines::<B, M>(mixer, vyp, vxps, lines, pitch, dx);
+    }
+}
+
+/// The pixel buffer of the lines rendered by [render_float_lines].
+type FloatLineBuf = PixelBufRGBF32;
+
+/// The block of lines rendered by [render_float_lines] with the clamped `f32` color components.
+pub(crate) struct FloatLines<'b> {
+    data:       &'b [u8],
+    pitch:      usize,
+    /// The number of pixels of each line.
+    pub width:  usize,
+    /// The number of the rendered lines.
+    pub height: usize,
+}
+
+impl FloatLines<'_> {
+    /// Returns the red, green and blue components of the pixel at the column `col` of the `line`.
+    #[inline]
+    pub fn rgb(&self, line: usize, col: usize) -> [f32; 3] {
+        let offset = line * self.pitch + col * FloatLineBuf::PIXEL_BYTES;
+        let pixel = &self.data[offset..offset + FloatLineBuf::PIXEL_BYTES];
+        [0, 4, 8].map(|n| f32::from_ne_bytes([pixel[n], pixel[n + 1], pixel[n + 2], pixel[n + 3]]))
+    }
+}
+
+/// Renders the part of the plasma in blocks of lines with the clamped `f32` color components for the outputs
+/// which have to convert the pixels in their neighbourhood, see [render_part_with] for the arguments.
+///
+/// Each block is passed to `put_lines` along with the index of its first line relative to `y`.
+/// The blocks are `min_lines` or [Flt::LANES] high, whichever is larger, except the last one.
+/// The `min_lines` must be a power of 2.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn render_float_lines<'a, L, M, P, F>(mixer: &M, pw: usize, ph: usize, phase_amps: &'a P, x: usize,
+                                                 y: usize, w: usize, h: usize, wrkspc: Option<&mut Vec<u8>>,
+                                                 min_lines: usize, mut put_lines: F)
+    where L: IntermediateCalculatorProducer<'a, P, Flt>,
+          M: Mixer<Flt>,
+          P: PhaseAmpsSelect<'a> + ?Sized,
+          F: FnMut(&FloatLines, usize)
+{
+    if x >= pw || y >= ph {
+        return;
+    }
+    let mut tmpwrkspc: Vec<u8>;
+    let wrkspc = match wrkspc {
+        Some(w) => w,
+        None => {
+            tmpwrkspc = Vec::new();
+            &mut tmpwrkspc
+        },
+    };
+    let dx = min(pw, x + w) - x;
+    let dy = min(ph, y + h) - y;
+    let (separable, _) = split_phase_amps::<M, P>(phase_amps);
+    let block = max(min_lines, Flt::LANES);
+    let pitch = dx * FloatLineBuf::PIXEL_BYTES;
+    /* the lines are rendered into the workspace after the intermediate data */
+    let extra = (pitch * block).div_ceil(mem::size_of::<Flt>());
+    let (vxps, vyps, lines) = prepare_intermediates::<L, M, P>(wrkspc, separable, pw, ph, x, y, dx, dy, extra);
+    // SAFETY: any bytes are valid `u8` values and the alignment of `u8` is 1, so the whole slice is reinterpreted
+    let (_, data, _) = unsafe { lines.align_to_mut::<u8>() };
+    let data = &mut data[..pitch * block];
+    data.fill(0);
+    for (row, vyps) in (0..dy).step_by(block).zip(vyps.chunks(block / Flt::LANES)) {
+        for (lines, vyp) in data.chunks_mut(Flt::LANES * pitch).zip(vyps) {
+            gen_lines::<FloatLineBuf, M>(mixer, vyp, vxps, lines, pitch, dx);
+        }
+        put_lines(&FloatLines { data: &*data, pitch, width: dx, height: min(block, dy - row) }, row);
+    }
+}
+
+/// Renders the part of the plasma into the provided `buffer` without the [Plasma] instance
+/// with the provided [PixelTermsMixer] instance.
+///
+/// The separable intermediate data is produced by `L` from the leading components of `phase_amps`
+/// and the per-pixel terms are produced by `T` from the last [PixelTermsMixer::pixel_phase_amps_len]
+/// components. See [render_part] for the description of the other arguments.
+///
+/// # Panics
+///
+/// __Panics__ if [PhaseAmpsSelect::select] panics.
+#[allow(clippy::too_many_arguments)]
+pub fn render_part_terms<'a, B, L, T, M, P>(mixer: &M, buffer: &mut [u8], pitch: usize, pw: usize, ph: usize,
+                                            phase_amps: &'a P, x: usize, y: usize, w: usize, h: usize,
+                                            wrkspc: Option<&mut Vec<u8>>)
+    where B: PixelBuffer,
+          L: IntermediateCalculatorProducer<'a, P, Flt>,
+          T: PixelTermCalculatorProducer<'a, P, Flt>,
+          M: PixelTermsMixer<Flt>,
+          P: PhaseAmpsSelect<'a> + ?Sized
+{
+    if x >= pw || y >= ph {
+        return;
+    }
+    let mut tmpwrkspc: Vec<u8>;
+    let wrkspc = match wrkspc {
+        Some(w) => w,
+        None => {
+            tmpwrkspc = Vec::new();
+            &mut tmpwrkspc
+        },
+    };
+    let dx = min(pw, x + w) - x;
+    let dy = min(ph, y + h) - y;
+    let buffer = &mut buffer[0..pitch * dy];
+    let (separable, terms) = split_phase_amps::<M, P>(phase_amps);
+    let (vxps, vyps, _) = prepare_intermediates::<L, M, P>(wrkspc, separable, pw, ph, x, y, dx, dy, 0);
+    let mut calc_iter = T::compose_p_iter(terms);
+    assert_eq!(M::intermediate_p_len(), calc_iter.len());
+    assert!(calc_iter.len() <= MAX_PIXEL_TERMS);
+    let calcs: [Option<T::TermCalc>; MAX_PIXEL_TERMS] = core::array::from_fn(|_| calc_iter.next());
+    let (wr, hr) = (PI2 / pw as f32, PI2 / ph as f32);
+    for (lines, (vyp, y0)) in buffer.chunks_mut(Flt::LANES * pitch).zip(vyps.iter().zip((y..).step_by(Flt::LANES))) {
+        gen_lines_terms::<B, M, T::TermCalc>(mixer, &calcs, vyp, vxps, lines, pitch, dx, x, y0, wr, hr);
+    }
+}
+
+/// Checks if the `phase_amps` consist of the number of whole components required by the mixer.
+pub(crate) fn check_phase_amps_len<'a, M, P>(phase_amps: &'a P) -> Result<(), PlasmaError>
+    where M: Mixer<Flt>,
+          P: PhaseAmpsSelect<'a> + ?Sized
+{
+    let actual = phase_amps.phase_amps_len();
+    if phase_amps.has_incomplete_phase_amp() {
+        return Err(PlasmaError::OddPhaseAmpsData { len: 2 * actual + 1 });
+    }
+    if actual != M::phase_amps_len() {
+        return Err(PlasmaError::PhaseAmpsLength { expected: M::phase_amps_len(), actual });
+    }
+    Ok(())
+}
+
+/// Checks if the `phase_amps` match the mixer and compose into its intermediate data.
+pub(crate) fn check_phase_amps<'a, L, M, P>(phase_amps: &'a P) -> Result<(), PlasmaError>
+    where L: IntermediateCalculatorProducer<'a, P, Flt>,
+          M: Mixer<Flt>,
+          P: PhaseAmpsSelect<'a> + ?Sized
+{
+    check_phase_amps_len::<M, P>(phase_amps)?;
+    let (separable, _) = split_phase_amps::<M, P>(phase_amps);
+    check_intermediates::<L, M, P>(separable)
+}
+
+/// Checks if the producer composes the number of the intermediate values required by the mixer.
+fn check_intermediates<'a, L, M, P>(separable: &'a P) -> Result<(), PlasmaError>
+    where L: IntermediateCalculatorProducer<'a, P, Flt>,
+          M: Mixer<Flt>,
+          P: PhaseAmpsSelect<'a> + ?Sized
+{
+    let (h, v) = (L::compose_h_iter(separable).len(), L::compose_v_iter(separable).len());
+    if h != M::intermediate_h_len() || v != M::intermediate_v_len() {
+        return Err(PlasmaError::IntermediateLength { expected: M::intermediate_h_len() + M::intermediate_v_len(),
+                                                     actual:   h + v, });
+    }
+    Ok(())
+}
+
+/// Splits the components into the ones of the separable intermediate data and the ones of the per-pixel terms.
+fn split_phase_amps<'a, M, P>(phase_amps: &'a P) -> (&'a P, &'a P)
+    where M: Mixer<Flt>,
+          P: PhaseAmpsSelect<'a> + ?Sized
+{
+    let len = phase_amps.phase_amps_len();
+    let separable = min(len, M::separable_phase_amps_len());
+    (phase_amps.select(0..separable), phase_amps.select(separable..len))
+}
+
+/// Prepares the workspace and precalculates the horizontal and vertical intermediate data.
+///
+/// Returns also the `extra` number of the uninitialized values of the workspace.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn prepare_intermediates<'a, 'w, L, M, P>(wrkspc: &'w mut Vec<u8>, phase_amps: &'a P, pw: usize, ph: usize, x: usize,
+                                          y: usize, dx: usize, dy: usize, extra: usize)
+                                          -> (&'w mut [M::IntermediateH], &'w mut [M::IntermediateV], &'w mut [Flt])
+    where L: IntermediateCalculatorProducer<'a, P, Flt>,
+          M: Mixer<Flt>,
+          P: PhaseAmpsSelect<'a> + ?Sized
+{
+    let wr = PI2 / pw as f32;
+    let hr = PI2 / ph as f32;
     /* prepare workspaces */
-    let (vxps, vyps) = prepare_workspace::<M>(wrkspc, dx, dy);
+    let (vxps, vyps, extra) = prepare_workspace::<M>(wrkspc, dx, dy, extra);
     /* precalculate horizontal tables */
     {
         let mixiter_x = L::compose_h_iter(phase_amps);
@@ -184,9 +876,109 @@ pub fn render_part<'a, B, L, M, P>(buffer: &mut [u8], pitch: usize, pw: usize, p
             prepare_composition_line(i, y, hr, &calc, vyps);
         }
     }
-    /* render lines */
-    for (lines, vyp) in buffer.chunks_mut(Flt::LANES * pitch).zip(vyps.iter()) {
-        gen_lines::<B, M>(vyp, vxps, lines, pitch, dx);
+    (vxps, vyps, extra)
+}
+
+/// Renders the part of the plasma into the provided `buffer` without the [Plasma] instance.
+///
+/// This is a fallible variant of [render_part]. Instead of panicking or silently clipping the rendered
+/// part it returns:
+///
+/// * [PlasmaError::AreaOutOfBounds] if the area given by `x`, `y`, `w` and `h` exceeds `pw` or `ph`,
+/// * [PlasmaError::PitchTooSmall] if the `pitch` can't hold `w` pixels,
+/// * [PlasmaError::BufferTooSmall] if the `buffer` can't hold `h` lines of `pitch` bytes,
+/// * [PlasmaError::PhaseAmpsLength] if the number of `phase_amps` components doesn't match the mixer,
+/// * [PlasmaError::OddPhaseAmpsData] if the `phase_amps` data ends with an incomplete component,
+/// * [PlasmaError::IntermediateLength] if the intermediate calculator producer doesn't match the mixer.
+///
+/// The default instance of the mixer is used, see [try_render_part_with].
+#[allow(clippy::too_many_arguments)]
+#[inline]
+pub fn try_render_part<'a, B, L, M, P>(buffer: &mut [u8], pitch: usize, pw: usize, ph: usize, phase_amps: &'a P,
+                                       x: usize, y: usize, w: usize, h: usize, wrkspc: Option<&mut Vec<u8>>)
+                                       -> Result<(), PlasmaError>
+    where B: PixelBuffer,
+          L: IntermediateCalculatorProducer<'a, P, Flt>,
+          M: Mixer<Flt> + Default,
+          P: PhaseAmpsSelect<'a> + ?Sized
+{
+    try_render_part_with::<B, L, M, P>(&M::default(), buffer, pitch, pw, ph, phase_amps, x, y, w, h, wrkspc)
+}
+
+/// Renders the part of the plasma into the provided `buffer` without the [Plasma] instance
+/// with the provided `mixer` instance.
+///
+/// This is a fallible variant of [render_part_with], see [try_render_part] for the list of errors.
+#[allow(clippy::too_many_arguments)]
+pub fn try_render_part_with<'a, B, L, M, P>(mixer: &M, buffer: &mut [u8], pitch: usize, pw: usize, ph: usize,
+                                            phase_amps: &'a P, x: usize, y: usize, w: usize, h: usize,
+                                            wrkspc: Option<&mut Vec<u8>>)
+                                            -> Result<(), PlasmaError>
+    where B: PixelBuffer,
+          L: IntermediateCalculatorProducer<'a, P, Flt>,
+          M: Mixer<Flt>,
+          P: PhaseAmpsSelect<'a> + ?Sized
+{
+    check_area(pw, ph, x, y, w, h)?;
+    check_phase_amps::<L, M, P>(phase_amps)?;
+    check_buffer::<B>(buffer, pitch, w, h)?;
+    render_part_with::<B, L, M, P>(mixer, buffer, pitch, pw, ph, phase_amps, x, y, w, h, wrkspc);
+    Ok(())
+}
+
+/// Renders the part of the plasma into the provided `buffer` without the [Plasma] instance
+/// with the provided [PixelTermsMixer] instance.
+///
+/// This is a fallible variant of [render_part_terms], see [try_render_part] for the list of errors.
+#[allow(clippy::too_many_arguments)]
+pub fn try_render_part_terms<'a, B, L, T, M, P>(mixer: &M, buffer: &mut [u8], pitch: usize, pw: usize, ph: usize,
+                                                phase_amps: &'a P, x: usize, y: usize, w: usize, h: usize,
+                                                wrkspc: Option<&mut Vec<u8>>)
+                                                -> Result<(), PlasmaError>
+    where B: PixelBuffer,
+          L: IntermediateCalculatorProducer<'a, P, Flt>,
+          T: PixelTermCalculatorProducer<'a, P, Flt>,
+          M: PixelTermsMixer<Flt>,
+          P: PhaseAmpsSelect<'a> + ?Sized
+{
+    check_area(pw, ph, x, y, w, h)?;
+    check_phase_amps_len::<M, P>(phase_amps)?;
+    let (separable, terms) = split_phase_amps::<M, P>(phase_amps);
+    check_intermediates::<L, M, P>(separable)?;
+    let actual = T::compose_p_iter(terms).len();
+    if actual != M::intermediate_p_len() || actual > MAX_PIXEL_TERMS {
+        let expected = min(M::intermediate_p_len(), MAX_PIXEL_TERMS);
+        return Err(PlasmaError::IntermediateLength { expected, actual });
+    }
+    check_buffer::<B>(buffer, pitch, w, h)?;
+    render_part_terms::<B, L, T, M, P>(mixer, buffer, pitch, pw, ph, phase_amps, x, y, w, h, wrkspc);
+    Ok(())
+}
+
+/// Checks if the `buffer` and the `pitch` can hold `h` lines of `w` pixels.
+pub(crate) fn check_buffer<B: PixelBuffer>(buffer: &[u8], pitch: usize, w: usize, h: usize) -> Result<(), PlasmaError> {
+    if w == 0 || h == 0 {
+        return Ok(());
+    }
+    let line_bytes = w * B::PIXEL_BYTES;
+    if pitch < line_bytes {
+        return Err(PlasmaError::PitchTooSmall { required: line_bytes, actual: pitch });
+    }
+    let required = pitch * h;
+    if buffer.len() < required {
+        return Err(PlasmaError::BufferTooSmall { required, actual: buffer.len() });
+    }
+    Ok(())
+}
+
+/// Checks if the area given by `x`, `y`, `w` and `h` pixel coordinates is within the plasma
+/// boundaries given by `pw` and `ph`.
+///
+/// Returns [PlasmaError::AreaOutOfBounds] if it's not.
+pub fn check_area(pw: usize, ph: usize, x: usize, y: usize, w: usize, h: usize) -> Result<(), PlasmaError> {
+    match (x.checked_add(w), y.checked_add(h)) {
+        (Some(x2), Some(y2)) if x2 <= pw && y2 <= ph => Ok(()),
+        _ => Err(PlasmaError::AreaOutOfBounds),
     }
 }
 
@@ -207,29 +999,32 @@ cfg_if! {if #[cfg(feature = "use-simd")] {
 
     #[inline]
     fn vd_size(dv: usize) -> usize {
-        (dv + Flt::LANES - 1) / Flt::LANES
+        dv.div_ceil(Flt::LANES)
     }
 
-    fn prepare_workspace<M>(tmp: &mut Vec<u8>, dx: usize, dy: usize) -> (&mut [M::IntermediateH], &mut [M::IntermediateV])
+    #[allow(clippy::type_complexity)]
+    fn prepare_workspace<M>(tmp: &mut Vec<u8>, dx: usize, dy: usize, extra: usize)
+        -> (&mut [M::IntermediateH], &mut [M::IntermediateV], &mut [f32s])
     where M: Mixer<f32s>
     {
         let dxsize = vd_size(dx);
         let dysize = vd_size(dy);
         let xsize = dxsize * M::intermediate_h_len();
         let ysize = dysize * M::intermediate_v_len();
-        let slice: &mut[f32s] = unsafe { make_temporary_slice_mut(tmp, xsize + ysize) };
-        let (ax, ay) = slice.split_at_mut(xsize);
+        let slice: &mut[f32s] = unsafe { make_temporary_slice_mut(tmp, xsize + ysize + extra) };
+        let (ax, rest) = slice.split_at_mut(xsize);
+        let (ay, extra) = rest.split_at_mut(ysize);
         let (_, ax, _) = unsafe { ax.align_to_mut::<M::IntermediateH>() };
         let (_, ay, _) = unsafe { ay.align_to_mut::<M::IntermediateV>() };
         assert_eq!(ax.len(), dxsize);
         assert_eq!(ay.len(), dysize);
-        (ax, ay)
+        (ax, ay, extra)
     }
 
-    fn gen_lines<B, M>(vyp: &M::IntermediateV, vxps: &[M::IntermediateH], lines: &mut [u8], pitch: usize, dx: usize)
-    where B: PixelBuffer, M: Mixer<f32s>, M::IntermediateV: Borrow<[f32s]> + BorrowMut<[f32s]>
+    /// Splats each lane of the vertical intermediate data.
+    fn splat_lanes<M>(vyp: &M::IntermediateV) -> [M::IntermediateV; Flt::LANES]
+    where M: Mixer<f32s>
     {
-        /* splat each y */
         let mut vypl: [M::IntermediateV; Flt::LANES] = Default::default();
         for (i, &ys) in vyp.borrow().iter().enumerate() {
             let ys: f32tuple = ys.into();
@@ -237,6 +1032,41 @@ cfg_if! {if #[cfg(feature = "use-simd")] {
                 vyp.borrow_mut()[i] = Flt::splat(y);
             }
         }
+        vypl
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn gen_lines_terms<B, M, C>(mixer: &M, calcs: &[Option<C>], vyp: &M::IntermediateV, vxps: &[M::IntermediateH],
+                                lines: &mut [u8], pitch: usize, dx: usize, x0: usize, y0: usize, wr: f32, hr: f32)
+    where B: PixelBuffer, M: PixelTermsMixer<f32s>, C: PixelTermCalculator<f32s>
+    {
+        let vypl = splat_lanes::<M>(vyp);
+        let line_end = B::PIXEL_BYTES * dx;
+        let wr = Flt::splat(wr);
+        for ((line, vyp), y) in lines.chunks_exact_mut(pitch).zip(vypl.iter()).zip(y0..) {
+            let ys = Flt::splat(y as f32 * hr);
+            let mut writer = line[0..line_end].iter_mut();
+            let mut next_pixel = |pixel| {
+                B::put_pixel(&mut writer, pixel);
+            };
+            for (vxp, x) in vxps.iter().zip((x0..).step_by(Flt::LANES)) {
+                let xs: f32s = simd_new_consecutive!(u32s, x as u32).cast();
+                let xs = xs * wr;
+                let mut vpp = M::IntermediateP::default();
+                for (v, calc) in vpp.borrow_mut().iter_mut().zip(calcs.iter().flatten()) {
+                    *v = calc.calculate(xs, ys);
+                }
+                mixer.mix_pixels_terms(vxp, vyp, &vpp, &mut next_pixel);
+            }
+        }
+    }
+
+    fn gen_lines<B, M>(mixer: &M, vyp: &M::IntermediateV, vxps: &[M::IntermediateH], lines: &mut [u8], pitch: usize,
+                       dx: usize)
+    where B: PixelBuffer, M: Mixer<f32s>, M::IntermediateV: Borrow<[f32s]> + BorrowMut<[f32s]>
+    {
+        /* splat each y */
+        let vypl = splat_lanes::<M>(vyp);
         let line_end = B::PIXEL_BYTES * dx;
         for (line, vyp) in lines.chunks_exact_mut(pitch).zip(vypl.iter()) {
             let mut writer = line[0..line_end].iter_mut();
@@ -244,7 +1074,7 @@ cfg_if! {if #[cfg(feature = "use-simd")] {
                 B::put_pixel(&mut writer, pixel);
             };
             for vxp in vxps.iter() {
-                M::mix_pixels(vxp, vyp, &mut next_pixel);
+                mixer.mix_pixels(vxp, vyp, &mut next_pixel);
             }
         }
     }
@@ -259,21 +1089,45 @@ else {
         }
     }
 
-    fn prepare_workspace<M>(tmp: &mut Vec<u8>, dx: usize, dy: usize) -> (&mut [M::IntermediateH], &mut [M::IntermediateV])
+    #[allow(clippy::type_complexity)]
+    fn prepare_workspace<M>(tmp: &mut Vec<u8>, dx: usize, dy: usize, extra: usize)
+        -> (&mut [M::IntermediateH], &mut [M::IntermediateV], &mut [f32])
     where M: Mixer<f32>
     {
         let xsize = dx * M::intermediate_h_len();
         let ysize = dy * M::intermediate_v_len();
-        let slice: &mut[f32] = unsafe { make_temporary_slice_mut(tmp, xsize + ysize) };
-        let (ax, ay) = slice.split_at_mut(xsize);
+        let slice: &mut[f32] = unsafe { make_temporary_slice_mut(tmp, xsize + ysize + extra) };
+        let (ax, rest) = slice.split_at_mut(xsize);
+        let (ay, extra) = rest.split_at_mut(ysize);
         let (_, ax, _) = unsafe { ax.align_to_mut::<M::IntermediateH>() };
         let (_, ay, _) = unsafe { ay.align_to_mut::<M::IntermediateV>() };
         assert_eq!(ax.len(), dx);
         assert_eq!(ay.len(), dy);
-        (ax, ay)
+        (ax, ay, extra)
     }
 
-    fn gen_lines<B, M>(vyp: &M::IntermediateV, vxps: &[M::IntermediateH], line: &mut [u8], _pitch: usize, _dx: usize)
+    #[allow(clippy::too_many_arguments)]
+    fn gen_lines_terms<B, M, C>(mixer: &M, calcs: &[Option<C>], vyp: &M::IntermediateV, vxps: &[M::IntermediateH],
+                                line: &mut [u8], _pitch: usize, _dx: usize, x0: usize, y: usize, wr: f32, hr: f32)
+    where B: PixelBuffer, M: PixelTermsMixer<f32>, C: PixelTermCalculator<f32>
+    {
+        let mut writer = line.iter_mut();
+        let mut next_pixel = |pixel| {
+            B::put_pixel(&mut writer, pixel);
+        };
+        let ys = y as f32 * hr;
+        for (vxp, x) in vxps.iter().zip(x0..) {
+            let xs = x as f32 * wr;
+            let mut vpp = M::IntermediateP::default();
+            for (v, calc) in vpp.borrow_mut().iter_mut().zip(calcs.iter().flatten()) {
+                *v = calc.calculate(xs, ys);
+            }
+            mixer.mix_pixels_terms(vxp, vyp, &vpp, &mut next_pixel);
+        }
+    }
+
+    fn gen_lines<B, M>(mixer: &M, vyp: &M::IntermediateV, vxps: &[M::IntermediateH], line: &mut [u8], _pitch: usize,
+                       _dx: usize)
     where B: PixelBuffer, M: Mixer<f32>
     {
         let mut writer = line.iter_mut();
@@ -282,17 +1136,43 @@ else {
         };
 
         for vxp in vxps.iter() {
-            M::mix_pixels(vxp, vyp, &mut next_pixel);
+            mixer.mix_pixels(vxp, vyp, &mut next_pixel);
         }
     }
 }}
 
+#[inline]
+fn f32_to_u32_exact(value: f32) -> Option<u32> {
+    let res = value as u32;
+    if res as f32 == value {
+        Some(res)
+    }
+    else {
+        None
+    }
+}
+
+/// Exports the 64bit integer as four 16bit parts, each exactly representable as a 32bit float.
+#[inline]
+fn export_u64(value: u64, out: &mut Vec<f32>) {
+    out.extend(value.to_le_bytes().chunks_exact(2).map(|b| u16::from_le_bytes([b[0], b[1]]) as f32));
+}
+
+/// Imports the 64bit integer exported with `export_u64`.
+fn import_u64(source: &[f32]) -> Option<u64> {
+    let mut value = 0u64;
+    for &part in source.iter().rev() {
+        value = value << 16 | u16::try_from(f32_to_u32_exact(part)?).ok()? as u64;
+    }
+    Some(value)
+}
+
 // 1. this function will clear provided vec
 // 2. uses vecs memory without initialization (reserves more if needed)
 // 3. returned slice is aligned to type T.
 // 4. T must not implement drop (e.g. primitives)
 // 5. values in slice may be uninitialized
-unsafe fn make_temporary_slice_mut<'a, T: Copy>(vec: &'a mut Vec<u8>, len: usize) -> &'a mut [T] {
+unsafe fn make_temporary_slice_mut<T: Copy>(vec: &mut Vec<u8>, len: usize) -> &mut [T] {
     use core::{mem, slice};
     assert!(!mem::needs_drop::<T>(), "only non droppable types please");
     vec.clear();
@@ -306,3 +1186,284 @@ unsafe fn make_temporary_slice_mut<'a, T: Copy>(vec: &'a mut Vec<u8>, len: usize
     let (_, res, _) = raw_bytes.align_to_mut::<T>();
     res
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{simd_polyfill::Flt, *};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn state_works() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let cfg = PhaseAmpCfg::new(80.0, 200.0);
+        let mut plasma = Plasma::new(64, 48, cfg, &mut rng);
+        for _ in 0..100 {
+            plasma.update(&mut rng);
+        }
+        let mut state: Vec<f32> = Vec::new();
+        plasma.export_state(&mut state);
+        assert_eq!(state.len(), 14 + PhaseAmpCfg::STATE_LEN + 24 * PhaseAmp::STATE_LEN);
+        let mut restored = Plasma::from_state(&state).unwrap();
+        assert_eq!(restored, plasma);
+
+        let mut rng2 = rng.clone();
+        for _ in 0..100 {
+            plasma.update(&mut rng);
+            restored.update(&mut rng2);
+        }
+        assert_eq!(restored, plasma);
+
+        assert_eq!(Plasma::from_state(&state[..state.len() - 1]), None);
+        assert_eq!(Plasma::from_state(&[]), None);
+        let mut bad_version = state.clone();
+        bad_version[0] = (Plasma::STATE_VERSION + 1) as f32;
+        assert_eq!(Plasma::from_state(&bad_version), None);
+        let mut bad_width = state.clone();
+        bad_width[1] = 0.5;
+        assert_eq!(Plasma::from_state(&bad_width), None);
+
+        let cfg_end = 3 + PhaseAmpCfg::STATE_LEN;
+        let legacy_state = |version: f32, config_len: usize, timeline_len: usize, phase_amp_len: usize| {
+            let mut legacy = vec![version];
+            legacy.extend_from_slice(&state[1..3 + config_len]);
+            legacy.extend_from_slice(&state[cfg_end..cfg_end + timeline_len]);
+            legacy.push(state[cfg_end + 10]);
+            for pa in state[cfg_end + 11..].chunks_exact(PhaseAmp::STATE_LEN) {
+                legacy.extend_from_slice(&pa[..phase_amp_len]);
+            }
+            legacy
+        };
+        let (config_v1, phase_amp_v1) = (PhaseAmpCfg::STATE_LEN_V1, PhaseAmp::STATE_LEN_V1);
+        for (version, config_len, timeline_len, phase_amp_len) in [(1.0, config_v1, 0, phase_amp_v1),
+                                                                   (2.0, config_v1, 5, phase_amp_v1),
+                                                                   (3.0, config_v1, 6, phase_amp_v1),
+                                                                   (4.0, PhaseAmpCfg::STATE_LEN, 6, phase_amp_v1),
+                                                                   (5.0, PhaseAmpCfg::STATE_LEN, 6, PhaseAmp::STATE_LEN)]
+        {
+            let legacy = legacy_state(version, config_len, timeline_len, phase_amp_len);
+            assert_eq!(Plasma::from_state(&legacy), Plasma::from_state(&state));
+        }
+        let mut bad_fraction = state.clone();
+        *bad_fraction.last_mut().unwrap() = 1.0;
+        assert_eq!(Plasma::from_state(&bad_fraction), None);
+        for bad_value in [f32::NAN, f32::INFINITY] {
+            let mut bad_phase_amp = state.clone();
+            *bad_phase_amp.last_mut().unwrap() = bad_value;
+            assert_eq!(Plasma::from_state(&bad_phase_amp), None);
+            let mut bad_config = state.clone();
+            bad_config[4] = bad_value;
+            assert_eq!(Plasma::from_state(&bad_config), None);
+        }
+        let mut bad_loop = state.clone();
+        bad_loop[8 + PhaseAmpCfg::STATE_LEN] = 100.0;
+        assert_eq!(Plasma::from_state(&bad_loop), None);
+
+        let plasma = Plasma::new_timeline(64, 48, cfg, 0xfedc_ba98_7654_3210);
+        state.clear();
+        plasma.export_state(&mut state);
+        assert_eq!(Plasma::from_state(&state), Some(plasma));
+
+        let mut plasma = Plasma::new_looped(64, 48, cfg, 0xfedc_ba98_7654_3210, 600.0);
+        plasma.at_loop_frame(7, 30);
+        state.clear();
+        plasma.export_state(&mut state);
+        let restored = Plasma::from_state(&state).unwrap();
+        assert_eq!(restored.time(), 140.0);
+        assert_eq!(restored, plasma);
+        let mut bad_time = state.clone();
+        bad_time[cfg_end + 6..cfg_end + 10].copy_from_slice(&[0.0, 0.0, 0.0, 65535.0]);
+        assert_eq!(Plasma::from_state(&bad_time), None);
+    }
+
+    #[test]
+    fn looped_works() {
+        let cfg = PhaseAmpCfg::new(80.0, 200.0);
+        let mut plasma = Plasma::new_looped(64, 48, cfg, 12345, 600.0);
+        assert_eq!(plasma.timeline_seed(), Some(12345));
+        assert_eq!(plasma.loop_steps(), Some(600.0));
+        let mut first: Vec<f32> = Vec::new();
+        plasma.export_phase_amps(&mut first);
+        plasma.at_loop_frame(7, 30);
+        let mut other = plasma.clone();
+        other.at_time(140.0);
+        assert_eq!(other, plasma);
+        plasma.at_loop_frame(30, 30);
+        let mut last: Vec<f32> = Vec::new();
+        plasma.export_phase_amps(&mut last);
+        assert_eq!(first, last);
+        plasma.at_time(600.0);
+        last.clear();
+        plasma.export_phase_amps(&mut last);
+        for (a, b) in first.chunks_exact(2).zip(last.chunks_exact(2)) {
+            assert!((a[0] - b[0]).abs() < 1e-4 || ((a[0] - b[0]).abs() - 2.0 * core::f32::consts::PI).abs() < 1e-4);
+            assert!((a[1] - b[1]).abs() < 1e-5);
+        }
+        assert_ne!(Plasma::new_looped(64, 48, cfg, 12345, 600.0), Plasma::new_timeline(64, 48, cfg, 12345));
+        for loop_steps in [0.0, -1.0, f32::NAN, f32::INFINITY] {
+            assert_eq!(Plasma::try_new_looped(64, 48, cfg, 12345, loop_steps), Err(PlasmaError::InvalidLoopSteps));
+        }
+        assert_eq!(plasma.try_at_loop_frame(1, 0), Err(PlasmaError::InvalidFrames));
+        assert_eq!(plasma.try_at_loop_frame(37, 30), Ok(()));
+        assert_eq!(plasma.time(), 140.0);
+        let mut timeline = Plasma::new_timeline(64, 48, cfg, 12345);
+        assert_eq!(timeline.try_at_loop_frame(7, 30), Err(PlasmaError::NotLooped));
+    }
+
+    #[test]
+    fn timeline_works() {
+        let cfg = PhaseAmpCfg::new(80.0, 200.0);
+        let mut plasma = Plasma::new_timeline(64, 48, cfg, 12345);
+        assert_eq!(plasma.timeline_seed(), Some(12345));
+        assert_eq!(plasma, Plasma::new_timeline(64, 48, cfg, 12345));
+        let mut phase_amps: Vec<f32> = Vec::new();
+        plasma.at_time(1000.0);
+        plasma.export_phase_amps(&mut phase_amps);
+        let mut other = Plasma::new_timeline(64, 48, cfg, 12345);
+        for t in (0..=2000).rev() {
+            other.at_time(t as f64 * 0.5);
+        }
+        other.at_time(1000.0);
+        assert_eq!(other, plasma);
+        other.at_time(-1000.0);
+        assert_ne!(other, plasma);
+        assert_eq!(other.try_at_time(1000.0), Ok(()));
+        assert_eq!(other, plasma);
+        let (mut seeded, _) = Plasma::new_seeded(64, 48, cfg, 12345);
+        let orig = seeded.clone();
+        assert_eq!(seeded.try_at_time(1000.0), Err(PlasmaError::NotTimeline));
+        assert_eq!(seeded, orig);
+        assert_ne!(Plasma::new_timeline(64, 48, cfg, 12346), Plasma::new_timeline(64, 48, cfg, 12345));
+    }
+
+    #[test]
+    fn phase_amps_len_works() {
+        #[derive(Default)]
+        struct HalfMixer;
+
+        impl Mixer<Flt> for HalfMixer {
+            type IntermediateH = [Flt; 3];
+            type IntermediateV = [Flt; 3];
+
+            fn mix_pixels(&self, vxp: &Self::IntermediateH, vyp: &Self::IntermediateV,
+                          next_pixel: &mut dyn FnMut(PixelRgb)) {
+                next_pixel(PixelRgb::new(vxp[0] * vyp[0], vxp[1] * vyp[1], vxp[2] * vyp[2]));
+            }
+        }
+
+        assert_eq!(PlasmaMixer::phase_amps_len(), Plasma::DEFAULT_PHASE_AMPS_LEN);
+        assert_eq!(HalfMixer::phase_amps_len(), 12);
+        let cfg = PhaseAmpCfg::new(80.0, 200.0);
+        let (mut plasma, mut rng) = Plasma::new_seeded(32, 16, cfg, 1);
+        let (first, _) = Plasma::new_seeded(32, 16, cfg, 1);
+        plasma.set_phase_amps_len(HalfMixer::phase_amps_len(), &mut rng);
+        assert_eq!(plasma.phase_amps_len(), 12);
+        let mut phase_amps: Vec<f32> = Vec::new();
+        plasma.export_phase_amps(&mut phase_amps);
+        let mut first_amps: Vec<f32> = Vec::new();
+        first.export_phase_amps(&mut first_amps);
+        assert_eq!(phase_amps[..], first_amps[..24]);
+        let orig = plasma.clone();
+        assert_eq!(plasma.try_import_phase_amps(&first_amps),
+                   Err(PlasmaError::PhaseAmpsLength { expected: 12, actual: 24 }));
+        assert_eq!(plasma.try_import_phase_amps(&first_amps[..23]), Err(PlasmaError::OddPhaseAmpsData { len: 23 }));
+        assert_eq!(plasma, orig);
+        assert_eq!(plasma.try_import_phase_amps(&first_amps[..24]), Ok(()));
+
+        let pitch = 32 * PixelBufRGB24::PIXEL_BYTES;
+        let mut buffer = vec![0u8; pitch * 16];
+        let mut buffer_ext = vec![0u8; pitch * 16];
+        plasma.render::<PixelBufRGB24, PlasmaICP, HalfMixer>(&mut buffer, pitch, None);
+        render_part::<PixelBufRGB24, PlasmaICPExtPa, HalfMixer, _>(&mut buffer_ext, pitch, 32, 16, &phase_amps[..],
+                                                                   0, 0, 32, 16, None);
+        assert_eq!(buffer, buffer_ext);
+
+        let mut state: Vec<f32> = Vec::new();
+        plasma.export_state(&mut state);
+        assert_eq!(Plasma::from_state(&state).as_ref(), Some(&plasma));
+        plasma.set_phase_amps_len(30, &mut rng);
+        assert_eq!(plasma.phase_amps_len(), 30);
+        state.clear();
+        plasma.export_state(&mut state);
+        assert_eq!(Plasma::from_state(&state), Some(plasma));
+    }
+
+    #[test]
+    fn try_render_works() {
+        let cfg = PhaseAmpCfg::new(80.0, 200.0);
+        let (plasma, _) = Plasma::new_seeded(32, 16, cfg, 1);
+        let pitch = 32 * PixelBufRGB24::PIXEL_BYTES;
+        let mut buffer = vec![0u8; pitch * 16];
+        let mut expected = vec![0u8; pitch * 16];
+        plasma.render::<PixelBufRGB24, PlasmaICP, PlasmaMixer>(&mut expected, pitch, None);
+        assert_eq!(plasma.try_render::<PixelBufRGB24, PlasmaICP, PlasmaMixer>(&mut buffer, pitch, None), Ok(()));
+        assert_eq!(buffer, expected);
+        assert_eq!(plasma.try_render::<PixelBufRGB24, PlasmaICP, PlasmaMixer>(&mut buffer[1..], pitch, None),
+                   Err(PlasmaError::BufferTooSmall { required: pitch * 16, actual: pitch * 16 - 1 }));
+        assert_eq!(plasma.try_render::<PixelBufRGB24, PlasmaICP, PlasmaMixer>(&mut buffer, pitch - 1, None),
+                   Err(PlasmaError::PitchTooSmall { required: pitch, actual: pitch - 1 }));
+        assert_eq!(plasma.try_render_part::<PixelBufRGB24, PlasmaICP, PlasmaMixer>(&mut buffer, pitch, 1, 0, 32, 1, None),
+                   Err(PlasmaError::AreaOutOfBounds));
+        assert_eq!(plasma.try_render_part::<PixelBufRGB24, PlasmaICP, PlasmaMixer>(&mut buffer, pitch, 0, 8, 32, 8, None),
+                   Ok(()));
+        assert_eq!(plasma.check_area(0, 0, 32, 17), Err(PlasmaError::AreaOutOfBounds));
+        assert_eq!(plasma.check_area(usize::MAX, 0, 1, 1), Err(PlasmaError::AreaOutOfBounds));
+        assert_eq!(plasma.check_area(32, 16, 0, 0), Ok(()));
+        let mut phase_amps: Vec<f32> = Vec::new();
+        plasma.export_phase_amps(&mut phase_amps);
+        let res = try_render_part::<PixelBufRGB24, PlasmaICPExtPa, PlasmaMixer, _>(&mut buffer, pitch, 32, 16,
+                                                                                   &phase_amps[4..], 0, 0, 32, 16,
+                                                                                   None);
+        assert_eq!(res, Err(PlasmaError::PhaseAmpsLength { expected: 24, actual: 22 }));
+        let mut extra_amps = phase_amps.clone();
+        extra_amps.extend(&[1.0, 0.5]);
+        let res = try_render_part::<PixelBufRGB24, PlasmaICPExtPa, PlasmaMixer, _>(&mut buffer, pitch, 32, 16,
+                                                                                   &extra_amps[..], 0, 0, 32, 16,
+                                                                                   None);
+        assert_eq!(res, Err(PlasmaError::PhaseAmpsLength { expected: 24, actual: 25 }));
+        let res = try_render_part::<PixelBufRGB24, PlasmaICPExtPa, PlasmaMixer, _>(&mut buffer, pitch, 32, 16,
+                                                                                   &extra_amps[..49], 0, 0, 32, 16,
+                                                                                   None);
+        assert_eq!(res, Err(PlasmaError::OddPhaseAmpsData { len: 49 }));
+        let res = try_render_part::<PixelBufRGB24, MonochromeICPExtPa, PlasmaMixer, _>(&mut buffer, pitch, 32, 16,
+                                                                                       &phase_amps[..], 0, 0, 32, 16,
+                                                                                       None);
+        assert_eq!(res, Err(PlasmaError::IntermediateLength { expected: 12, actual: 24 }));
+    }
+
+    #[test]
+    fn seeded_works() {
+        let cfg = PhaseAmpCfg::new(80.0, 200.0);
+        let (mut plasma, mut rng) = Plasma::new_seeded(64, 48, cfg, 12345);
+        let mut phase_amps: Vec<f32> = Vec::new();
+        plasma.export_phase_amps(&mut phase_amps);
+        assert_eq!(&phase_amps[..6], [4.9524884, 0.048969388, 1.4934502, 0.90098953, 0.6730206, 0.36957717]);
+        for _ in 0..1000 {
+            plasma.update(&mut rng);
+        }
+        phase_amps.clear();
+        plasma.export_phase_amps(&mut phase_amps);
+        assert_eq!(&phase_amps[..6], [-4.066217, 0.042328514, -14.42165, 0.15798372, 4.984758, 0.43910637]);
+
+        let (mut plasma2, mut rng2) = Plasma::new_seeded(64, 48, cfg, 12345);
+        for _ in 0..1000 {
+            plasma2.update(&mut rng2);
+        }
+        assert_eq!(plasma2, plasma);
+        let (plasma3, _) = Plasma::new_seeded(64, 48, cfg, 12346);
+        assert_ne!(plasma3, Plasma::new_seeded(64, 48, cfg, 12345).0);
+
+        let (mut plasma2, mut rng2) = Plasma::new_seeded(64, 48, cfg, 12345);
+        let (mut plasma3, mut rng3) = Plasma::new_seeded(64, 48, cfg, 12345);
+        for _ in 0..500 {
+            plasma2.update_by(2.0, &mut rng2);
+            plasma3.update_by(1.0, &mut rng3);
+            plasma3.update_by(1.0, &mut rng3);
+        }
+        assert_eq!(plasma3, plasma2);
+        let (mut plasma4, mut rng4) = Plasma::new_seeded(64, 48, cfg, 12345);
+        for _ in 0..4000 {
+            plasma4.update_by(0.25, &mut rng4);
+        }
+        assert_eq!(plasma4, plasma2);
+    }
+}
@@ -14,10 +14,12 @@ pub trait SimdCompat: Copy + Sized {
     fn sc_min(self, other: Self) -> Self;
     /// Return the element-wise maximum with other
     fn sc_max(self, other: Self) -> Self;
+    /// Return the value of the first lane
+    fn sc_first_lane(self) -> f32;
 }
 
 cfg_if! {if #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "use-simd"))] {
-    pub use std::simd::{StdFloat, cmp::SimdPartialOrd, prelude::{SimdFloat, mask32x8, u32x8, f32x8}};
+    pub use std::simd::{Select, StdFloat, cmp::SimdPartialOrd, prelude::{SimdFloat, mask32x8, u32x8, f32x8}};
     // #[cfg(all(target_arch = "x86", target_feature = "sse4.1"))]
     // use std::arch::x86::*;
     // #[cfg(all(target_arch = "x86_64", target_feature = "sse4.1"))]
@@ -43,6 +45,8 @@ cfg_if! {if #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature
         fn sc_min(self, other: Self) -> Self { self.simd_min(other) }
         #[inline(always)]
         fn sc_max(self, other: Self) -> Self { self.simd_max(other) }
+        #[inline(always)]
+        fn sc_first_lane(self) -> f32 { self[0] }
     }
 
     macro_rules! simd_new_consecutive {
@@ -100,7 +104,7 @@ cfg_if! {if #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature
     // }}
 }
 else if #[cfg(all(target_arch = "aarch64", feature = "use-simd"))] {
-    pub use std::simd::{StdFloat, cmp::SimdPartialOrd, prelude::{SimdFloat, mask32x4, u32x4, f32x4}};
+    pub use std::simd::{Select, StdFloat, cmp::SimdPartialOrd, prelude::{SimdFloat, mask32x4, u32x4, f32x4}};
     // use core::ops::Sub;
 
     #[allow(non_camel_case_types)]
@@ -123,6 +127,8 @@ else if #[cfg(all(target_arch = "aarch64", feature = "use-simd"))] {
         fn sc_min(self, other: Self) -> Self { self.simd_min(other) }
         #[inline(always)]
         fn sc_max(self, other: Self) -> Self { self.simd_max(other) }
+        #[inline(always)]
+        fn sc_first_lane(self) -> f32 { self[0] }
     }
 
     macro_rules! simd_new_consecutive {
@@ -171,5 +177,7 @@ else {
         fn sc_min(self, other: Self) -> Self { self.min(other) }
         #[inline(always)]
         fn sc_max(self, other: Self) -> Self { self.max(other) }
+        #[inline(always)]
+        fn sc_first_lane(self) -> f32 { self }
     }
 }}
@@ -0,0 +1,248 @@
+//! A seekable plasma animation.
+//!
+//! In this mode the phase and the amplitude of each [PhaseAmp] are pure functions of time,
+//! computed from a seeded smooth noise instead of being updated randomly step by step.
+//! Optionally the trajectories may be periodic, producing a seamlessly looping animation.
+use crate::{easing::Easing, phase_amp::*};
+
+const PI2: f64 = 2.0 * core::f64::consts::PI;
+/// The largest absolute value of the smooth noise derivative is `2 * 2.04 / period`,
+/// this divisor keeps the phase velocity of the wandering below a half of the maximum.
+const PHASE_WANDER_DIV: f32 = 9.0;
+
+const SALT_PARAMS: u64 = 0x243f_6a88_85a3_08d3;
+const SALT_PHASE: u64 = 0x1319_8a2e_0370_7344;
+const SALT_AMPLITUDE: u64 = 0xa409_3822_299f_31d0;
+
+/// Computes the phase and the amplitude of a single plasma component as a function of time.
+///
+/// Instances are created from a `seed` and the same `seed` with the same [PhaseAmpConfig] always
+/// yields the same trajectory. The time is expressed in animation steps and may be any finite
+/// number, including a negative one.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct PhaseAmpTimeline {
+    phase:          f64,
+    phase_velocity: f64,
+    phase_wander:   f32,
+    phase_noise:    SmoothNoise,
+    amplitude:      SmoothNoise,
+    amplitude_min:  f32,
+    amplitude_max:  f32,
+    easing:         Easing,
+}
+
+/// A value noise in the range `[0, 1]` with knots placed every `period` steps.
+///
+/// If `knots` is not zero the knot values repeat after `knots` knots.
+#[derive(Debug, PartialEq, Clone, Copy)]
+struct SmoothNoise {
+    seed:   u64,
+    period: f64,
+    knots:  u64,
+}
+
+impl PhaseAmpTimeline {
+    /// Creates new [PhaseAmpTimeline] instance from the provided `seed`.
+    pub fn new<C: PhaseAmpConfig>(cfg: &C, seed: u64) -> Self {
+        let (min_steps, max_steps) = (cfg.min_steps(), cfg.max_steps());
+        let param = |n| unit_f32(hash(seed ^ SALT_PARAMS, n));
+        let phase = param(0) as f64 * PI2;
+        let phase_velocity = ((param(1) - 0.5) * cfg.delta_phase_abs_max()) as f64;
+        let phase_period = min_steps + (max_steps - min_steps) * param(2);
+        let phase_wander = cfg.delta_phase_abs_max() * phase_period / PHASE_WANDER_DIV;
+        let amplitude_period = min_steps + (max_steps - min_steps) * param(3);
+        PhaseAmpTimeline { phase,
+                           phase_velocity,
+                           phase_wander,
+                           phase_noise: SmoothNoise::new(seed ^ SALT_PHASE, phase_period as f64, 0),
+                           amplitude: SmoothNoise::new(seed ^ SALT_AMPLITUDE, amplitude_period as f64, 0),
+                           amplitude_min: cfg.amplitude_min(),
+                           amplitude_max: cfg.amplitude_max(),
+                           easing: cfg.easing() }
+    }
+
+    /// Creates new [PhaseAmpTimeline] instance from the provided `seed` with trajectories
+    /// repeating every `loop_steps` animation steps.
+    ///
+    /// The phase and the amplitude at the time `t + loop_steps` are the same as at the time `t`
+    /// and the transition between the end and the beginning of the loop is as smooth as
+    /// anywhere else. The phase makes a whole number of turns during each loop.
+    pub fn new_looped<C: PhaseAmpConfig>(cfg: &C, seed: u64, loop_steps: f32) -> Self {
+        let timeline = Self::new(cfg, seed);
+        let loop_steps = loop_steps as f64;
+        let param = |n| unit_f32(hash(seed ^ SALT_PARAMS, n));
+        let max_turns = floor(cfg.delta_phase_abs_max() as f64 / 2.0 * loop_steps / PI2);
+        let turns = floor((param(4) as f64 * (2.0 * max_turns + 1.0)).min(2.0 * max_turns)) - max_turns;
+        let phase_velocity = turns * PI2 / loop_steps;
+        let phase_noise = timeline.phase_noise.looped(loop_steps);
+        let phase_wander = cfg.delta_phase_abs_max() * phase_noise.period as f32 / PHASE_WANDER_DIV;
+        PhaseAmpTimeline { phase_velocity,
+                           phase_wander,
+                           phase_noise,
+                           amplitude: timeline.amplitude.looped(loop_steps),
+                           ..timeline }
+    }
+
+    /// Returns the phase at the time `t` in the range: `[0, 2PI)`.
+    pub fn phase_at(&self, t: f64) -> f32 {
+        let noise = self.phase_noise.value_at(t) * 2.0 - 1.0;
+        let phase = self.phase + self.phase_velocity * t + (self.phase_wander * noise) as f64;
+        (phase - floor(phase / PI2) * PI2) as f32
+    }
+
+    /// Returns the amplitude at the time `t` in the range of the configured amplitudes.
+    pub fn amplitude_at(&self, t: f64) -> f32 {
+        let (source, target, transition) = self.amplitude_knots_at(t);
+        source + (target - source) * self.easing.ease(transition)
+    }
+
+    /// Returns the [PhaseAmp] at the time `t`.
+    ///
+    /// Apart from the phase and the amplitude, the returned instance has its animation state set
+    /// to follow the current trajectory, so it can be further animated with [PhaseAmp::update].
+    pub fn phase_amp_at(&self, t: f64) -> PhaseAmp {
+        let phase = self.phase_at(t);
+        let delta_phase = self.phase_at(t + 1.0) - phase;
+        let delta_phase = match delta_phase {
+            d if d > PI2 as f32 / 2.0 => d - PI2 as f32,
+            d if d < -PI2 as f32 / 2.0 => d + PI2 as f32,
+            d => d,
+        };
+        let (source, target, transition) = self.amplitude_knots_at(t);
+        let step = self.amplitude.period.recip() as f32;
+        PhaseAmp::with_transition(phase, delta_phase, source, target, step, transition, self.easing)
+    }
+
+    /// Returns the amplitudes of the surrounding knots and the transition between them.
+    fn amplitude_knots_at(&self, t: f64) -> (f32, f32, f32) {
+        let (v0, v1, transition) = self.amplitude.knots_at(t);
+        let range = self.amplitude_max - self.amplitude_min;
+        (self.amplitude_min + v0 * range, self.amplitude_min + v1 * range, transition)
+    }
+}
+
+impl SmoothNoise {
+    fn new(seed: u64, period: f64, knots: u64) -> Self { SmoothNoise { seed, period, knots } }
+
+    /// Adjusts the period so a whole number of knots fits in `loop_steps` and repeats them.
+    fn looped(self, loop_steps: f64) -> Self {
+        let knots = floor(loop_steps / self.period + 0.5).max(1.0);
+        SmoothNoise::new(self.seed, loop_steps / knots, knots as u64)
+    }
+
+    fn value_at(&self, t: f64) -> f32 {
+        let (v0, v1, transition) = self.knots_at(t);
+        v0 + (v1 - v0) * transform(transition)
+    }
+
+    /// Returns the values of the surrounding knots and the transition between them.
+    fn knots_at(&self, t: f64) -> (f32, f32, f32) {
+        let x = t / self.period;
+        let knot = floor(x);
+        let index = knot as i64;
+        (self.knot_value(index), self.knot_value(index.wrapping_add(1)), (x - knot) as f32)
+    }
+
+    fn knot_value(&self, index: i64) -> f32 {
+        let index = match self.knots {
+            0 => index as u64,
+            knots => index.rem_euclid(knots as i64) as u64,
+        };
+        unit_f32(hash(self.seed, index))
+    }
+}
+
+/// Returns the seed of the component at `index` of the plasma created with the given `seed`.
+#[inline]
+pub(crate) fn component_seed(seed: u64, index: usize) -> u64 { hash(seed, index as u64) }
+
+/// A SplitMix64 based hash of a pair of integers.
+#[inline]
+fn hash(seed: u64, n: u64) -> u64 {
+    let mut z = seed.wrapping_add(n.wrapping_add(1).wrapping_mul(0x9e37_79b9_7f4a_7c15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Converts the hash to a float in the range `[0, 1)`.
+#[inline]
+fn unit_f32(hash: u64) -> f32 { (hash >> 40) as f32 / (1u32 << 24) as f32 }
+
+#[inline]
+fn floor(x: f64) -> f64 {
+    let res = x as i64 as f64;
+    if res > x {
+        res - 1.0
+    }
+    else {
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{phase_amp::*, timeline::*, PlasmaRng};
+    use rand::SeedableRng;
+
+    fn assert_smooth(cfg: &PhaseAmpCfg, timeline: &PhaseAmpTimeline, range: core::ops::Range<i32>) {
+        let mut prev = timeline.phase_amp_at(range.start as f64 - 1.0);
+        for i in range {
+            let t = i as f64;
+            let pa = timeline.phase_amp_at(t);
+            assert_eq!(pa, timeline.phase_amp_at(t));
+            assert_eq!(pa.phase(), timeline.phase_at(t));
+            assert!((pa.amplitude() - timeline.amplitude_at(t)).abs() < 1e-6);
+            assert!((0.0..PI2 as f32).contains(&pa.phase()));
+            assert!((0.0..=1.0).contains(&pa.amplitude()));
+            let mut delta = pa.phase() - prev.phase();
+            if delta > 3.0 {
+                delta -= PI2 as f32;
+            }
+            else if delta < -3.0 {
+                delta += PI2 as f32;
+            }
+            assert!(delta.abs() <= cfg.delta_phase_abs_max());
+            assert!((pa.amplitude() - prev.amplitude()).abs() < 2.1 / timeline.amplitude.period as f32);
+            prev = pa;
+        }
+    }
+
+    #[test]
+    fn timeline_works() {
+        let cfg = PhaseAmpCfg::new(80.0, 200.0);
+        let timeline = PhaseAmpTimeline::new(&cfg, 42);
+        assert_eq!(timeline, PhaseAmpTimeline::new(&cfg, 42));
+        assert_ne!(timeline, PhaseAmpTimeline::new(&cfg, 43));
+        assert_smooth(&cfg, &timeline, -999..1000);
+        let mut pa = timeline.phase_amp_at(500.25);
+        let mut rng = PlasmaRng::seed_from_u64(42);
+        pa.update_by(&cfg, 0.5, &mut rng);
+        assert!((pa.amplitude() - timeline.amplitude_at(500.75)).abs() < 1e-5);
+    }
+
+    #[test]
+    fn looped_timeline_works() {
+        let cfg = PhaseAmpCfg::new(80.0, 200.0);
+        for seed in 0..24 {
+            for loop_steps in [50.0f32, 500.0, 1200.0] {
+                let timeline = PhaseAmpTimeline::new_looped(&cfg, seed, loop_steps);
+                assert_smooth(&cfg, &timeline, -(loop_steps as i32)..2 * loop_steps as i32);
+                for i in 0..loop_steps as i32 {
+                    let (t0, t1) = (i as f64, i as f64 + loop_steps as f64);
+                    let (phase0, phase1) = (timeline.phase_at(t0), timeline.phase_at(t1));
+                    let delta = (phase1 - phase0).abs();
+                    assert!(delta < 1e-4 || (delta - PI2 as f32).abs() < 1e-4);
+                    assert!((timeline.amplitude_at(t0) - timeline.amplitude_at(t1)).abs() < 1e-6);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn floor_works() {
+        for x in [-2.0, -1.5, -1.0, -0.5, 0.0, 0.5, 1.0, 1.5, 2.0] {
+            assert_eq!(floor(x), x.floor());
+        }
+    }
+}
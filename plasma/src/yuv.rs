@@ -0,0 +1,367 @@
+//! Rendering into the planar YUV images consumed by the video encoders.
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::cmp::min;
+
+use crate::{error::*, mixer::*, phase_amp::*, plasma::*, simd_polyfill::*};
+
+/// The matrix coefficients of the conversion from RGB to YUV.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum YuvMatrix {
+    /// The ITU-R BT.601 coefficients of the standard definition video.
+    Bt601,
+    /// The ITU-R BT.709 coefficients of the high definition video.
+    #[default]
+    Bt709,
+}
+
+/// The range of the YUV components.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum YuvRange {
+    /// The luma in the range `[16, 235]` and the chroma in the range `[16, 240]` expected by most of the encoders.
+    #[default]
+    Limited,
+    /// The components in the full range `[0, 255]`.
+    Full,
+}
+
+/// The conversion of the rendered pixels to YUV.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct YuvFormat {
+    /// The matrix coefficients.
+    pub matrix: YuvMatrix,
+    /// The range of the components.
+    pub range:  YuvRange,
+}
+
+/// A single plane of the YUV image.
+#[derive(Debug)]
+pub struct Plane<'a> {
+    /// The samples of the plane.
+    pub data:   &'a mut [u8],
+    /// The number of bytes between the subsequent lines of the plane.
+    pub stride: usize,
+}
+
+/// The planes of the YUV image with the chroma subsampled 2 times horizontally and vertically (4:2:0).
+///
+/// For the rendered area of `w` by `h` pixels the luma plane holds `h` lines of `w` samples and the chroma planes
+/// hold `(h + 1) / 2` lines of `(w + 1) / 2` samples each.
+#[derive(Debug)]
+pub enum YuvPlanes<'a> {
+    /// The I420 layout with the separate U (Cb) and V (Cr) planes.
+    I420 {
+        /// The luma plane.
+        y: Plane<'a>,
+        /// The blue-difference chroma plane.
+        u: Plane<'a>,
+        /// The red-difference chroma plane.
+        v: Plane<'a>,
+    },
+    /// The NV12 layout with the single plane of interleaved U (Cb) and V (Cr) samples.
+    Nv12 {
+        /// The luma plane.
+        y:  Plane<'a>,
+        /// The interleaved chroma plane.
+        uv: Plane<'a>,
+    },
+}
+
+/// The precalculated coefficients of the conversion.
+struct YuvConverter {
+    kr:       f32,
+    kg:       f32,
+    kb:       f32,
+    y_scale:  f32,
+    y_offset: f32,
+    c_scale:  f32,
+}
+
+impl YuvMatrix {
+    /// Returns the red and blue luma coefficients.
+    fn coefficients(self) -> (f32, f32) {
+        match self {
+            YuvMatrix::Bt601 => (0.299, 0.114),
+            YuvMatrix::Bt709 => (0.2126, 0.0722),
+        }
+    }
+}
+
+impl YuvPlanes<'_> {
+    /// Checks if the planes can hold the image of `w` by `h` pixels.
+    fn check(&self, w: usize, h: usize) -> Result<(), PlasmaError> {
+        if w == 0 || h == 0 {
+            return Ok(());
+        }
+        let (cw, ch) = (w.div_ceil(2), h.div_ceil(2));
+        match self {
+            YuvPlanes::I420 { y, u, v } => {
+                check_plane(y, w, h)?;
+                check_plane(u, cw, ch)?;
+                check_plane(v, cw, ch)
+            },
+            YuvPlanes::Nv12 { y, uv } => {
+                check_plane(y, w, h)?;
+                check_plane(uv, 2 * cw, ch)
+            },
+        }
+    }
+
+    #[inline]
+    fn luma_line(&mut self, row: usize, len: usize) -> &mut [u8] {
+        let (YuvPlanes::I420 { y, .. } | YuvPlanes::Nv12 { y, .. }) = self;
+        &mut y.data[row * y.stride..][..len]
+    }
+
+    #[inline]
+    fn put_chroma(&mut self, row: usize, col: usize, [cb, cr]: [u8; 2]) {
+        match self {
+            YuvPlanes::I420 { u, v, .. } => {
+                u.data[row * u.stride + col] = cb;
+                v.data[row * v.stride + col] = cr;
+            },
+            YuvPlanes::Nv12 { uv, .. } => {
+                let index = row * uv.stride + 2 * col;
+                uv.data[index..index + 2].copy_from_slice(&[cb, cr]);
+            },
+        }
+    }
+}
+
+impl YuvConverter {
+    fn new(format: YuvFormat) -> Self {
+        let (kr, kb) = format.matrix.coefficients();
+        let (y_scale, y_offset, c_scale) = match format.range {
+            YuvRange::Limited => (219.0, 16.0, 224.0),
+            YuvRange::Full => (255.0, 0.0, 255.0),
+        };
+        YuvConverter { kr, kg: 1.0 - kr - kb, kb, y_scale, y_offset, c_scale }
+    }
+
+    #[inline]
+    fn luma(&self, [r, g, b]: [f32; 3]) -> f32 { self.kr * r + self.kg * g + self.kb * b }
+
+    #[inline]
+    fn y(&self, rgb: [f32; 3]) -> u8 { (self.luma(rgb) * self.y_scale + self.y_offset + 0.5) as u8 }
+
+    #[inline]
+    fn uv(&self, rgb: [f32; 3]) -> [u8; 2] {
+        let luma = self.luma(rgb);
+        let cb = (rgb[2] - luma) / (2.0 - 2.0 * self.kb);
+        let cr = (rgb[0] - luma) / (2.0 - 2.0 * self.kr);
+        // this is saturating conversion
+        [cb, cr].map(|c| (c * self.c_scale + 128.5) as u8)
+    }
+
+    /// Converts the rendered `lines` and puts them into the `planes` at the even `row`.
+    fn put_lines(&self, planes: &mut YuvPlanes, lines: &FloatLines, row: usize) {
+        let (width, height) = (lines.width, lines.height);
+        for line in 0..height {
+            for (col, sample) in planes.luma_line(row + line, width).iter_mut().enumerate() {
+                *sample = self.y(lines.rgb(line, col));
+            }
+        }
+        for line in (0..height).step_by(2) {
+            for col in (0..width).step_by(2) {
+                // the average of the 2x2 block, clipped at the area boundaries
+                let (rows, cols) = (line..min(line + 2, height), col..min(col + 2, width));
+                let count = (rows.len() * cols.len()) as f32;
+                let mut sum = [0.0; 3];
+                for (l, c) in rows.flat_map(|l| cols.clone().map(move |c| (l, c))) {
+                    for (sum, v) in sum.iter_mut().zip(lines.rgb(l, c)) {
+                        *sum += v;
+                    }
+                }
+                planes.put_chroma((row + line) / 2, col / 2, self.uv(sum.map(|v| v / count)));
+            }
+        }
+    }
+}
+
+/// Checks if the `plane` can hold `h` lines of `w` samples.
+fn check_plane(plane: &Plane, w: usize, h: usize) -> Result<(), PlasmaError> {
+    if plane.stride < w {
+        return Err(PlasmaError::PitchTooSmall { required: w, actual: plane.stride });
+    }
+    let required = plane.stride * h;
+    if plane.data.len() < required {
+        return Err(PlasmaError::BufferTooSmall { required, actual: plane.data.len() });
+    }
+    Ok(())
+}
+
+/// Renders the part of the plasma into the provided YUV `planes` without the [Plasma] instance
+/// with the provided `mixer` instance.
+///
+/// The pixels are converted to YUV according to the `format` line by line, so the planes can be passed
+/// directly to the video encoder. The planes correspond to the rendered area, see [YuvPlanes] for the required
+/// sizes. Like with [render_part_with], the rendered area is clipped to the plasma boundaries.
+///
+/// The color components are clamped like with the 8-bit [PixelBuffer](crate::PixelBuffer)s. The chroma is
+/// subsampled from the blocks of 2x2 pixels, so the area rendered in parts must be split at the even pixel
+/// coordinates.
+///
+/// # Panics
+///
+/// __Panics__ if `x` or `y` is odd, if the planes are too small or if [PhaseAmpsSelect::select] panics.
+#[allow(clippy::too_many_arguments)]
+pub fn render_part_yuv<'a, L, M, P>(mixer: &M, planes: &mut YuvPlanes, format: YuvFormat, pw: usize, ph: usize,
+                                    phase_amps: &'a P, x: usize, y: usize, w: usize, h: usize,
+                                    wrkspc: Option<&mut Vec<u8>>)
+    where L: IntermediateCalculatorProducer<'a, P, Flt>,
+          M: Mixer<Flt>,
+          P: PhaseAmpsSelect<'a> + ?Sized
+{
+    if x >= pw || y >= ph {
+        return;
+    }
+    assert!(x.is_multiple_of(2) && y.is_multiple_of(2), "the area offset must be even");
+    let converter = YuvConverter::new(format);
+    render_float_lines::<L, M, P, _>(mixer, pw, ph, phase_amps, x, y, w, h, wrkspc, 2, |lines, row| {
+        converter.put_lines(planes, lines, row)
+    });
+}
+
+/// Renders the part of the plasma into the provided YUV `planes` without the [Plasma] instance
+/// with the provided `mixer` instance.
+///
+/// This is a fallible variant of [render_part_yuv]. Instead of panicking or silently clipping the rendered
+/// part it returns:
+///
+/// * [PlasmaError::AreaOutOfBounds] if the area given by `x`, `y`, `w` and `h` exceeds `pw` or `ph`,
+/// * [PlasmaError::UnalignedArea] if `x` or `y` is odd,
+/// * [PlasmaError::PitchTooSmall] if the stride of any of the planes is too small,
+/// * [PlasmaError::BufferTooSmall] if any of the planes is too small,
+/// * [PlasmaError::PhaseAmpsLength] if the number of `phase_amps` components doesn't match the mixer,
+/// * [PlasmaError::OddPhaseAmpsData] if the `phase_amps` data ends with an incomplete component,
+/// * [PlasmaError::IntermediateLength] if the intermediate calculator producer doesn't match the mixer.
+#[allow(clippy::too_many_arguments)]
+pub fn try_render_part_yuv<'a, L, M, P>(mixer: &M, planes: &mut YuvPlanes, format: YuvFormat, pw: usize, ph: usize,
+                                        phase_amps: &'a P, x: usize, y: usize, w: usize, h: usize,
+                                        wrkspc: Option<&mut Vec<u8>>)
+                                        -> Result<(), PlasmaError>
+    where L: IntermediateCalculatorProducer<'a, P, Flt>,
+          M: Mixer<Flt>,
+          P: PhaseAmpsSelect<'a> + ?Sized
+{
+    check_area(pw, ph, x, y, w, h)?;
+    if !x.is_multiple_of(2) || !y.is_multiple_of(2) {
+        return Err(PlasmaError::UnalignedArea);
+    }
+    check_phase_amps::<L, M, P>(phase_amps)?;
+    planes.check(w, h)?;
+    render_part_yuv::<L, M, P>(mixer, planes, format, pw, ph, phase_amps, x, y, w, h, wrkspc);
+    Ok(())
+}
+
+impl Plasma {
+    /// Renders the part of the plasma into the provided YUV `planes` with the provided `mixer` instance.
+    ///
+    /// See [render_part_yuv].
+    #[allow(clippy::too_many_arguments)]
+    pub fn render_part_yuv<'a, L, M>(&'a self, mixer: &M, planes: &mut YuvPlanes, format: YuvFormat, x: usize,
+                                     y: usize, w: usize, h: usize, wrkspc: Option<&mut Vec<u8>>)
+        where L: ICProducer<'a>,
+              M: Mixer<Flt>
+    {
+        let (pw, ph) = (self.pixel_width as usize, self.pixel_height as usize);
+        render_part_yuv::<L, M, _>(mixer, planes, format, pw, ph, &self.phase_amps[..], x, y, w, h, wrkspc)
+    }
+
+    /// Renders the part of the plasma into the provided YUV `planes` with the provided `mixer` instance.
+    ///
+    /// This is a fallible variant of [Plasma::render_part_yuv], see [try_render_part_yuv] for the list of errors.
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_render_part_yuv<'a, L, M>(&'a self, mixer: &M, planes: &mut YuvPlanes, format: YuvFormat, x: usize,
+                                         y: usize, w: usize, h: usize, wrkspc: Option<&mut Vec<u8>>)
+                                         -> Result<(), PlasmaError>
+        where L: ICProducer<'a>,
+              M: Mixer<Flt>
+    {
+        let (pw, ph) = (self.pixel_width as usize, self.pixel_height as usize);
+        try_render_part_yuv::<L, M, _>(mixer, planes, format, pw, ph, &self.phase_amps[..], x, y, w, h, wrkspc)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{yuv::*, *};
+
+    struct Frame {
+        y:  Vec<u8>,
+        u:  Vec<u8>,
+        v:  Vec<u8>,
+        uv: Vec<u8>,
+    }
+
+    const W: usize = 21;
+    const H: usize = 13;
+    const CW: usize = W.div_ceil(2);
+    const CH: usize = H.div_ceil(2);
+
+    impl Frame {
+        /// The planes hold an extra line, so the tiles can be rendered at the plane offsets.
+        fn new() -> Self {
+            Frame { y:  vec![0; W * (H + 1)],
+                    u:  vec![0; CW * (CH + 1)],
+                    v:  vec![0; CW * (CH + 1)],
+                    uv: vec![0; 2 * CW * (CH + 1)], }
+        }
+
+        /// Renders the area into both the I420 and the NV12 planes.
+        fn render<M: Mixer<Flt>>(&mut self, plasma: &Plasma, mixer: &M, format: YuvFormat,
+                                 (x, y, w, h): (usize, usize, usize, usize)) {
+            let (offset, coffset) = (y * W + x, y / 2 * CW + x / 2);
+            let mut planes = YuvPlanes::I420 { y: Plane { data: &mut self.y[offset..], stride: W },
+                                               u: Plane { data: &mut self.u[coffset..], stride: CW },
+                                               v: Plane { data: &mut self.v[coffset..], stride: CW } };
+            plasma.try_render_part_yuv::<PlasmaICP, _>(mixer, &mut planes, format, x, y, w, h, None).unwrap();
+            let mut luma = self.y.clone();
+            let mut planes = YuvPlanes::Nv12 { y:  Plane { data: &mut luma[offset..], stride: W },
+                                               uv: Plane { data: &mut self.uv[2 * coffset..], stride: 2 * CW } };
+            plasma.try_render_part_yuv::<PlasmaICP, _>(mixer, &mut planes, format, x, y, w, h, None).unwrap();
+            assert_eq!(luma, self.y);
+        }
+    }
+
+    #[test]
+    fn yuv_works() {
+        let (plasma, _) = Plasma::new_seeded(W as u32, H as u32, PhaseAmpCfg::default(), 6);
+        let format = YuvFormat::default();
+        let mut frame = Frame::new();
+        frame.render(&plasma, &PlasmaMixer, format, (0, 0, W, H));
+        let interleaved: Vec<u8> = frame.u.iter().zip(frame.v.iter()).flat_map(|(&u, &v)| [u, v]).collect();
+        assert_eq!(frame.uv, interleaved);
+        let mut tiled = Frame::new();
+        for area in [(0, 0, 8, 6), (8, 0, 13, 6), (0, 6, 10, 7), (10, 6, 11, 7)] {
+            tiled.render(&plasma, &PlasmaMixer, format, area);
+        }
+        assert_eq!((tiled.y, tiled.u, tiled.v, tiled.uv), (frame.y, frame.u, frame.v, frame.uv));
+
+        let gray = AdjustedMixer::new(PlasmaMixer, ColorAdjust { contrast: 0.0, ..ColorAdjust::IDENTITY });
+        let mut frame = Frame::new();
+        frame.render(&plasma, &gray, format, (0, 0, W, H));
+        assert!(frame.y[..W * H].iter().all(|&y| y == 126));
+        assert!(frame.u[..CW * CH].iter().chain(frame.v[..CW * CH].iter()).all(|&c| c == 128));
+
+        let mut planes = YuvPlanes::I420 { y: Plane { data: &mut frame.y, stride: W },
+                                           u: Plane { data: &mut frame.u, stride: CW },
+                                           v: Plane { data: &mut frame.v, stride: CW - 1 } };
+        let result = plasma.try_render_part_yuv::<PlasmaICP, _>(&PlasmaMixer, &mut planes, format, 0, 0, W, H, None);
+        assert_eq!(result, Err(PlasmaError::PitchTooSmall { required: CW, actual: CW - 1 }));
+        let result = plasma.try_render_part_yuv::<PlasmaICP, _>(&PlasmaMixer, &mut planes, format, 1, 0, 2, 2, None);
+        assert_eq!(result, Err(PlasmaError::UnalignedArea));
+    }
+
+    #[test]
+    fn yuv_matrix_works() {
+        let red = [1.0, 0.0, 0.0];
+        let converter = YuvConverter::new(YuvFormat { matrix: YuvMatrix::Bt709, range: YuvRange::Limited });
+        assert_eq!((converter.y(red), converter.uv(red)), (63, [102, 240]));
+        let converter = YuvConverter::new(YuvFormat { matrix: YuvMatrix::Bt601, range: YuvRange::Limited });
+        assert_eq!((converter.y(red), converter.uv(red)), (81, [90, 240]));
+        let converter = YuvConverter::new(YuvFormat { matrix: YuvMatrix::Bt601, range: YuvRange::Full });
+        assert_eq!((converter.y([1.0; 3]), converter.uv([1.0; 3])), (255, [128, 128]));
+        assert_eq!((converter.y([0.0; 3]), converter.uv([0.0, 0.0, 1.0])), (0, [255, 107]));
+    }
+}
@@ -1,6 +1,6 @@
 #![allow(unused_imports)]
 use plasma::*;
-use rand::rngs::OsRng;
+use rand::{rngs::OsRng, Rng};
 use wasm_bindgen::prelude::*;
 // use wasm_bindgen::closure::Closure;
 use wasm_bindgen::{Clamped, JsCast};
@@ -8,9 +8,8 @@ use wasm_bindgen::{Clamped, JsCast};
 use web_sys::{ImageData, Window, WorkerGlobalScope};
 // use js_sys::JsCast;
 
-type PlasmaMixerT = PlasmaMixer;
-type PlasmaICPT<'a> = PlasmaICP<'a>;
-type PlasmaICPTExtPa<'a> = PlasmaICPExtPa<'a>;
+/// The format of the image data.
+const PIXEL_FORMAT: PixelFormat = PixelFormat::Rgba32;
 
 #[wasm_bindgen]
 extern "C" {
@@ -29,88 +28,171 @@ struct Area {
 #[wasm_bindgen]
 pub struct PlasmaHandle {
     plasma: Plasma,
-    rng:    OsRng,
+    rng:    PlasmaRng,
+    seed:   u64,
     data:   Vec<u8>,
     area:   Area,
     wrkspc: Vec<u8>,
-    // mixer:  PlasmaMixerT,
+    mixer:  MixerKind,
+    #[cfg(feature = "expr")]
+    expr:   ExprMixer,
+    adjust: ColorAdjust,
 }
 
 #[wasm_bindgen]
 impl PlasmaHandle {
     #[wasm_bindgen(constructor)]
-    pub fn constructor(width: u32, height: u32, min_steps: u32, max_steps: u32) -> Result<PlasmaHandle, JsValue> {
-        if min_steps <= 1 {
-            return Err(js_sys::Error::new("steps should be larger than 1").into());
+    pub fn constructor(width: u32, height: u32, min_steps: u32, max_steps: u32, seed: Option<u64>,
+                       loop_steps: Option<f32>)
+                       -> Result<PlasmaHandle, JsValue> {
+        let seed = seed.unwrap_or_else(|| OsRng.gen());
+        let cfg = PhaseAmpCfg::try_new(min_steps as f32, max_steps as f32).map_err(js_error)?;
+        let (plasma, mut rng) = Plasma::new_seeded(width, height, cfg, seed);
+        let mut plasma = match loop_steps {
+            Some(steps) => Plasma::try_new_looped(width, height, cfg, seed, steps).map_err(js_error)?,
+            None => plasma,
+        };
+        let mixer = MixerKind::default();
+        plasma.set_phase_amps_len(mixer.phase_amps_len(), &mut rng);
+        if plasma.timeline_seed().is_some() {
+            plasma.at_time(0.0);
         }
-        if max_steps <= min_steps {
-            return Err(js_sys::Error::new("max steps should be larger than min steps").into());
-        }
-        let mut rng = OsRng;
-        let cfg = PhaseAmpCfg::new(min_steps as f32, max_steps as f32);
-        let plasma = Plasma::new(width, height, cfg, &mut rng);
-        let data = vec![0; width as usize * height as usize * PixelBufRGBA32::PIXEL_BYTES];
+        let data = vec![0; width as usize * height as usize * PIXEL_FORMAT.pixel_bytes()];
         let wrkspc = Vec::new();
-        // let mixer = PlasmaMixerT::new();
         Ok(PlasmaHandle { plasma,
                           rng,
+                          seed,
                           data,
                           area: Area { x: 0, y: 0, w: width as usize, h: height as usize },
                           wrkspc,
-                          // mixer
-        })
+                          mixer,
+                          #[cfg(feature = "expr")]
+                          expr: ExprMixer::default(),
+                          adjust: ColorAdjust::IDENTITY })
     }
 
     #[wasm_bindgen(js_name=setArea)]
-    pub fn set_area(&mut self, x: usize, y: usize, w: usize, h: usize) {
-        if x + w > self.plasma.pixel_width as usize || y + h > self.plasma.pixel_height as usize {
-            panic!("invalid area provided");
-        }
+    pub fn set_area(&mut self, x: usize, y: usize, w: usize, h: usize) -> Result<(), JsValue> {
+        self.plasma.check_area(x, y, w, h).map_err(js_error)?;
         self.area = Area { x, y, w, h };
-        self.data.resize(w * h * PixelBufRGBA32::PIXEL_BYTES, 0u8);
+        self.data.resize(w * h * PIXEL_FORMAT.pixel_bytes(), 0u8);
+        Ok(())
     }
 
     pub fn width(&self) -> u32 { self.plasma.pixel_width }
 
     pub fn height(&self) -> u32 { self.plasma.pixel_height }
 
+    pub fn seed(&self) -> u64 { self.seed }
+
+    #[wasm_bindgen(js_name=loopSteps)]
+    pub fn loop_steps(&self) -> Option<f32> { self.plasma.loop_steps() }
+
     pub fn render(&mut self) {
         let Area { x, y, w, h } = self.area;
-        let pitch: usize = PixelBufRGBA32::PIXEL_BYTES * w;
-        self.plasma.render_part::<PixelBufRGBA32, PlasmaICPT, PlasmaMixerT>(
-                                                                &mut self.data,
-                                                                pitch,
-                                                                x,
-                                                                y,
-                                                                w,
-                                                                h,
-                                                                Some(&mut self.wrkspc));
+        let pitch: usize = PIXEL_FORMAT.pixel_bytes() * w;
+        let (data, wrkspc) = (&mut self.data, Some(&mut self.wrkspc));
+        #[cfg(feature = "expr")]
+        if self.mixer == MixerKind::Expr {
+            self.plasma.render_part_dispatch_expr(&self.expr, PIXEL_FORMAT, &self.adjust, data, pitch, x, y, w, h,
+                                                  wrkspc);
+            return;
+        }
+        self.plasma.render_part_dispatch_adjusted(self.mixer, PIXEL_FORMAT, &self.adjust, data, pitch, x, y, w, h,
+                                                  wrkspc);
     }
 
+    pub fn mixer(&self) -> String { self.mixer.to_string() }
+
+    #[wasm_bindgen(js_name=setMixer)]
+    pub fn set_mixer(&mut self, name: &str) -> Result<(), JsValue> {
+        let mixer = name.parse().map_err(js_error)?;
+        self.select_mixer(mixer);
+        Ok(())
+    }
+
+    #[wasm_bindgen(js_name=phaseAmpsLen)]
+    pub fn phase_amps_len(&self) -> usize { self.plasma.phase_amps_len() }
+
     #[wasm_bindgen(js_name=renderPhaseAmps)]
-    pub fn render_phase_amps(&mut self, phase_amps: &[f32]) {
+    pub fn render_phase_amps(&mut self, phase_amps: &[f32]) -> Result<(), JsValue> {
         let Area { x, y, w, h } = self.area;
-        let pitch: usize = PixelBufRGBA32::PIXEL_BYTES * w;
+        let pitch: usize = PIXEL_FORMAT.pixel_bytes() * w;
         let pw = self.plasma.pixel_width as usize;
         let ph = self.plasma.pixel_height as usize;
-        render_part::<PixelBufRGBA32, PlasmaICPTExtPa, PlasmaMixerT, _>(
-                                                            &mut self.data,
-                                                            pitch,
-                                                            pw,
-                                                            ph,
-                                                            phase_amps,
-                                                            x,
-                                                            y,
-                                                            w,
-                                                            h,
-                                                            Some(&mut self.wrkspc))
+        let (data, wrkspc) = (&mut self.data, Some(&mut self.wrkspc));
+        #[cfg(feature = "expr")]
+        if self.mixer == MixerKind::Expr {
+            return try_render_part_dispatch_expr(&self.expr, PIXEL_FORMAT, &self.adjust, data, pitch, pw, ph,
+                                                 phase_amps, x, y, w, h, wrkspc).map_err(js_error);
+        }
+        try_render_part_dispatch_adjusted(self.mixer, PIXEL_FORMAT, &self.adjust, data, pitch, pw, ph, phase_amps, x,
+                                          y, w, h, wrkspc).map_err(js_error)
+    }
+
+    pub fn brightness(&self) -> f32 { self.adjust.brightness }
+
+    #[wasm_bindgen(js_name=setBrightness)]
+    pub fn set_brightness(&mut self, brightness: f32) { self.adjust.brightness = brightness; }
+
+    pub fn contrast(&self) -> f32 { self.adjust.contrast }
+
+    #[wasm_bindgen(js_name=setContrast)]
+    pub fn set_contrast(&mut self, contrast: f32) { self.adjust.contrast = contrast; }
+
+    pub fn saturation(&self) -> f32 { self.adjust.saturation }
+
+    #[wasm_bindgen(js_name=setSaturation)]
+    pub fn set_saturation(&mut self, saturation: f32) { self.adjust.saturation = saturation; }
+
+    /// The hue rotation in half turns.
+    pub fn hue(&self) -> f32 { self.adjust.hue }
+
+    #[wasm_bindgen(js_name=setHue)]
+    pub fn set_hue(&mut self, hue: f32) { self.adjust.hue = hue; }
+
+    pub fn temperature(&self) -> f32 { self.adjust.temperature }
+
+    #[wasm_bindgen(js_name=setTemperature)]
+    pub fn set_temperature(&mut self, temperature: f32) { self.adjust.temperature = temperature; }
+
+    /// Exports the brightness, contrast, saturation, hue and temperature.
+    #[wasm_bindgen(js_name=exportColorAdjust)]
+    pub fn export_color_adjust(&self) -> Box<[f32]> {
+        let ColorAdjust { brightness, contrast, saturation, hue, temperature } = self.adjust;
+        Box::new([brightness, contrast, saturation, hue, temperature])
     }
 
-    pub fn update(&mut self) { self.plasma.update(&mut self.rng); }
+    #[wasm_bindgen(js_name=importColorAdjust)]
+    pub fn import_color_adjust(&mut self, adjust: &[f32]) -> Result<(), JsValue> {
+        match *adjust {
+            [brightness, contrast, saturation, hue, temperature] => {
+                self.adjust = ColorAdjust { brightness, contrast, saturation, hue, temperature };
+                Ok(())
+            },
+            _ => Err(js_sys::Error::new("invalid number of color adjustments").into()),
+        }
+    }
+
+    pub fn update(&mut self, steps: Option<f32>) {
+        let steps = steps.unwrap_or(1.0);
+        match self.plasma.loop_steps() {
+            Some(loop_steps) => {
+                let time = (self.plasma.time() + steps as f64) % loop_steps as f64;
+                self.plasma.at_time(time);
+            },
+            None => self.plasma.update_by(steps, &mut self.rng),
+        }
+    }
+
+    #[wasm_bindgen(js_name=atLoopFrame)]
+    pub fn at_loop_frame(&mut self, index: u32, frames: u32) -> Result<(), JsValue> {
+        self.plasma.try_at_loop_frame(index, frames).map_err(js_error)
+    }
 
     #[wasm_bindgen(js_name=imageData)]
     pub fn image_data(&mut self) -> Result<ImageData, JsValue> {
-        ImageData::new_with_u8_clamped_array(Clamped(&mut self.data), self.area.w as u32)
+        ImageData::new_with_u8_clamped_array(Clamped(&self.data), self.area.w as u32)
     }
 
     #[wasm_bindgen(js_name=createImageBitmap)]
@@ -128,7 +210,31 @@ impl PlasmaHandle {
     }
 
     #[wasm_bindgen(js_name=importPhaseAmps)]
-    pub fn import_phase_amps(&mut self, phase_amps: &[f32]) { self.plasma.import_phase_amps(phase_amps); }
+    pub fn import_phase_amps(&mut self, phase_amps: &[f32]) -> Result<(), JsValue> {
+        self.plasma.try_import_phase_amps(phase_amps).map_err(js_error)
+    }
+
+    #[wasm_bindgen(js_name=exportState)]
+    pub fn export_state(&self) -> Box<[f32]> {
+        let mut out = Vec::new();
+        self.plasma.export_state(&mut out);
+        out.into_boxed_slice()
+    }
+
+    #[wasm_bindgen(js_name=importState)]
+    pub fn import_state(&mut self, state: &[f32]) -> Result<(), JsValue> {
+        let plasma = Plasma::from_state(state).ok_or_else(|| js_sys::Error::new("invalid plasma state"))?;
+        if plasma.phase_amps_len() != self.mixer.phase_amps_len() {
+            return Err(js_sys::Error::new("invalid number of phase amps").into());
+        }
+        let (width, height) = (plasma.pixel_width as usize, plasma.pixel_height as usize);
+        if width != self.plasma.pixel_width as usize || height != self.plasma.pixel_height as usize {
+            self.area = Area { x: 0, y: 0, w: width, h: height };
+            self.data.resize(width * height * PIXEL_FORMAT.pixel_bytes(), 0u8);
+        }
+        self.plasma = plasma;
+        Ok(())
+    }
 
     #[wasm_bindgen(js_name=minSteps)]
     pub fn min_steps(&self) -> f32 { self.plasma.min_steps() }
@@ -137,6 +243,39 @@ impl PlasmaHandle {
     pub fn max_steps(&self) -> f32 { self.plasma.max_steps() }
 }
 
+#[cfg(feature = "expr")]
+#[wasm_bindgen]
+impl PlasmaHandle {
+    /// Parses the expression and selects the "expr" mixer rendering it.
+    #[wasm_bindgen(js_name=setExpr)]
+    pub fn set_expr(&mut self, expr: &str) -> Result<(), JsValue> {
+        let mut expr: ExprMixer = expr.parse().map_err(js_error)?;
+        expr.set_time(self.expr.time());
+        self.expr = expr;
+        self.select_mixer(MixerKind::Expr);
+        Ok(())
+    }
+
+    /// The value of the time variable `t` of the expression.
+    #[wasm_bindgen(js_name=exprTime)]
+    pub fn expr_time(&self) -> f32 { self.expr.time() }
+
+    #[wasm_bindgen(js_name=setExprTime)]
+    pub fn set_expr_time(&mut self, time: f32) { self.expr.set_time(time); }
+}
+
+impl PlasmaHandle {
+    fn select_mixer(&mut self, mixer: MixerKind) {
+        self.mixer = mixer;
+        self.plasma.set_phase_amps_len(mixer.phase_amps_len(), &mut self.rng);
+        if self.plasma.timeline_seed().is_some() {
+            self.plasma.at_time(self.plasma.time());
+        }
+    }
+}
+
+fn js_error(err: PlasmaError) -> JsValue { js_sys::Error::new(&err.to_string()).into() }
+
 enum GlobalProxy {
     Window(Window),
     WorkerGlobalScope(WorkerGlobalScope),
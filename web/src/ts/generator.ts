@@ -18,6 +18,33 @@
      *  The larger the number the slower plasma animates.
      */
     maxSteps?: number
+    /**
+     *  The animation seed, an unsigned 64-bit integer. The same seed always produces the same animation.
+     *
+     *  Use a `bigint` for the seeds above `Number.MAX_SAFE_INTEGER`. If not provided a random seed is chosen.
+     */
+    seed?: bigint | number,
+    /**
+     *  The length of a seamlessly looping animation in animation steps (60 steps per second).
+     *
+     *  If not provided the animation never repeats.
+     */
+    loopSteps?: number,
+    /**
+     *  The name of the plasma mixer, e.g. "plasma", "monochrome" or "lava-lamp".
+     *
+     *  If not provided the default "plasma" mixer is used.
+     */
+    mixer?: string,
+    /**
+     *  The expression of the "expr" mixer, e.g. `"hsv(x0 * y1 + t, 1, abs(x2 + y2) * 0.5 + 0.5)"`.
+     *
+     *  The variables `x0` to `x5` and `y0` to `y5` are the plasma waves in the range `[-1, 1]`
+     *  and `t` is the number of seconds of the animation. If provided the `mixer` option is ignored.
+     */
+    expr?: string,
+    /** The initial color adjustments of the rendered plasma. */
+    colorAdjust?: ColorAdjust,
     /** Width in pixels of the generated plasma. */
     width?: number,
     /** Height in pixels of the generated plasma. */
@@ -30,6 +57,20 @@
     workers?: number,
 }
 
+/** The color adjustments of the rendered plasma, the missing properties are left unchanged. */
+export interface ColorAdjust {
+    /** The value added to each color component, `0` leaves the brightness unchanged. */
+    brightness?: number,
+    /** The contrast factor around the middle gray, `1` leaves the contrast unchanged. */
+    contrast?: number,
+    /** The saturation factor, `0` yields the grayscale and `1` leaves the saturation unchanged. */
+    saturation?: number,
+    /** The hue rotation in half turns, so the range `[0, 2)` makes a full circle. */
+    hue?: number,
+    /** The color temperature in the range: `[-1, 1]`, the positive values make the colors warmer. */
+    temperature?: number,
+}
+
 /** Received in a `detail` property of a "bitmap" CustomEvent. */
 export interface BitmapDetail {
     /** Left pixel offset of this fragment in the original bitmap. */
@@ -54,11 +95,19 @@ export const DefaultPlasmaGeneratorOptions: PlasmaGeneratorOptions = {
     workers: Math.min(2, Math.max(1, (self.navigator.hardwareConcurrency|0)))
 }
 
+/** The number of plasma animation steps per second. */
+const STEPS_PER_SECOND = 60;
+/** The maximum number of animation steps between two consecutive frames. */
+const MAX_STEPS_PER_UPDATE = 4;
+
 interface WorkerPlasmaSetup {
     width: number,
     height: number,
     minSteps: number,
     maxSteps: number,
+    mixer: string,
+    expr?: string,
+    colorAdjust: Float32Array,
     x: number,
     y: number,
     w: number,
@@ -103,6 +152,8 @@ export class PlasmaGenerator extends EventTarget implements BitmapGenerator {
     protected workers: Worker[];
     protected queued: number;
     protected imageData: ImageData;
+    protected lastUpdate: number;
+    protected expr: boolean;
     /**
      * Creates a new generator instance.
      *
@@ -111,10 +162,17 @@ export class PlasmaGenerator extends EventTarget implements BitmapGenerator {
     constructor(options?: PlasmaGeneratorOptions) {
         const opts: PlasmaGeneratorOptions = Object.assign({}, DefaultPlasmaGeneratorOptions, options);
         super();
-        const plasma = this.handle = new PlasmaHandle(opts.width, opts.height, opts.minSteps, opts.maxSteps);
+        const plasma = this.handle = new PlasmaHandle(opts.width, opts.height, opts.minSteps, opts.maxSteps,
+                                                      opts.seed == null ? undefined : BigInt(opts.seed),
+                                                      opts.loopSteps);
+        if (opts.mixer) plasma.setMixer(opts.mixer);
+        this.expr = opts.expr != null;
+        if (this.expr) plasma.setExpr(opts.expr);
+        if (opts.colorAdjust) this.applyColorAdjust(opts.colorAdjust);
         this.workers = [];
         this.queued = -1;
         this.imageData = null;
+        this.lastUpdate = performance.now();
 
         if (opts.parallel) {
             const [width, height] = [plasma.width(), plasma.height()];
@@ -128,6 +186,9 @@ export class PlasmaGenerator extends EventTarget implements BitmapGenerator {
                     height,
                     minSteps: plasma.minSteps(),
                     maxSteps: plasma.maxSteps(),
+                    mixer: plasma.mixer(),
+                    expr: opts.expr,
+                    colorAdjust: plasma.exportColorAdjust(),
                     x, y,
                     w: width,
                     h: Math.min(segmentHeight, height - y)
@@ -135,6 +196,7 @@ export class PlasmaGenerator extends EventTarget implements BitmapGenerator {
                 worker.onmessage = (e) => {
                     const data = e.data;
                     if (data === 'ready') {
+                        setup.colorAdjust = plasma.exportColorAdjust();
                         worker.postMessage(setup);
                         if (this.workers.push(worker) == workers) {
                             this.queued = 0;
@@ -145,7 +207,7 @@ export class PlasmaGenerator extends EventTarget implements BitmapGenerator {
                         let detail: BitmapDetail = {x, y, width, height, bitmap: data};
                         this.dispatchEvent(new CustomEvent('bitmap', { detail }));
                         if (--this.queued === 0) {
-                            plasma.update();
+                            this.update();
                             this.dispatchEvent(new Event('ready'));
                         }
                     }
@@ -178,7 +240,9 @@ export class PlasmaGenerator extends EventTarget implements BitmapGenerator {
         if (count > 0) {
             this.queued = count;
             let { buffer } = plasma.exportPhaseAmps();
+            const exprTime = plasma.exprTime();
             workers.forEach((worker, index) => {
+                if (this.expr) worker.postMessage({ exprTime });
                 let buf = (index + 1 < count) ? buffer.slice(0) : buffer;
                 worker.postMessage(buf, [buf]);
             });
@@ -196,7 +260,7 @@ export class PlasmaGenerator extends EventTarget implements BitmapGenerator {
                 let { width, height } = bitmap;
                 let detail: BitmapDetail = {x: 0, y: 0, width, height, bitmap};
                 this.dispatchEvent(new CustomEvent('bitmap', { detail }));
-                plasma.update();
+                this.update();
                 this.queued = 0;
                 this.dispatchEvent(new Event('ready'));
             }, error => {
@@ -205,4 +269,33 @@ export class PlasmaGenerator extends EventTarget implements BitmapGenerator {
         }
         return true;
     }
+
+    /**
+     *  Changes the color adjustments of the rendered plasma.
+     *
+     *  Call it between the frames with the interpolated values to animate the adjustments.
+     */
+    setColorAdjust(adjust: ColorAdjust): void {
+        this.applyColorAdjust(adjust);
+        const colorAdjust = this.handle.exportColorAdjust();
+        this.workers.forEach(worker => worker.postMessage({ colorAdjust }));
+    }
+
+    protected applyColorAdjust(adjust: ColorAdjust): void {
+        const plasma = this.handle;
+        if (adjust.brightness != null) plasma.setBrightness(adjust.brightness);
+        if (adjust.contrast != null) plasma.setContrast(adjust.contrast);
+        if (adjust.saturation != null) plasma.setSaturation(adjust.saturation);
+        if (adjust.hue != null) plasma.setHue(adjust.hue);
+        if (adjust.temperature != null) plasma.setTemperature(adjust.temperature);
+    }
+
+    /** Animates the plasma proportionally to the time elapsed since the previous update. */
+    protected update(): void {
+        const now = performance.now();
+        const steps = Math.min(MAX_STEPS_PER_UPDATE, (now - this.lastUpdate) * STEPS_PER_SECOND / 1000);
+        this.lastUpdate = now;
+        this.handle.update(steps);
+        if (this.expr) this.handle.setExprTime(this.handle.exprTime() + steps / STEPS_PER_SECOND);
+    }
 }
@@ -3,6 +3,11 @@ export default import("./plasma").then(plasma => {
   Array.from(document.querySelectorAll("plasma.autostart")).forEach((element: HTMLElement) => {
      let minSteps: number = parseInt(element.dataset.minSteps) || plasma.DefaultPlasmaGeneratorOptions.minSteps;
      let maxSteps: number = parseInt(element.dataset.maxSteps) || plasma.DefaultPlasmaGeneratorOptions.maxSteps;
+     let seed: bigint;
+     try { seed = element.dataset.seed ? BigInt(element.dataset.seed) : undefined; } catch (e) { seed = undefined; }
+     let loopSteps: number = parseFloat(element.dataset.loopSteps) || undefined;
+     let mixer: string = element.dataset.mixer || undefined;
+     let expr: string = element.dataset.expr || undefined;
      let width: number = parseInt(element.dataset.plasmaWidth) || plasma.DefaultPlasmaGeneratorOptions.width;
      let height: number = parseInt(element.dataset.plasmaHeight) || plasma.DefaultPlasmaGeneratorOptions.height;
      let targetWidth: number = parseInt(element.dataset.targetWidth) || plasma.DefaultCanvasRendererOptions.width;
@@ -12,7 +17,7 @@ export default import("./plasma").then(plasma => {
      if (targetHeight < height) targetHeight = height;
      let canvas = document.createElement("canvas");
      element.appendChild(canvas);
-     let generator = new plasma.PlasmaGenerator({ width, height, minSteps, maxSteps });
+     let generator = new plasma.PlasmaGenerator({ width, height, minSteps, maxSteps, seed, loopSteps, mixer, expr });
      let renderer = new plasma.CanvasRenderer(canvas, { width: targetWidth, height: targetHeight });
      resize();
      renderer.attach(generator);
@@ -26,9 +26,18 @@ onmessage = (event) => {
             console.error(err);
         });
     }
+    else if (data.exprTime !== undefined) {
+        plasma.setExprTime(data.exprTime);
+    }
+    else if (data.width === undefined) {
+        plasma.importColorAdjust(data.colorAdjust);
+    }
     else {
-        let { width, height, minSteps, maxSteps, x, y, w, h } = data;
+        let { width, height, minSteps, maxSteps, mixer, expr, colorAdjust, x, y, w, h } = data;
         plasma = new PlasmaHandle(width, height, minSteps, maxSteps);
+        plasma.setMixer(mixer);
+        if (expr != null) plasma.setExpr(expr);
+        plasma.importColorAdjust(colorAdjust);
         plasma.setArea(x, y, w, h);
         imageData = plasma.imageData();
     }